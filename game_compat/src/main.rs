@@ -2,12 +2,14 @@ use std::{
     fs::{self, OpenOptions},
     io::{Read, Seek},
     path::Path,
-    time::{Duration, SystemTime},
+    time::Duration,
 };
 
-use hex_renderer::{grids::GridDraw, pattern_utils};
-use pest::{iterators::Pair, Parser};
-use pest_derive::Parser;
+use hex_renderer::{
+    grids::GridDraw,
+    parse::{self, Iota, ParsedIota},
+    Pattern,
+};
 
 const FILE_LOC: &str =
     "/home/johng/.local/share/PrismLauncher/instances/HexxyCraft/.minecraft/logs/latest.log";
@@ -46,104 +48,39 @@ fn main() {
     }
 }
 
-#[derive(Parser)]
-#[grammar_inline = r#"
-
-everything = { SOI ~ (chat_msg | ANY)+ ~ EOI}
-
-chat_msg = {time ~ render_type ~ ":" ~ chat_subtype ~ iota~ NEWLINE*}
-
-time = { "[" ~ (ASCII_DIGIT* ~ ":"*){3} ~ "]"}
-
-render_type = { "[Render thread/INFO]" }
-
-chat_subtype = { "[CHAT]" }
-
-till_end = { (!NEWLINE ~ ANY)+}
-
-WHITESPACE = _{" "}
-
-
-iota = _{ pattern | list | string | number | vector}
-
-pattern = { "<" ~ direction ~ "," ~ pattern_segs? ~ ">" }
-
-direction = { "southeast" | "east" | "northeast" | "southwest" | "west" | "northwest" }
-
-pattern_segs = { segments+ }
-
-segments = _{ "w" | "e" | "d" | "s" | "a" | "q" }
-
-list = { "[" ~ (iota ~ ",")* ~ iota* ~ "]" }
-
-string = {"\"" ~ (!"\"" ~ ANY)* ~ "\"" }
-
-number = { ASCII_DIGIT+ ~ ("." ~ ASCII_DIGIT+)? }
-
-vector = { "(" ~ (number ~ ","){2} ~ number ~ ")" }
-
-"#]
-struct PatternParser;
+const CHAT_PREFIX: &str = "[Render thread/INFO]: [CHAT] ";
 
 pub fn parse_str(patterns_str: &str) {
-    let parsed_iotas = PatternParser::parse(Rule::everything, patterns_str)
-        .unwrap()
-        .next()
-        .unwrap()
-        .into_inner();
-
-    for iota in parsed_iotas {
-        if iota.as_rule() != Rule::chat_msg {
+    for line in patterns_str.lines() {
+        let Some((_, message)) = line.split_once(CHAT_PREFIX) else {
             continue;
-        }
-        let iota = iota.into_inner().skip(3).next().unwrap();
-
-        let iota = parse_iota(iota).unwrap();
+        };
+        let Ok(iotas) = parse::parse_iotas(message) else {
+            continue;
+        };
 
-        //println!("{:?}", iota);
+        //println!("{:?}", iotas);
 
-        if let Iota::List(list) = iota {
-            check_command(list);
+        if let [ParsedIota {
+            iota: Iota::List(list),
+            ..
+        }] = &iotas[..]
+        {
+            check_command(strip_spans(list.clone()));
         }
     }
 }
 
-fn parse_iota(iota: Pair<'_, Rule>) -> Option<Iota> {
-    Some(match iota.as_rule() {
-        Rule::pattern => parse_pattern(iota)?,
-        Rule::list => parse_list(iota)?,
-        Rule::string | Rule::number | Rule::vector => Iota::Unknown,
-        _ => return None,
-    })
-}
-fn parse_pattern(iota: Pair<'_, Rule>) -> Option<Iota> {
-    let mut iota = iota.into_inner();
-    //println!("{iota}");
-    let direction = iota.next()?.as_str().to_string();
-
-    Some(Iota::Pattern(Pattern {
-        direction,
-        segments: if let Some(segments) = iota.next() {
-            segments.as_str().to_string()
-        } else {
-            String::new()
-        },
-    }))
-}
-fn parse_list(iota: Pair<'_, Rule>) -> Option<Iota> {
-    Some(Iota::List(
-        iota.into_inner()
-            .map(|iota| parse_iota(iota))
-            .collect::<Option<Vec<Iota>>>()?,
-    ))
+fn strip_spans(list: Vec<ParsedIota>) -> Vec<Iota> {
+    list.into_iter().map(|iota| iota.iota).collect()
 }
 
 fn check_command(list: Vec<Iota>) {
-    if list.len() < 1 {
+    if list.is_empty() {
         return;
     }
     if let Iota::Pattern(pattern) = &list[0] {
-        if pattern.segments == "wwqaqdada" {
+        if pattern.get_angle_sig() == "wwqaqdada" {
             initialize_dictionary(list);
         }
     }
@@ -156,7 +93,7 @@ fn initialize_dictionary(list: Vec<Iota>) {
     let dictionary = {
         let [_, iota]: [Iota; 2] = list.try_into().unwrap();
         if let Iota::List(dictionary) = iota {
-            dictionary
+            strip_spans(dictionary)
         } else {
             return;
         }
@@ -169,7 +106,7 @@ fn initialize_dictionary(list: Vec<Iota>) {
         let [keys, values]: [Iota; 2] = dictionary.try_into().unwrap();
         if let Iota::List(keys) = keys {
             if let Iota::List(values) = values {
-                (keys, values)
+                (strip_spans(keys), strip_spans(values))
             } else {
                 return;
             }
@@ -197,7 +134,7 @@ fn initialize_dictionary(list: Vec<Iota>) {
             .into_iter()
             .map(|iota| {
                 if let Iota::List(iota) = iota {
-                    Some(iota)
+                    Some(strip_spans(iota))
                 } else {
                     None
                 }
@@ -214,24 +151,14 @@ fn initialize_dictionary(list: Vec<Iota>) {
         }
     };
 
-    for (key, patterns) in keys.into_iter().zip(values.into_iter()) {
-        let name = format!("{}.png", key.segments);
+    for (key, patterns) in keys.into_iter().zip(values) {
+        let name = format!("{}.png", key.get_angle_sig());
 
-        let patterns: Vec<hex_renderer::Pattern> = patterns
-            .iter()
+        let patterns: Vec<Pattern> = patterns
+            .into_iter()
             .filter_map(|iota| match iota {
-                Iota::Pattern(pattern) => Some({
-                    let direction =
-                        pattern_utils::Direction::try_from(&pattern.direction[..]).unwrap();
-                    let angles = pattern
-                        .segments
-                        .chars()
-                        .map(|ch| pattern_utils::Angle::try_from(ch).unwrap())
-                        .collect();
-                    hex_renderer::Pattern::new(direction, angles)
-                }),
-                Iota::List(_) => None,
-                Iota::Unknown => None,
+                Iota::Pattern(pattern) => Some(pattern),
+                _ => None,
             })
             .collect();
 
@@ -242,15 +169,3 @@ fn initialize_dictionary(list: Vec<Iota>) {
             .unwrap();
     }
 }
-
-#[derive(Debug)]
-enum Iota {
-    Pattern(Pattern),
-    List(Vec<Iota>),
-    Unknown,
-}
-#[derive(Debug)]
-struct Pattern {
-    pub direction: String,
-    pub segments: String,
-}
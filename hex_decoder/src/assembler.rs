@@ -0,0 +1,304 @@
+use hex_renderer::{
+    parse::{self, Iota},
+    Pattern,
+};
+
+use crate::{
+    encode_bookkeeper, encode_number, BookkeeperEncodeError, NumberEncodeError, PatternRegistry,
+    DROP, KEEP,
+};
+
+const NUMBER_PREFIX: &str = "Numerical Reflection:";
+const MASK_PREFIX: &str = "Bookkeeper's Gambit:";
+const CONSIDERATION_PREFIX: &str = "Consideration:";
+
+//line numbers start at 1
+#[derive(Debug, Clone)]
+pub enum AssembleError {
+    UnknownName {
+        line: usize,
+        name: String,
+    },
+    InvalidNumber {
+        line: usize,
+        error: NumberEncodeError,
+    },
+    InvalidMask {
+        line: usize,
+        error: BookkeeperEncodeError,
+    },
+    InvalidLiteral {
+        line: usize,
+        literal: String,
+    },
+    UnmatchedRetrospection {
+        line: usize,
+    },
+    UnclosedIntrospection {
+        line: usize,
+    },
+    NotAPattern {
+        line: usize,
+    },
+}
+
+//reads the format the disassembler writes (see disassemble) one line at a time
+//  // starts a comment
+//  { and } can also go at the start and end of a line with something else
+//  numbers and masks don't need the Numerical Reflection:/Bookkeeper's Gambit: in front
+//  strings and vectors can be written without the = in front
+pub fn assemble(source: &str, registry: &PatternRegistry) -> Result<Vec<Iota>, AssembleError> {
+    Ok(Assembler::run(source, registry)?.iotas)
+}
+
+//the same as assemble but only allows patterns
+pub fn assemble_patterns(
+    source: &str,
+    registry: &PatternRegistry,
+) -> Result<Vec<Pattern>, AssembleError> {
+    let assembler = Assembler::run(source, registry)?;
+
+    assembler
+        .iotas
+        .into_iter()
+        .zip(assembler.lines)
+        .map(|(iota, line)| match iota {
+            Iota::Pattern(pattern) => Ok(pattern),
+            _ => Err(AssembleError::NotAPattern { line }),
+        })
+        .collect()
+}
+
+//HexPattern(EAST qaq), HexPattern(WEST qqq), ... for pasting into the game or the renderer
+pub fn to_hex_notation(iotas: &[Iota]) -> String {
+    iotas
+        .iter()
+        .map(|iota| iota.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+struct Assembler<'a> {
+    registry: &'a PatternRegistry,
+    iotas: Vec<Iota>,
+    //the line each iota came from
+    lines: Vec<usize>,
+    //the lines of the Introspections that haven't been closed yet
+    open_blocks: Vec<usize>,
+}
+
+impl<'a> Assembler<'a> {
+    fn run(source: &str, registry: &'a PatternRegistry) -> Result<Self, AssembleError> {
+        let mut assembler = Assembler {
+            registry,
+            iotas: Vec::new(),
+            lines: Vec::new(),
+            open_blocks: Vec::new(),
+        };
+
+        for (i, line) in source.lines().enumerate() {
+            assembler.add_line(i + 1, line)?;
+        }
+
+        if let Some(&line) = assembler.open_blocks.last() {
+            return Err(AssembleError::UnclosedIntrospection { line });
+        }
+        Ok(assembler)
+    }
+
+    fn add_line(&mut self, line: usize, text: &str) -> Result<(), AssembleError> {
+        let mut text = strip_comment(text).trim();
+
+        while let Some(rest) = text.strip_prefix('{') {
+            self.add_named(line, "Introspection")?;
+            self.open_blocks.push(line);
+            text = rest.trim_start();
+        }
+
+        let mut closes = 0;
+        while let Some(rest) = text.strip_suffix('}') {
+            closes += 1;
+            text = rest.trim_end();
+        }
+
+        if !text.is_empty() {
+            self.add_item(line, text)?;
+        }
+
+        for _ in 0..closes {
+            if self.open_blocks.pop().is_none() {
+                return Err(AssembleError::UnmatchedRetrospection { line });
+            }
+            self.add_named(line, "Retrospection")?;
+        }
+        Ok(())
+    }
+
+    fn add_item(&mut self, line: usize, text: &str) -> Result<(), AssembleError> {
+        if let Some(escaped) = text.strip_prefix(CONSIDERATION_PREFIX) {
+            self.add_named(line, "Consideration")?;
+            return self.add_item(line, escaped.trim());
+        }
+        if let Some(num) = text.strip_prefix(NUMBER_PREFIX) {
+            return self.add_number(line, num.trim());
+        }
+        if let Some(mask) = text.strip_prefix(MASK_PREFIX) {
+            return self.add_mask(line, mask.trim());
+        }
+        if let Some(literal) = text.strip_prefix('=') {
+            return self.add_literal(line, literal.trim());
+        }
+
+        if text.chars().all(|part| part == KEEP || part == DROP) {
+            self.add_mask(line, text)
+        } else if text
+            .starts_with(|first: char| first.is_ascii_digit() || first == '-' || first == '.')
+        {
+            self.add_number(line, text)
+        } else if text.starts_with('"') || text.starts_with('(') {
+            self.add_literal(line, text)
+        } else if text.starts_with('<') || text.to_lowercase().starts_with("hexpattern") {
+            let pattern = Pattern::try_from(text).map_err(|_| AssembleError::InvalidLiteral {
+                line,
+                literal: text.to_string(),
+            })?;
+            self.push(line, Iota::Pattern(pattern));
+            Ok(())
+        } else {
+            self.add_named(line, text)
+        }
+    }
+
+    fn add_named(&mut self, line: usize, name: &str) -> Result<(), AssembleError> {
+        let pattern = self
+            .registry
+            .get_by_name(name)
+            .ok_or_else(|| AssembleError::UnknownName {
+                line,
+                name: name.to_string(),
+            })?
+            .get_pattern();
+        self.push(line, Iota::Pattern(pattern));
+        Ok(())
+    }
+
+    fn add_number(&mut self, line: usize, text: &str) -> Result<(), AssembleError> {
+        let num: f64 = text.parse().map_err(|_| AssembleError::InvalidLiteral {
+            line,
+            literal: text.to_string(),
+        })?;
+        let pattern =
+            encode_number(num).map_err(|error| AssembleError::InvalidNumber { line, error })?;
+        self.push(line, Iota::Pattern(pattern));
+        Ok(())
+    }
+
+    fn add_mask(&mut self, line: usize, text: &str) -> Result<(), AssembleError> {
+        let pattern =
+            encode_bookkeeper(text).map_err(|error| AssembleError::InvalidMask { line, error })?;
+        self.push(line, Iota::Pattern(pattern));
+        Ok(())
+    }
+
+    //a single iota written the same way the parser reads it
+    fn add_literal(&mut self, line: usize, text: &str) -> Result<(), AssembleError> {
+        let invalid = || AssembleError::InvalidLiteral {
+            line,
+            literal: text.to_string(),
+        };
+
+        let mut iotas = parse::parse_iotas(text).map_err(|_| invalid())?;
+        if iotas.len() != 1 || matches!(iotas[0].iota, Iota::Unknown(_)) {
+            return Err(invalid());
+        }
+        self.push(line, iotas.remove(0).iota);
+        Ok(())
+    }
+
+    fn push(&mut self, line: usize, iota: Iota) {
+        self.iotas.push(iota);
+        self.lines.push(line);
+    }
+}
+
+//removes everything after a // that isn't in a string
+fn strip_comment(text: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut last = None;
+    for (i, char) in text.char_indices() {
+        match char {
+            //\" and \\ don't end a string
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '/' if !in_string && last == Some('/') => return &text[..i - 1],
+            _ => (),
+        }
+        last = Some(char);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_renderer::parse::ParsedIota;
+
+    use super::*;
+    use crate::disassemble;
+
+    //disassembles what source assembles to, without the comments the disassembler adds
+    fn round_trip(source: &str) -> String {
+        let registry = PatternRegistry::with_builtins();
+        let iotas: Vec<ParsedIota> = assemble(source, &registry)
+            .unwrap()
+            .into_iter()
+            .map(|iota| ParsedIota { iota, span: 0..0 })
+            .collect();
+
+        disassemble(&iotas, &registry)
+            .lines()
+            .map(|line| format!("{}\n", strip_comment(line).trim_end()))
+            .collect()
+    }
+
+    #[test]
+    fn named_patterns() {
+        let source = "Mind's Reflection\nCompass' Purification\nAdditive Distillation\n";
+        assert_eq!(round_trip(source), source);
+    }
+
+    #[test]
+    fn numbers_and_masks() {
+        let source = "Numerical Reflection: 10\n\
+            Numerical Reflection: -2.5\n\
+            Numerical Reflection: 1234\n\
+            Numerical Reflection: 0\n\
+            Bookkeeper's Gambit: v-v\n\
+            Bookkeeper's Gambit: -\n";
+        assert_eq!(round_trip(source), source);
+
+        //the prefixes can be left off
+        assert_eq!(
+            round_trip("10\n-2.5\nv-v\n"),
+            "Numerical Reflection: 10\nNumerical Reflection: -2.5\nBookkeeper's Gambit: v-v\n"
+        );
+    }
+
+    #[test]
+    fn nested_lists() {
+        let source = "{\n    Mind's Reflection\n    {\n        Numerical Reflection: 2\n    }\n}\n\
+            Consideration: Hermes' Gambit\n\
+            = [1, [2, \"three\"], HexPattern(EAST qaq)]\n";
+        assert_eq!(round_trip(source), source);
+    }
+
+    #[test]
+    fn unknown_names() {
+        let registry = PatternRegistry::with_builtins();
+        assert!(matches!(
+            assemble("Mind's Reflection\nMind's Reflexion", &registry),
+            Err(AssembleError::UnknownName { line: 2, name }) if name == "Mind's Reflexion"
+        ));
+    }
+}
@@ -0,0 +1,78 @@
+use hex_renderer::{
+    pattern_utils::{Angle, Direction},
+    Pattern,
+};
+
+pub const KEEP: char = '-';
+pub const DROP: char = 'v';
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookkeeperEncodeError {
+    EmptyMask,
+    InvalidMask(char),
+}
+
+//reads the mask of a Bookkeeper's Gambit (- keeps an iota, v drops it)
+//the same way the game does, relative to the direction the "flat" lines go in
+pub fn decode_bookkeeper(pattern: &Pattern) -> Option<String> {
+    let angles = pattern.get_angles();
+    let start = pattern.get_start_direction();
+
+    //a pattern starting with a dip starts off going down
+    let flat = if angles.first() == Some(&Angle::BackLeft) {
+        start + Angle::Left
+    } else {
+        start
+    };
+
+    let mut direction = start;
+    let mut directions = vec![direction - flat];
+    for &angle in angles {
+        direction = direction + angle;
+        directions.push(direction - flat);
+    }
+
+    let mut mask = String::new();
+    let mut i = 0;
+    while i < directions.len() {
+        match (directions[i], directions.get(i + 1)) {
+            (Angle::Forward, _) => {
+                mask.push(KEEP);
+                i += 1;
+            }
+            (Angle::Right, Some(Angle::Left)) => {
+                mask.push(DROP);
+                i += 2;
+            }
+            _ => return None,
+        }
+    }
+    Some(mask)
+}
+
+//draws the Bookkeeper's Gambit for a mask like "-vv-v"
+pub fn encode_bookkeeper(mask: &str) -> Result<Pattern, BookkeeperEncodeError> {
+    let flat = Direction::East;
+
+    let mut directions = Vec::new();
+    for part in mask.chars() {
+        match part {
+            KEEP => directions.push(flat),
+            DROP => directions.extend([flat + Angle::Right, flat + Angle::Left]),
+            _ => return Err(BookkeeperEncodeError::InvalidMask(part)),
+        }
+    }
+
+    let Some(&start) = directions.first() else {
+        return Err(BookkeeperEncodeError::EmptyMask);
+    };
+    let angles = directions
+        .windows(2)
+        .map(|directions| directions[1] - directions[0])
+        .collect();
+
+    let pattern = Pattern::new(start, angles);
+    debug_assert!(!pattern.is_self_colliding());
+    debug_assert_eq!(decode_bookkeeper(&pattern).as_deref(), Some(mask));
+    Ok(pattern)
+}
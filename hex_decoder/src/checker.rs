@@ -0,0 +1,553 @@
+use std::fmt::{self, Display};
+
+use hex_renderer::Pattern;
+
+use crate::{
+    decode_bookkeeper, decode_number, PatternRegistry, CONSIDERATION, DROP, INTROSPECTION,
+    RETROSPECTION,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IotaType {
+    Number,
+    Vector,
+    Boolean,
+    Entity,
+    Null,
+    Pattern,
+    List,
+    String,
+    //addon types (motes, matrices, ...) that are only checked loosely
+    Other(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum CheckErrorKind {
+    Underflow {
+        needed: usize,
+        available: usize,
+    },
+    //the argument is counted from the bottom (the same order as the parameters)
+    WrongType {
+        argument: usize,
+        expected: String,
+        found: String,
+    },
+    UnmatchedRetrospection,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckError {
+    //where in the pattern list the mishap would happen
+    pub index: usize,
+    pub pattern: String,
+    pub kind: CheckErrorKind,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckReport {
+    //the top of the stack after the patterns run (bottom first)
+    pub stack: Vec<String>,
+    //false if there could be more iotas below the ones in stack
+    pub exact: bool,
+    //patterns whose effect isn't known, so the stack below them isn't checked
+    pub untracked: Vec<usize>,
+}
+
+//patterns that only move iotas around, as (name, iotas taken, which of them are put back)
+const SHUFFLES: [(&str, usize, &[usize]); 7] = [
+    ("Jester's Gambit", 2, &[1, 0]),
+    ("Rotation Gambit", 3, &[1, 2, 0]),
+    ("Rotation Gambit II", 3, &[2, 0, 1]),
+    ("Gemini Decomposition", 1, &[0, 0]),
+    ("Prospector's Gambit", 2, &[0, 1, 0]),
+    ("Undertaker's Gambit", 2, &[1, 0, 1]),
+    ("Dioscuri Gambit", 2, &[0, 1, 0, 1]),
+];
+
+//an iota on the stack, numbers from Numerical Reflection keep their value
+#[derive(Debug, Clone)]
+struct Slot {
+    //None if it could be anything
+    types: Option<Vec<IotaType>>,
+    value: Option<f64>,
+}
+
+impl Slot {
+    fn any() -> Self {
+        Self {
+            types: None,
+            value: None,
+        }
+    }
+    fn of(types: Option<Vec<IotaType>>) -> Self {
+        Self { types, value: None }
+    }
+    fn number(value: f64) -> Self {
+        Self {
+            types: Some(vec![IotaType::Number]),
+            value: Some(value),
+        }
+    }
+}
+
+struct Signature {
+    inputs: Vec<Option<Vec<IotaType>>>,
+    outputs: Vec<Option<Vec<IotaType>>>,
+}
+
+struct Checker<'a> {
+    registry: &'a PatternRegistry,
+    stack: Vec<Slot>,
+    exact: bool,
+    untracked: Vec<usize>,
+}
+
+//simulates the stack through the patterns (starting from an empty stack)
+//and finds the first one that would mishap from too few iotas or the wrong type of iota
+pub fn check(patterns: &[Pattern], registry: &PatternRegistry) -> Result<CheckReport, CheckError> {
+    let mut checker = Checker {
+        registry,
+        stack: Vec::new(),
+        exact: true,
+        untracked: Vec::new(),
+    };
+
+    let mut i = 0;
+    while i < patterns.len() {
+        let sig = patterns[i].get_angle_sig();
+        match &sig[..] {
+            INTROSPECTION => {
+                i = skip_block(patterns, i);
+                checker.stack.push(Slot::of(Some(vec![IotaType::List])));
+            }
+            RETROSPECTION => {
+                return Err(CheckError {
+                    index: i,
+                    pattern: "Retrospection".to_string(),
+                    kind: CheckErrorKind::UnmatchedRetrospection,
+                })
+            }
+            CONSIDERATION if i + 1 < patterns.len() => {
+                i += 1;
+                checker.stack.push(Slot::of(Some(vec![IotaType::Pattern])));
+            }
+            _ => checker.run(i, &patterns[i]).map_err(|kind| CheckError {
+                index: i,
+                pattern: checker.name(&patterns[i]),
+                kind,
+            })?,
+        }
+        i += 1;
+    }
+
+    Ok(CheckReport {
+        stack: checker.stack.iter().map(describe_slot).collect(),
+        exact: checker.exact,
+        untracked: checker.untracked,
+    })
+}
+
+//the index of the Retrospection that closes the Introspection at start
+//(or the last pattern if it's never closed)
+fn skip_block(patterns: &[Pattern], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i < patterns.len() {
+        match &patterns[i].get_angle_sig()[..] {
+            INTROSPECTION => depth += 1,
+            RETROSPECTION => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            CONSIDERATION => i += 1,
+            _ => (),
+        }
+        i += 1;
+    }
+    patterns.len() - 1
+}
+
+impl Checker<'_> {
+    fn name(&self, pattern: &Pattern) -> String {
+        if let Some(known) = self.registry.get_by_pattern(pattern) {
+            known.name.clone()
+        } else if decode_number(pattern).is_some() {
+            "Numerical Reflection".to_string()
+        } else if decode_bookkeeper(pattern).is_some() {
+            "Bookkeeper's Gambit".to_string()
+        } else {
+            pattern.to_string()
+        }
+    }
+
+    fn run(&mut self, index: usize, pattern: &Pattern) -> Result<(), CheckErrorKind> {
+        let Some(known) = self.registry.get_by_pattern(pattern) else {
+            if let Some(num) = decode_number(pattern) {
+                self.stack.push(Slot::number(num));
+            } else if let Some(mask) = decode_bookkeeper(pattern) {
+                let taken = self.pop(mask.len())?;
+                for (slot, part) in taken.into_iter().zip(mask.chars()) {
+                    if part != DROP {
+                        self.stack.push(slot);
+                    }
+                }
+            } else {
+                self.lose_track(index);
+            }
+            return Ok(());
+        };
+
+        if let Some((_, count, order)) = SHUFFLES.iter().find(|(name, ..)| *name == known.name) {
+            let taken = self.pop(*count)?;
+            for &i in *order {
+                self.stack.push(taken[i].clone());
+            }
+            return Ok(());
+        }
+
+        match &known.name[..] {
+            "Flock's Reflection" => {
+                let size = self.exact.then_some(self.stack.len() as f64);
+                self.stack.push(Slot {
+                    types: Some(vec![IotaType::Number]),
+                    value: size,
+                });
+            }
+            //Fisherman's Gambit counts from 1 for the iota under the number
+            //but Fisherman's Gambit II counts from 0
+            "Fisherman's Gambit" | "Fisherman's Gambit II" => {
+                let copies = known.name == "Fisherman's Gambit II";
+                let depth = self.pop_count()?;
+                match depth.map(|depth| depth + copies as usize) {
+                    Some(depth) if depth >= 1 => {
+                        if depth > self.stack.len() {
+                            if self.exact {
+                                return Err(CheckErrorKind::Underflow {
+                                    needed: depth + 1,
+                                    available: self.stack.len() + 1,
+                                });
+                            }
+                            self.stack.push(Slot::any());
+                        } else if copies {
+                            let fish = self.stack[self.stack.len() - depth].clone();
+                            self.stack.push(fish);
+                        } else {
+                            let fish = self.stack.remove(self.stack.len() - depth);
+                            self.stack.push(fish);
+                        }
+                    }
+                    _ => self.lose_track(index),
+                }
+            }
+            "Gemini Gambit" => {
+                let count = self.pop_count()?;
+                let copied = self.pop(1)?.remove(0);
+                match count {
+                    Some(count) => {
+                        for _ in 0..count {
+                            self.stack.push(copied.clone());
+                        }
+                    }
+                    None => self.lose_track(index),
+                }
+            }
+            "Flock's Gambit" => {
+                let count = self.pop_count()?;
+                match count {
+                    Some(count) => {
+                        self.pop(count).map_err(|err| match err {
+                            CheckErrorKind::Underflow { needed, available } => {
+                                CheckErrorKind::Underflow {
+                                    needed: needed + 1,
+                                    available: available + 1,
+                                }
+                            }
+                            err => err,
+                        })?;
+                    }
+                    None => self.lose_track(index),
+                }
+                self.stack.push(Slot::of(Some(vec![IotaType::List])));
+            }
+            _ => match known.parameters.as_deref().and_then(parse_signature) {
+                Some(signature) => {
+                    let taken = self.pop(signature.inputs.len())?;
+                    for (argument, (slot, expected)) in
+                        taken.iter().zip(&signature.inputs).enumerate()
+                    {
+                        if !compatible(slot, expected) {
+                            return Err(CheckErrorKind::WrongType {
+                                argument,
+                                expected: describe_types(expected),
+                                found: describe_slot(slot),
+                            });
+                        }
+                    }
+                    for output in signature.outputs {
+                        self.stack.push(Slot::of(output));
+                    }
+                }
+                //many, missing parameters and anything else that can't be followed
+                None => {
+                    if let Some(inputs) = known.parameters.as_deref().and_then(fixed_inputs) {
+                        self.pop(inputs)?;
+                    }
+                    self.lose_track(index);
+                }
+            },
+        }
+        Ok(())
+    }
+
+    //takes the top iotas off the stack, bottom first
+    fn pop(&mut self, count: usize) -> Result<Vec<Slot>, CheckErrorKind> {
+        if count > self.stack.len() {
+            if self.exact {
+                return Err(CheckErrorKind::Underflow {
+                    needed: count,
+                    available: self.stack.len(),
+                });
+            }
+            let missing = count - self.stack.len();
+            let mut taken = vec![Slot::any(); missing];
+            taken.append(&mut self.stack);
+            return Ok(taken);
+        }
+        Ok(self.stack.split_off(self.stack.len() - count))
+    }
+
+    //takes a number off the stack that says how many iotas are used
+    //None if it isn't known (or isn't a whole number)
+    fn pop_count(&mut self) -> Result<Option<usize>, CheckErrorKind> {
+        let slot = self.pop(1)?.remove(0);
+        let expected = Some(vec![IotaType::Number]);
+        if !compatible(&slot, &expected) {
+            return Err(CheckErrorKind::WrongType {
+                argument: 0,
+                expected: describe_types(&expected),
+                found: describe_slot(&slot),
+            });
+        }
+        Ok(slot
+            .value
+            .filter(|value| value.fract() == 0.0 && *value >= 0.0)
+            .map(|value| value as usize))
+    }
+
+    //anything that was on the stack could have been used or moved
+    fn lose_track(&mut self, index: usize) {
+        self.stack.clear();
+        self.exact = false;
+        self.untracked.push(index);
+    }
+}
+
+//"entity, vector → number" into its inputs and outputs
+//None for signatures that use "many" or can't be followed
+fn parse_signature(parameters: &str) -> Option<Signature> {
+    let (inputs, outputs) = parameters.split_once('→')?;
+    //"mote, vec | mote, vec, num" could be two different lists of inputs
+    //so how many iotas it takes isn't known
+    if split_top_level(inputs, '|').len() > 1 && split_top_level(inputs, ',').len() > 1 {
+        return None;
+    }
+    Some(Signature {
+        inputs: parse_types(inputs)?,
+        outputs: parse_types(outputs)?,
+    })
+}
+
+//how many iotas a signature that can't be followed still takes for sure
+//(the fewest any of its lists of inputs takes)
+fn fixed_inputs(parameters: &str) -> Option<usize> {
+    let (inputs, _) = parameters.split_once('→')?;
+    split_top_level(inputs, '|')
+        .into_iter()
+        .map(|inputs| {
+            let inputs = split_top_level(inputs, ',');
+            inputs
+                .iter()
+                .all(|input| input.trim() != "many")
+                .then_some(inputs.len())
+        })
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .min()
+}
+
+fn parse_types(list: &str) -> Option<Vec<Option<Vec<IotaType>>>> {
+    split_top_level(list, ',')
+        .into_iter()
+        .map(|part| parse_type(part.trim()))
+        .collect()
+}
+
+//splits on the separator where it isn't inside brackets
+fn split_top_level(list: &str, separator: char) -> Vec<&str> {
+    if list.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, char) in list.char_indices() {
+        match char {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            char if char == separator && depth == 0 => {
+                parts.push(&list[start..i]);
+                start = i + char.len_utf8();
+            }
+            _ => (),
+        }
+    }
+    parts.push(&list[start..]);
+    parts
+}
+
+//None means the type couldn't be read, Some(None) means it could be anything
+fn parse_type(text: &str) -> Option<Option<Vec<IotaType>>> {
+    let text = text
+        .strip_prefix('(')
+        .and_then(|text| text.strip_suffix(')'))
+        .unwrap_or(text);
+
+    //lists are written as [type] and might have | inside the brackets
+    if text.starts_with('[') && text.ends_with(']') {
+        return Some(Some(vec![IotaType::List]));
+    }
+    if text == "many" || text.contains(',') || text.contains('(') {
+        return None;
+    }
+
+    let mut types = Vec::new();
+    for name in text.split(['|', '/']).flat_map(|name| name.split(" or ")) {
+        let iota_type = match name.trim().to_lowercase().as_str() {
+            "any" | "value" => return Some(None),
+            "num" | "number" | "int" | "int?" | "int ≥ 0" | "double" => IotaType::Number,
+            "vec" | "vector" | "pos" => IotaType::Vector,
+            "bool" => IotaType::Boolean,
+            "entity" | "villager" | "wisp" => IotaType::Entity,
+            "null" => IotaType::Null,
+            "pattern" => IotaType::Pattern,
+            "str" => IotaType::String,
+            name if name == "list" || name.starts_with("list of") || name.starts_with('[') => {
+                IotaType::List
+            }
+            name => IotaType::Other(name.to_string()),
+        };
+        if !types.contains(&iota_type) {
+            types.push(iota_type);
+        }
+    }
+    Some(Some(types))
+}
+
+//only iotas that are definitely one of the base types are checked
+//addon types could be accepted by patterns in ways the parameters don't show
+//numbers are allowed where bools are expected since some pattern files still list
+//patterns that give bools as giving numbers (from before bools were their own type)
+fn compatible(slot: &Slot, expected: &Option<Vec<IotaType>>) -> bool {
+    let (Some(found), Some(expected)) = (&slot.types, expected) else {
+        return true;
+    };
+    let is_other = |iota_type: &IotaType| matches!(iota_type, IotaType::Other(_));
+    if found.iter().any(is_other) || expected.iter().any(is_other) {
+        return true;
+    }
+    if found.contains(&IotaType::Number) && expected.contains(&IotaType::Boolean) {
+        return true;
+    }
+    found.iter().any(|iota_type| expected.contains(iota_type))
+}
+
+fn describe_types(types: &Option<Vec<IotaType>>) -> String {
+    match types {
+        Some(types) => types
+            .iter()
+            .map(|iota_type| iota_type.to_string())
+            .collect::<Vec<_>>()
+            .join(" | "),
+        None => "any".to_string(),
+    }
+}
+
+fn describe_slot(slot: &Slot) -> String {
+    match slot.value {
+        Some(value) => format!("number ({value})"),
+        None => describe_types(&slot.types),
+    }
+}
+
+impl Display for IotaType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IotaType::Number => f.write_str("number"),
+            IotaType::Vector => f.write_str("vector"),
+            IotaType::Boolean => f.write_str("bool"),
+            IotaType::Entity => f.write_str("entity"),
+            IotaType::Null => f.write_str("null"),
+            IotaType::Pattern => f.write_str("pattern"),
+            IotaType::List => f.write_str("list"),
+            IotaType::String => f.write_str("str"),
+            IotaType::Other(name) => f.write_str(name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_renderer::parse;
+
+    use super::*;
+    use crate::assemble_patterns;
+
+    //the spell hex_decoder's binary disassembles when it isn't given a file
+    #[test]
+    fn example_spell_checks() {
+        let registry = PatternRegistry::with_builtins();
+        let iotas = parse::parse_iotas(include_str!("example_spell.txt")).unwrap();
+        let patterns: Vec<Pattern> = iotas
+            .iter()
+            .flat_map(|iota| iota.patterns())
+            .cloned()
+            .collect();
+
+        if let Err(err) = check(&patterns, &registry) {
+            panic!("{} at {}: {:?}", err.pattern, err.index, err.kind);
+        }
+    }
+
+    #[test]
+    fn comparisons_give_bools() {
+        let registry = PatternRegistry::with_builtins();
+        let patterns = assemble_patterns("1\n2\nMaximus Distillation", &registry).unwrap();
+        let report = check(&patterns, &registry).unwrap();
+        assert_eq!(report.stack, ["bool"]);
+
+        let source = "1\n2\nEquality Distillation\nConsideration: Hermes' Gambit\n\
+            Consideration: Hermes' Gambit\nAugur's Exaltation";
+        let patterns = assemble_patterns(source, &registry).unwrap();
+        assert!(check(&patterns, &registry).is_ok());
+    }
+
+    #[test]
+    fn wrong_type() {
+        let registry = PatternRegistry::with_builtins();
+        let source = "Vacant Reflection\n1\nAdditive Distillation";
+        let patterns = assemble_patterns(source, &registry).unwrap();
+
+        let err = check(&patterns, &registry).unwrap_err();
+        assert_eq!(err.index, 2);
+        assert_eq!(err.pattern, "Additive Distillation");
+        assert!(
+            matches!(&err.kind, CheckErrorKind::WrongType { argument: 0, found, .. } if found == "list"),
+            "{:?}",
+            err.kind
+        );
+    }
+}
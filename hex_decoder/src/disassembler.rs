@@ -0,0 +1,143 @@
+use hex_renderer::{
+    parse::{Iota, ParsedIota},
+    Pattern,
+};
+
+use crate::{decode_bookkeeper, decode_number, PatternRegistry};
+
+pub const INTROSPECTION: &str = "qqq";
+pub const RETROSPECTION: &str = "eee";
+pub const CONSIDERATION: &str = "qqqaw";
+
+const INDENT: &str = "    ";
+//comments are lined up after the longest line, unless that would push them too far out
+const MAX_COMMENT_COLUMN: usize = 48;
+
+struct Line {
+    depth: usize,
+    code: String,
+    comment: Option<String>,
+}
+
+//turns a list of iotas into one line per pattern, in the same format the assembler reads:
+//  names of known patterns (with their parameters as a comment)
+//  Numerical Reflection: <number> and Bookkeeper's Gambit: <mask>
+//  Consideration: <pattern> for escaped patterns
+//  { and } for Introspection and Retrospection, with everything between them indented
+//  HexPattern(...) for patterns that aren't known and = <iota> for anything that isn't a pattern
+pub fn disassemble(iotas: &[ParsedIota], registry: &PatternRegistry) -> String {
+    let mut lines = Vec::new();
+    let mut depth = 0;
+    let mut escaped = false;
+
+    for (i, iota) in iotas.iter().enumerate() {
+        let Iota::Pattern(pattern) = &iota.iota else {
+            let line = match &iota.iota {
+                Iota::String(_) | Iota::Vector(..) => iota.iota.to_string(),
+                other => format!("= {other}"),
+            };
+            lines.push(Line {
+                depth,
+                code: if escaped {
+                    format!("Consideration: {line}")
+                } else {
+                    line
+                },
+                comment: None,
+            });
+            escaped = false;
+            continue;
+        };
+
+        if escaped {
+            let (code, comment) = describe(pattern, registry);
+            lines.push(Line {
+                depth,
+                code: format!("Consideration: {code}"),
+                comment,
+            });
+            escaped = false;
+            continue;
+        }
+
+        match &pattern.get_angle_sig()[..] {
+            INTROSPECTION => {
+                lines.push(Line {
+                    depth,
+                    code: "{".to_string(),
+                    comment: None,
+                });
+                depth += 1;
+            }
+            RETROSPECTION if depth > 0 => {
+                depth -= 1;
+                lines.push(Line {
+                    depth,
+                    code: "}".to_string(),
+                    comment: None,
+                });
+            }
+            CONSIDERATION if i + 1 < iotas.len() => escaped = true,
+            _ => {
+                let (code, comment) = describe(pattern, registry);
+                lines.push(Line {
+                    depth,
+                    code,
+                    comment,
+                });
+            }
+        }
+    }
+
+    format_lines(&lines)
+}
+
+//the name of a known pattern, including the number/mask for Numerical Reflection and Bookkeeper's Gambit
+pub fn name_pattern(pattern: &Pattern, registry: &PatternRegistry) -> Option<String> {
+    if let Some(known) = registry.get_by_pattern(pattern) {
+        Some(known.name.clone())
+    } else if let Some(num) = decode_number(pattern) {
+        Some(format!("Numerical Reflection: {num}"))
+    } else {
+        decode_bookkeeper(pattern).map(|mask| format!("Bookkeeper's Gambit: {mask}"))
+    }
+}
+
+//the text for a single pattern along with its comment
+fn describe(pattern: &Pattern, registry: &PatternRegistry) -> (String, Option<String>) {
+    let Some(name) = name_pattern(pattern, registry) else {
+        return (pattern.to_string(), Some("unknown pattern".to_string()));
+    };
+    let comment = registry
+        .get_by_pattern(pattern)
+        .and_then(|known| known.parameters.as_ref())
+        .map(|parameters| format!("({parameters})"));
+    (name, comment)
+}
+
+fn format_lines(lines: &[Line]) -> String {
+    let width = |line: &Line| line.depth * INDENT.len() + line.code.chars().count();
+
+    let comment_column = lines
+        .iter()
+        .filter(|line| line.comment.is_some())
+        .map(width)
+        .filter(|&width| width < MAX_COMMENT_COLUMN)
+        .max()
+        .unwrap_or(0)
+        + 2;
+
+    let mut output = String::new();
+    for line in lines {
+        output.push_str(&INDENT.repeat(line.depth));
+        output.push_str(&line.code);
+        if let Some(comment) = &line.comment {
+            let padding = comment_column.saturating_sub(width(line)).max(1);
+            output.push_str(&" ".repeat(padding));
+            output.push_str("// ");
+            output.push_str(comment);
+        }
+        output.push('\n');
+    }
+    output
+}
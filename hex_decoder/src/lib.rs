@@ -0,0 +1,19 @@
+mod registry;
+pub use registry::{PatternRegistry, RegistryError, RegistryPattern, BUILTIN_FILES};
+
+mod numerical;
+pub use numerical::{
+    decode_number, encode_number, NumberEncodeError, NEGATIVE_PREFIX, POSITIVE_PREFIX,
+};
+
+mod bookkeeper;
+pub use bookkeeper::{decode_bookkeeper, encode_bookkeeper, BookkeeperEncodeError, DROP, KEEP};
+
+mod disassembler;
+pub use disassembler::{disassemble, name_pattern, CONSIDERATION, INTROSPECTION, RETROSPECTION};
+
+mod assembler;
+pub use assembler::{assemble, assemble_patterns, to_hex_notation, AssembleError};
+
+mod checker;
+pub use checker::{check, CheckError, CheckErrorKind, CheckReport, IotaType};
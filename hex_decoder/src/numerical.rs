@@ -0,0 +1,459 @@
+use std::collections::{HashMap, HashSet};
+
+use hex_renderer::{
+    pattern_utils::{Angle, Direction},
+    reverse_angle, Pattern,
+};
+
+pub const POSITIVE_PREFIX: &str = "aqaa";
+pub const NEGATIVE_PREFIX: &str = "dedd";
+
+//numbers with more digits after the point than this can't be drawn in any reasonable length
+const MAX_HALVINGS: u32 = 32;
+//past this f64 can't represent every integer so the search can't be exact
+const MAX_MAGNITUDE: f64 = (1u64 << 53) as f64;
+
+//the largest amount a single add can give
+const MAX_ADD: f64 = 10.0;
+
+//how many steps the search for the shortest pattern gets before giving up on it
+//and building a longer one instead
+const SEARCH_BUDGET: usize = 100_000;
+
+//the moves on the hex grid for each direction (NorthEast to NorthWest, clockwise)
+const OFFSETS: [(i32, i32); 6] = [(1, -1), (1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1)];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumberEncodeError {
+    NotFinite(f64),
+    TooLarge(f64),
+    //only numbers made up of halves (eg. 0.75 but not 0.1) can be drawn
+    NotRepresentable(f64),
+    //the search ran out of steps and the number can't be built without it
+    TooComplex(f64),
+}
+
+//reads a Numerical Reflection pattern (aqaa for positive numbers, dedd for negative numbers)
+//w = +1, q = +5, e = +10, a = *2, d = /2
+pub fn decode_number(pattern: &Pattern) -> Option<f64> {
+    let sig = pattern.get_angle_sig();
+
+    let (sign, ops) = if let Some(ops) = sig.strip_prefix(POSITIVE_PREFIX) {
+        (1.0, ops)
+    } else if let Some(ops) = sig.strip_prefix(NEGATIVE_PREFIX) {
+        (-1.0, ops)
+    } else {
+        return None;
+    };
+
+    let mut num = 0.0;
+    for op in ops.chars() {
+        match op {
+            'w' => num += 1.0,
+            'q' => num += 5.0,
+            'e' => num += 10.0,
+            'a' => num *= 2.0,
+            'd' => num /= 2.0,
+            _ => return None,
+        }
+    }
+    Some(sign * num)
+}
+
+//finds the shortest Numerical Reflection for the number that doesn't run over itself
+//numbers that take too long to search for are built digit by digit instead,
+//which is longer but doesn't have to search
+pub fn encode_number(num: f64) -> Result<Pattern, NumberEncodeError> {
+    if !num.is_finite() {
+        return Err(NumberEncodeError::NotFinite(num));
+    }
+    if num.abs() >= MAX_MAGNITUDE {
+        return Err(NumberEncodeError::TooLarge(num));
+    }
+    if halvings(num.abs()).is_none() {
+        return Err(NumberEncodeError::NotRepresentable(num));
+    }
+
+    let (prefix, direction) = if num < 0.0 {
+        (NEGATIVE_PREFIX, Direction::NorthEast)
+    } else {
+        (POSITIVE_PREFIX, Direction::SouthEast)
+    };
+    let prefix: Vec<Angle> = prefix
+        .chars()
+        .map(|angle| Angle::try_from(angle).unwrap())
+        .collect();
+
+    //the ops are found from the end backwards (undoing each one until it reaches 0)
+    //which means the search is drawing the pattern backwards
+    let mut search = Search {
+        prefix: &prefix,
+        start_direction: direction,
+        ops: Vec::new(),
+        path: vec![(0, 0), OFFSETS[0]],
+        direction: 0,
+        edges: HashSet::from([edge((0, 0), OFFSETS[0])]),
+        bounds: Bounds {
+            known: HashMap::new(),
+            budget: SEARCH_BUDGET,
+        },
+    };
+
+    let target = num.abs();
+    let mut max_length = estimate(target);
+    while search.bounds.budget > 0 {
+        if let Some(pattern) = search.search(target, max_length) {
+            return Ok(pattern);
+        }
+        max_length += 1;
+    }
+
+    [Heading::Right, Heading::Straight, Heading::Left]
+        .into_iter()
+        .filter_map(|start| build(target, start))
+        .map(|ops| {
+            let mut angles = prefix.clone();
+            angles.extend(ops);
+            Pattern::new(direction, angles)
+        })
+        .filter(|pattern| !pattern.is_self_colliding())
+        .min_by_key(|pattern| pattern.get_angle_sig().len())
+        .ok_or(NumberEncodeError::TooComplex(num))
+}
+
+//which way the pattern is turned compared to the line it's built along
+//as long as it never turns further than one step either way
+//it keeps moving forward along that line and can't run over itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Heading {
+    Left = -1,
+    Straight = 0,
+    Right = 1,
+}
+
+impl Heading {
+    fn turn(self, by: i32) -> Heading {
+        match self as i32 + by {
+            -1 => Heading::Left,
+            0 => Heading::Straight,
+            1 => Heading::Right,
+            _ => unreachable!(),
+        }
+    }
+}
+
+//builds the ops for the number without searching
+//the number is built in binary with an a (turning left twice) for each doubling
+//and the e's needed to turn back right, then the halvings are done with a d each
+//(turning right twice) and the q's to turn back left
+//None if the number is too small for the turns to fit in it
+fn build(num: f64, start: Heading) -> Option<Vec<Angle>> {
+    let halvings = halvings(num)?;
+    let scaled = (num * 2f64.powi(halvings as i32)) as u64;
+
+    //every halving but the last has to add at least 10 with the q's that turn it back
+    //which is added in before the halvings instead of being built in binary
+    let turns = if halvings == 0 {
+        0
+    } else {
+        10 * ((1u64 << halvings) - 2)
+    };
+    let whole = scaled.checked_sub(turns)?;
+
+    //the adds between each doubling, from last to first
+    //every add between two doublings has to be at least 20 for the e's that turn it back
+    let mut adds = Vec::new();
+    let mut rest = whole;
+    if rest >= 40 {
+        adds.push(rest % 2);
+        rest /= 2;
+        while rest >= 60 {
+            let doubled = (rest - 20) / 2;
+            adds.push(rest - doubled * 2);
+            rest = doubled;
+        }
+    }
+    adds.push(rest);
+    adds.reverse();
+
+    let mut ops = Vec::new();
+    let mut heading = start;
+    //the halvings have to start turned left, otherwise it can end up turned either way
+    let end = if halvings > 0 || adds.len() > 1 {
+        Heading::Left
+    } else {
+        start
+    };
+    //doubling or halving 0 doesn't change it, so they can turn for free before anything is added
+    let first = if adds.len() > 1 { Heading::Right } else { end };
+    let free_turn = match first as i32 - heading as i32 {
+        2 => Some(Angle::BackRight),
+        -2 => Some(Angle::BackLeft),
+        _ => None,
+    };
+    if let Some(op) = free_turn {
+        ops.push(op);
+        heading = first;
+    }
+
+    let last = adds.len() - 1;
+    for (i, &add) in adds.iter().enumerate() {
+        if i == last {
+            push_add(&mut ops, &mut heading, add, end)?;
+        } else {
+            push_add(&mut ops, &mut heading, add, Heading::Right)?;
+            ops.push(Angle::BackLeft);
+            heading = heading.turn(-2);
+        }
+    }
+
+    for i in 0..halvings {
+        ops.push(Angle::BackRight);
+        heading = heading.turn(2);
+        if i + 1 < halvings {
+            push_add(&mut ops, &mut heading, 10, Heading::Left)?;
+        }
+    }
+    Some(ops)
+}
+
+//adds to the number with w's, q's and e's so that it ends up turned to the heading
+//None if the number is too small to make that turn
+fn push_add(ops: &mut Vec<Angle>, heading: &mut Heading, add: u64, end: Heading) -> Option<()> {
+    let turn = end as i32 - *heading as i32;
+    let (turns, turn_op) = if turn > 0 {
+        (turn as u64 * 10, Angle::Right)
+    } else {
+        (-turn as u64 * 5, Angle::Left)
+    };
+    let rest = add.checked_sub(turns)?;
+
+    ops.extend(std::iter::repeat_n(Angle::Forward, (rest % 15) as usize));
+    //a q and an e add 15 without turning
+    let pair = if *heading == Heading::Right {
+        [Angle::Left, Angle::Right]
+    } else {
+        [Angle::Right, Angle::Left]
+    };
+    for _ in 0..rest / 15 {
+        ops.extend(pair);
+    }
+    ops.extend(std::iter::repeat_n(turn_op, turn.unsigned_abs() as usize));
+
+    *heading = end;
+    Some(())
+}
+
+//how many times the number has to be halved to get it
+//None if it isn't made up of halves
+fn halvings(num: f64) -> Option<u32> {
+    (0..=MAX_HALVINGS).find(|&i| (num * 2f64.powi(i as i32)).fract() == 0.0)
+}
+
+//a lower bound on how many ops it takes to get the number
+//every op other than d at most doubles the number or adds 10 to it
+//and every halving needs its own d
+fn estimate(num: f64) -> usize {
+    let halvings = halvings(num).unwrap_or(MAX_HALVINGS) as usize;
+
+    let mut largest = 0.0;
+    let mut ops = 0;
+    while largest < num {
+        largest = (largest * 2.0f64).max(largest + MAX_ADD);
+        ops += 1;
+    }
+    ops + halvings
+}
+
+//each op along with the number it had to be used on to end up with num
+fn undo(num: f64) -> [(Angle, f64); 5] {
+    [
+        (Angle::BackLeft, num / 2.0),
+        (Angle::Right, num - 10.0),
+        (Angle::Left, num - 5.0),
+        (Angle::Forward, num - 1.0),
+        (Angle::BackRight, num * 2.0),
+    ]
+}
+
+//remembers how many ops it takes at least to get each number, ignoring whether the
+//pattern runs over itself, so that the same numbers aren't searched over and over
+struct Bounds {
+    known: HashMap<u64, usize>,
+    //steps left before the search gives up
+    budget: usize,
+}
+
+impl Bounds {
+    fn get(&self, num: f64) -> usize {
+        self.known
+            .get(&num.to_bits())
+            .copied()
+            .unwrap_or_else(|| estimate(num))
+    }
+
+    fn reachable(&mut self, num: f64, remaining: usize) -> bool {
+        if num == 0.0 {
+            return true;
+        }
+        if self.get(num) > remaining || self.budget == 0 {
+            return false;
+        }
+        self.budget -= 1;
+
+        for (_, previous) in undo(num) {
+            if (0.0..MAX_MAGNITUDE).contains(&previous) && self.reachable(previous, remaining - 1) {
+                return true;
+            }
+        }
+
+        //running out of steps doesn't mean it can't be reached
+        if self.budget > 0 {
+            self.known.insert(num.to_bits(), remaining + 1);
+        }
+        false
+    }
+}
+
+fn edge(a: (i32, i32), b: (i32, i32)) -> ((i32, i32), (i32, i32)) {
+    (a.min(b), a.max(b))
+}
+
+struct Search<'a> {
+    prefix: &'a [Angle],
+    start_direction: Direction,
+    //the ops after the prefix, from last to first
+    ops: Vec<Angle>,
+    //the pattern drawn backwards starting from the end
+    path: Vec<(i32, i32)>,
+    direction: usize,
+    edges: HashSet<((i32, i32), (i32, i32))>,
+    bounds: Bounds,
+}
+
+impl Search<'_> {
+    fn search(&mut self, num: f64, remaining: usize) -> Option<Pattern> {
+        if num == 0.0 {
+            let mut angles = self.prefix.to_vec();
+            angles.extend(self.ops.iter().rev());
+
+            let pattern = Pattern::new(self.start_direction, angles);
+            if !pattern.is_self_colliding() {
+                return Some(pattern);
+            }
+        }
+        if remaining == 0 || !self.bounds.reachable(num, remaining) {
+            return None;
+        }
+
+        for (op, previous) in undo(num) {
+            if !(0.0..MAX_MAGNITUDE).contains(&previous) {
+                continue;
+            }
+
+            if !self.push(op) {
+                continue;
+            }
+            let found = self.search(previous, remaining - 1);
+            self.pop();
+
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+
+    //draws the op (backwards), false if that would run over an edge that's already drawn
+    fn push(&mut self, op: Angle) -> bool {
+        let direction = (self.direction + reverse_angle(op) as usize) % 6;
+        let start = *self.path.last().unwrap();
+        let offset = OFFSETS[direction];
+        let end = (start.0 + offset.0, start.1 + offset.1);
+
+        if !self.edges.insert(edge(start, end)) {
+            return false;
+        }
+        self.path.push(end);
+        self.ops.push(op);
+        self.direction = direction;
+        true
+    }
+
+    fn pop(&mut self) {
+        let end = self.path.pop().unwrap();
+        let start = *self.path.last().unwrap();
+        self.edges.remove(&edge(start, end));
+
+        let op = self.ops.pop().unwrap();
+        self.direction = (self.direction + op as usize) % 6;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    fn round_trip(num: f64) {
+        let start = Instant::now();
+        let pattern = encode_number(num).unwrap_or_else(|err| panic!("{num}: {err:?}"));
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "{num} took too long"
+        );
+
+        assert_eq!(decode_number(&pattern), Some(num), "{pattern}");
+        assert!(!pattern.is_self_colliding(), "{pattern}");
+    }
+
+    #[test]
+    fn encodes_small_numbers() {
+        for num in [0.0, 1.0, 2.5, 0.75, -7.0, 10.0, 37.0, -123.5] {
+            round_trip(num);
+        }
+    }
+
+    #[test]
+    fn encodes_large_numbers() {
+        for num in [1e6, -1e6, 1e12, 1e15, 987654321.0] {
+            round_trip(num);
+        }
+    }
+
+    #[test]
+    fn encodes_fractional_numbers() {
+        for num in [1234567.125, -98765.0625, 4096.5, 1e9 + 0.25] {
+            round_trip(num);
+        }
+    }
+
+    #[test]
+    fn encodes_numbers_near_the_limit() {
+        let limit = (1u64 << 53) as f64;
+        for num in [
+            4503599627370495.0,
+            limit - 1.0,
+            -(limit - 1.0),
+            limit / 2.0 + 0.5,
+        ] {
+            round_trip(num);
+        }
+        assert!(matches!(
+            encode_number(limit),
+            Err(NumberEncodeError::TooLarge(num)) if num == limit
+        ));
+    }
+
+    #[test]
+    fn gives_up_on_tiny_fractions() {
+        let start = Instant::now();
+        let num = 2f64.powi(-15);
+        match encode_number(num) {
+            Ok(pattern) => assert_eq!(decode_number(&pattern), Some(num)),
+            Err(err) => assert_eq!(err, NumberEncodeError::TooComplex(num)),
+        }
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
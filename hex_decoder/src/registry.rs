@@ -0,0 +1,366 @@
+use std::{collections::HashMap, fs, io, path::Path};
+
+use hex_renderer::{
+    pattern_utils::{Angle, Direction},
+    Pattern,
+};
+
+//the pattern lists that ship with the decoder as (addon, file contents)
+pub const BUILTIN_FILES: [(&str, &str); 6] = [
+    ("hexal", include_str!("pattern_files/hexal.txt")),
+    ("hexbound", include_str!("pattern_files/hexbound.txt")),
+    ("hexcasting", include_str!("pattern_files/hexcasting.txt")),
+    ("hexkinetics", include_str!("pattern_files/hexkinetics.txt")),
+    ("hextweaks", include_str!("pattern_files/hextweaks.txt")),
+    ("moreiotas", include_str!("pattern_files/moreiotas.txt")),
+];
+
+//patterns that don't show up in the files since they aren't in the book as actions
+const EXTRA_PATTERNS: [(&str, &str); 3] = [
+    ("Introspection", "qqq"),
+    ("Retrospection", "eee"),
+    ("Consideration", "qqqaw"),
+];
+
+//each pattern takes up this many lines in a pattern file:
+//name (parameters), link, signature, default direction, great spell
+const LINES_PER_PATTERN: usize = 5;
+
+#[derive(Debug)]
+pub enum RegistryError {
+    FileError(io::Error),
+    MissingLines {
+        addon: String,
+        line: usize,
+    },
+    InvalidSignature {
+        addon: String,
+        line: usize,
+        signature: String,
+    },
+    InvalidDirection {
+        addon: String,
+        line: usize,
+        direction: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct RegistryPattern {
+    pub name: String,
+    //the stack effect as written in the book, eg. "entity → vector"
+    pub parameters: Option<String>,
+    pub link: Option<String>,
+    //the angles of the pattern, eg. "qaq"
+    pub signature: String,
+    pub default_direction: Direction,
+    pub great_spell: bool,
+    //the mod/addon the pattern comes from (the name of the file it was loaded from)
+    pub addon: String,
+}
+
+impl RegistryPattern {
+    //the pattern drawn in its default direction
+    pub fn get_pattern(&self) -> Pattern {
+        let angles = self
+            .signature
+            .chars()
+            .map(|angle| Angle::try_from(angle).unwrap())
+            .collect();
+        Pattern::new(self.default_direction, angles)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PatternRegistry {
+    patterns: Vec<RegistryPattern>,
+    by_signature: HashMap<String, usize>,
+    //names are stored lowercase so lookups don't care about case
+    by_name: HashMap<String, usize>,
+}
+
+impl PatternRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //all of the builtin pattern files along with Introspection, Retrospection and Consideration
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        for (addon, data) in BUILTIN_FILES {
+            registry
+                .load_str(addon, data)
+                .expect("builtin pattern files are valid");
+        }
+
+        for (name, signature) in EXTRA_PATTERNS {
+            registry.insert(RegistryPattern {
+                name: name.to_string(),
+                parameters: None,
+                link: None,
+                signature: signature.to_string(),
+                default_direction: Direction::West,
+                great_spell: false,
+                addon: "hexcasting".to_string(),
+            });
+        }
+        registry
+    }
+
+    //loads a pattern file, the addon is named after the file
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<(), RegistryError> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path).map_err(RegistryError::FileError)?;
+        let addon = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.load_str(&addon, &data)
+    }
+
+    pub fn load_str(&mut self, addon: &str, data: &str) -> Result<(), RegistryError> {
+        let lines: Vec<&str> = data.lines().collect();
+
+        //nothing is added unless the whole file is valid
+        let mut patterns = Vec::new();
+        for (i, entry) in lines.chunks(LINES_PER_PATTERN).enumerate() {
+            let line = i * LINES_PER_PATTERN + 1;
+            if entry.iter().all(|part| part.trim().is_empty()) {
+                continue;
+            }
+            if entry.len() != LINES_PER_PATTERN {
+                return Err(RegistryError::MissingLines {
+                    addon: addon.to_string(),
+                    line: line + entry.len(),
+                });
+            }
+            patterns.push(parse_entry(addon, line, entry)?);
+        }
+
+        for pattern in patterns {
+            self.insert(pattern);
+        }
+        Ok(())
+    }
+
+    //adds a pattern, replacing any pattern that has the same signature
+    pub fn insert(&mut self, pattern: RegistryPattern) {
+        let name = pattern.name.to_lowercase();
+
+        let index = match self.by_signature.get(&pattern.signature) {
+            Some(&index) => {
+                let old_name = self.patterns[index].name.to_lowercase();
+                if self.by_name.get(&old_name) == Some(&index) {
+                    self.by_name.remove(&old_name);
+                }
+                self.patterns[index] = pattern;
+                index
+            }
+            None => {
+                self.by_signature
+                    .insert(pattern.signature.clone(), self.patterns.len());
+                self.patterns.push(pattern);
+                self.patterns.len() - 1
+            }
+        };
+        self.by_name.insert(name, index);
+    }
+
+    pub fn get_by_signature(&self, signature: &str) -> Option<&RegistryPattern> {
+        self.by_signature
+            .get(signature)
+            .map(|&index| &self.patterns[index])
+    }
+
+    //the start direction doesn't matter, only the angles
+    pub fn get_by_pattern(&self, pattern: &Pattern) -> Option<&RegistryPattern> {
+        self.get_by_signature(&pattern.get_angle_sig())
+    }
+
+    //also finds patterns that were drawn backwards as long as that doesn't
+    //give a different pattern that's in the registry
+    pub fn get_by_pattern_reversible(&self, pattern: &Pattern) -> Option<&RegistryPattern> {
+        self.get_by_pattern(pattern)
+            .or_else(|| self.get_by_pattern(&pattern.reversed()))
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&RegistryPattern> {
+        self.by_name
+            .get(&name.to_lowercase())
+            .map(|&index| &self.patterns[index])
+    }
+
+    pub fn get_by_addon<'a>(&'a self, addon: &'a str) -> impl Iterator<Item = &'a RegistryPattern> {
+        self.patterns
+            .iter()
+            .filter(move |pattern| pattern.addon.eq_ignore_ascii_case(addon))
+    }
+
+    pub fn get_addons(&self) -> Vec<&str> {
+        let mut addons: Vec<&str> = self
+            .patterns
+            .iter()
+            .map(|pattern| &pattern.addon[..])
+            .collect();
+        addons.sort();
+        addons.dedup();
+        addons
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RegistryPattern> {
+        self.patterns.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+fn parse_entry(addon: &str, line: usize, entry: &[&str]) -> Result<RegistryPattern, RegistryError> {
+    let title = entry[0].trim();
+    let link = entry[1].trim();
+    let signature = entry[2].trim();
+    let direction = entry[3].trim();
+    let great_spell = entry[4].trim();
+
+    //"Mind's Reflection (→ entity | null)" is split into the name and the parameters
+    let (name, parameters) = match title.split_once(" (") {
+        Some((name, parameters)) => (
+            name,
+            Some(parameters.strip_suffix(')').unwrap_or(parameters)),
+        ),
+        None => (title, None),
+    };
+
+    if signature
+        .chars()
+        .any(|angle| Angle::try_from(angle).is_err())
+    {
+        return Err(RegistryError::InvalidSignature {
+            addon: addon.to_string(),
+            line: line + 2,
+            signature: signature.to_string(),
+        });
+    }
+
+    let default_direction =
+        Direction::try_from(direction).map_err(|_| RegistryError::InvalidDirection {
+            addon: addon.to_string(),
+            line: line + 3,
+            direction: direction.to_string(),
+        })?;
+
+    Ok(RegistryPattern {
+        name: name.to_string(),
+        parameters: parameters.map(String::from),
+        link: (!link.is_empty() && link != "N/A").then(|| link.to_string()),
+        signature: signature.to_string(),
+        default_direction,
+        great_spell: great_spell.eq_ignore_ascii_case("true"),
+        addon: addon.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "\
+Mind's Reflection (→ entity | null)
+N/A
+qaq
+north_east
+false
+Custom Purification (number → number)
+https://example.com
+wqwqw
+east
+true
+";
+
+    #[test]
+    fn lookups() {
+        let mut registry = PatternRegistry::new();
+        registry.load_str("custom", FILE).unwrap();
+        assert_eq!(registry.len(), 2);
+
+        let pattern = registry.get_by_signature("wqwqw").unwrap();
+        assert_eq!(pattern.name, "Custom Purification");
+        assert_eq!(pattern.parameters.as_deref(), Some("number → number"));
+        assert_eq!(pattern.link.as_deref(), Some("https://example.com"));
+        assert_eq!(pattern.default_direction, Direction::East);
+        assert!(pattern.great_spell);
+
+        let pattern = registry.get_by_name("MIND'S REFLECTION").unwrap();
+        assert_eq!(pattern.signature, "qaq");
+        assert_eq!(pattern.link, None);
+        assert!(registry.get_by_name("Mind's").is_none());
+        assert!(registry.get_by_signature("qa").is_none());
+
+        assert_eq!(registry.get_by_addon("Custom").count(), 2);
+        assert_eq!(registry.get_by_addon("hexcasting").count(), 0);
+        assert_eq!(registry.get_addons(), ["custom"]);
+    }
+
+    #[test]
+    fn malformed_files() {
+        let mut registry = PatternRegistry::new();
+
+        let bad_signature = FILE.replacen("wqwqw", "wqxqw", 1);
+        assert!(matches!(
+            registry.load_str("custom", &bad_signature),
+            Err(RegistryError::InvalidSignature { line: 8, .. })
+        ));
+        let bad_direction = FILE.replacen("north_east", "up", 1);
+        assert!(matches!(
+            registry.load_str("custom", &bad_direction),
+            Err(RegistryError::InvalidDirection { line: 4, .. })
+        ));
+        let cut_off = FILE.lines().take(8).collect::<Vec<_>>().join("\n");
+        assert!(matches!(
+            registry.load_str("custom", &cut_off),
+            Err(RegistryError::MissingLines { line: 9, .. })
+        ));
+
+        //the valid entries before the mistake aren't added either
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn redefining_builtins() {
+        let mut registry = PatternRegistry::with_builtins();
+        let builtins = registry.len();
+        let file = "\
+Mind's Reflection
+N/A
+eee
+east
+false
+Better Mind's Reflection
+N/A
+qaq
+east
+false
+";
+        registry.load_str("custom", file).unwrap();
+
+        //the same signature replaces the builtin pattern
+        let pattern = registry.get_by_signature("qaq").unwrap();
+        assert_eq!(pattern.name, "Better Mind's Reflection");
+        assert_eq!(pattern.addon, "custom");
+        //eee was Retrospection, which is replaced the same way
+        assert_eq!(registry.get_by_signature("eee").unwrap().addon, "custom");
+        assert!(registry.get_by_name("Retrospection").is_none());
+        assert_eq!(registry.len(), builtins);
+
+        //and the name goes to the pattern that was loaded last
+        assert_eq!(
+            registry.get_by_name("mind's reflection").unwrap().signature,
+            "eee"
+        );
+    }
+}
@@ -0,0 +1,462 @@
+use hex_decoder::{
+    decode_bookkeeper, decode_number, PatternRegistry, CONSIDERATION, DROP, INTROSPECTION,
+    RETROSPECTION,
+};
+use hex_renderer::Pattern;
+
+use crate::{ops, stack_size, take_args, Iota, Mishap, MishapKind, World};
+
+//how many patterns a single run can cast (counting the ones run by Hermes' and Thoth's)
+//this is also what stops a Hermes' Gambit that runs itself forever
+pub const MAX_OPS: usize = 100_000;
+
+//how many iotas the stack can hold (counting the ones inside lists)
+//this stops spells like Gemini Gambit with a huge count from using up all the memory
+pub const MAX_IOTAS: usize = 1024;
+
+pub struct Interpreter<W: World> {
+    registry: PatternRegistry,
+    pub world: W,
+    pub stack: Vec<Iota>,
+    pub ravenmind: Iota,
+    ops: usize,
+}
+
+//the frames are kept in a list instead of recursing so deep Hermes' Gambits can't overflow
+enum Frame {
+    Code(Code),
+    //a Thoth's Gambit between its iterations
+    ForEach(ForEach),
+}
+
+//a list of iotas being run along with its quoting state
+struct Code {
+    iotas: Vec<Iota>,
+    next: usize,
+    //how many Introspections are open
+    parens: usize,
+    quoted: Vec<Iota>,
+    escape_next: bool,
+}
+
+struct ForEach {
+    code: Vec<Iota>,
+    data: std::vec::IntoIter<Iota>,
+    //the stack each iteration starts from
+    base: Vec<Iota>,
+    collected: Vec<Iota>,
+    started: bool,
+}
+
+enum Flow {
+    Continue,
+    //Charon's Gambit stops the innermost Hermes' or Thoth's Gambit
+    Halt,
+    Call(Frame),
+}
+
+impl Code {
+    fn new(iotas: Vec<Iota>) -> Self {
+        Self {
+            iotas,
+            next: 0,
+            parens: 0,
+            quoted: Vec::new(),
+            escape_next: false,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.next >= self.iotas.len()
+    }
+}
+
+impl<W: World> Interpreter<W> {
+    pub fn new(world: W) -> Self {
+        Self::with_registry(PatternRegistry::with_builtins(), world)
+    }
+
+    //patterns are found by their name in the registry
+    pub fn with_registry(registry: PatternRegistry, world: W) -> Self {
+        Self {
+            registry,
+            world,
+            stack: Vec::new(),
+            ravenmind: Iota::Null,
+            ops: 0,
+        }
+    }
+
+    pub fn registry(&self) -> &PatternRegistry {
+        &self.registry
+    }
+
+    //casts the iotas on top of whatever is already on the stack
+    pub fn run(&mut self, iotas: &[Iota]) -> Result<&[Iota], Mishap> {
+        self.ops = 0;
+
+        let mut frames = vec![Frame::Code(Code::new(iotas.to_vec()))];
+        while let Some(frame) = frames.last_mut() {
+            match frame {
+                Frame::Code(code) => {
+                    //like in the game, an Introspection that's never closed just gets dropped
+                    let Some(iota) = code.iotas.get(code.next).cloned() else {
+                        frames.pop();
+                        continue;
+                    };
+                    code.next += 1;
+
+                    match self.step(code, &iota)? {
+                        Flow::Continue => (),
+                        Flow::Halt => {
+                            frames.pop();
+                            if let Some(Frame::ForEach(each)) = frames.last_mut() {
+                                each.data = Vec::new().into_iter();
+                            }
+                        }
+                        Flow::Call(frame) => {
+                            //nothing is left to run in the caller so it can be dropped
+                            //(unless it's an iteration of a Thoth's Gambit, which Charon's Gambit has to find)
+                            let len = frames.len();
+                            let caller_done =
+                                matches!(&frames[len - 1], Frame::Code(code) if code.is_done());
+                            let in_loop = len > 1 && matches!(&frames[len - 2], Frame::ForEach(_));
+                            if caller_done && !in_loop {
+                                frames.pop();
+                            }
+                            frames.push(frame);
+                        }
+                    }
+                }
+                Frame::ForEach(each) => {
+                    if each.started {
+                        each.collected.append(&mut self.stack);
+                        if stack_size(&each.collected) + stack_size(&each.base) > MAX_IOTAS {
+                            return Err(Mishap {
+                                pattern: "Thoth's Gambit".to_string(),
+                                kind: MishapKind::TooManyIotas,
+                            });
+                        }
+                    }
+                    each.started = true;
+
+                    match each.data.next() {
+                        Some(datum) => {
+                            self.stack = each.base.clone();
+                            self.stack.push(datum);
+                            let code = Code::new(each.code.clone());
+                            frames.push(Frame::Code(code));
+                        }
+                        None => {
+                            self.stack = std::mem::take(&mut each.base);
+                            self.stack
+                                .push(Iota::List(std::mem::take(&mut each.collected)));
+                            frames.pop();
+                        }
+                    }
+                }
+            }
+        }
+        Ok(&self.stack)
+    }
+
+    fn step(&mut self, code: &mut Code, iota: &Iota) -> Result<Flow, Mishap> {
+        let sig = match iota {
+            Iota::Pattern(pattern) => pattern.get_angle_sig(),
+            _ => String::new(),
+        };
+        let is_pattern = matches!(iota, Iota::Pattern(_));
+
+        if code.parens > 0 {
+            if code.escape_next {
+                code.escape_next = false;
+                code.quoted.push(iota.clone());
+                return Ok(Flow::Continue);
+            }
+            match &sig[..] {
+                CONSIDERATION if is_pattern => code.escape_next = true,
+                INTROSPECTION if is_pattern => {
+                    code.parens += 1;
+                    code.quoted.push(iota.clone());
+                }
+                RETROSPECTION if is_pattern => {
+                    code.parens -= 1;
+                    if code.parens == 0 {
+                        let quoted = std::mem::take(&mut code.quoted);
+                        self.stack.push(Iota::List(quoted));
+                    } else {
+                        code.quoted.push(iota.clone());
+                    }
+                }
+                _ => code.quoted.push(iota.clone()),
+            }
+            return Ok(Flow::Continue);
+        }
+
+        if code.escape_next {
+            code.escape_next = false;
+            self.stack.push(iota.clone());
+            return Ok(Flow::Continue);
+        }
+
+        let Iota::Pattern(pattern) = iota else {
+            return Err(Mishap {
+                pattern: iota.to_string(),
+                kind: MishapKind::UnescapedValue(Box::new(iota.clone())),
+            });
+        };
+        match &sig[..] {
+            INTROSPECTION => code.parens = 1,
+            CONSIDERATION => code.escape_next = true,
+            RETROSPECTION => {
+                return Err(Mishap {
+                    pattern: "Retrospection".to_string(),
+                    kind: MishapKind::HastyRetrospection,
+                })
+            }
+            _ => {
+                return self
+                    .cast(pattern)
+                    .and_then(|flow| {
+                        if stack_size(&self.stack) > MAX_IOTAS {
+                            Err(MishapKind::TooManyIotas)
+                        } else {
+                            Ok(flow)
+                        }
+                    })
+                    .map_err(|kind| Mishap {
+                        pattern: self.name(pattern),
+                        kind,
+                    })
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn cast(&mut self, pattern: &Pattern) -> Result<Flow, MishapKind> {
+        self.ops += 1;
+        if self.ops > MAX_OPS {
+            return Err(MishapKind::TooManyOps);
+        }
+
+        let Some(known) = self.registry.get_by_pattern(pattern).cloned() else {
+            if let Some(num) = decode_number(pattern) {
+                self.stack.push(Iota::Number(num));
+            } else if let Some(mask) = decode_bookkeeper(pattern) {
+                self.bookkeeper(&mask)?;
+            } else {
+                return Err(MishapKind::InvalidPattern);
+            }
+            return Ok(Flow::Continue);
+        };
+
+        match &known.name[..] {
+            "Hermes' Gambit" => {
+                let mut args = take_args(&mut self.stack, 1)?;
+                let code = match args.remove(0) {
+                    Iota::List(list) => list,
+                    Iota::Pattern(pattern) => vec![Iota::Pattern(pattern)],
+                    other => return Err(other.incorrect(0, "list of patterns")),
+                };
+                return Ok(Flow::Call(Frame::Code(Code::new(code))));
+            }
+            //runs the code once for each iota in the list, each time on a copy of the stack
+            //with the iota on top, and collects whatever is left on the stack into one list
+            "Thoth's Gambit" => {
+                let args = take_args(&mut self.stack, 2)?;
+                let code = args[0].as_list(0)?.to_vec();
+                let data = args[1].as_list(1)?.to_vec();
+                return Ok(Flow::Call(Frame::ForEach(ForEach {
+                    code,
+                    data: data.into_iter(),
+                    base: std::mem::take(&mut self.stack),
+                    collected: Vec::new(),
+                    started: false,
+                })));
+            }
+            "Charon's Gambit" => return Ok(Flow::Halt),
+            "Huginn's Gambit" => self.ravenmind = take_args(&mut self.stack, 1)?.remove(0),
+            "Muninn's Reflection" => self.stack.push(self.ravenmind.clone()),
+            "Reveal" => {
+                let top = self.stack.last().ok_or(MishapKind::NotEnoughIotas {
+                    needed: 1,
+                    available: 0,
+                })?;
+                self.world.reveal(top);
+            }
+            name => match ops::operate(name, &mut self.stack) {
+                Some(result) => result?,
+                None => self.world.operate(&known, &mut self.stack)?,
+            },
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn name(&self, pattern: &Pattern) -> String {
+        if let Some(known) = self.registry.get_by_pattern(pattern) {
+            known.name.clone()
+        } else if decode_number(pattern).is_some() {
+            "Numerical Reflection".to_string()
+        } else if decode_bookkeeper(pattern).is_some() {
+            "Bookkeeper's Gambit".to_string()
+        } else {
+            pattern.to_string()
+        }
+    }
+
+    fn bookkeeper(&mut self, mask: &str) -> Result<(), MishapKind> {
+        let args = take_args(&mut self.stack, mask.len())?;
+        for (iota, part) in args.into_iter().zip(mask.chars()) {
+            if part != DROP {
+                self.stack.push(iota);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_decoder::assemble;
+
+    use super::*;
+    use crate::cast;
+
+    fn run(source: &str) -> Result<Vec<Iota>, Mishap> {
+        let registry = PatternRegistry::with_builtins();
+        let iotas: Vec<Iota> = assemble(source, &registry)
+            .unwrap()
+            .iter()
+            .map(|iota| Iota::try_from(iota).unwrap())
+            .collect();
+        cast(&iotas)
+    }
+
+    fn numbers(nums: &[f64]) -> Vec<Iota> {
+        nums.iter().map(|&num| Iota::Number(num)).collect()
+    }
+
+    fn named(name: &str) -> Iota {
+        let registry = PatternRegistry::with_builtins();
+        Iota::Pattern(registry.get_by_name(name).unwrap().get_pattern())
+    }
+
+    #[test]
+    fn arithmetic() {
+        let stack = run("2\n3\nAdditive Distillation\n4\nMultiplicative Dstl.\n7.5");
+        assert_eq!(stack.unwrap(), numbers(&[20.0, 7.5]));
+    }
+
+    #[test]
+    fn hermes_gambit() {
+        let stack = run("{\n  2\n  3\n  Additive Distillation\n}\nHermes' Gambit");
+        assert_eq!(stack.unwrap(), numbers(&[5.0]));
+    }
+
+    #[test]
+    fn thoths_gambit() {
+        let source =
+            "{\n  2\n  Multiplicative Dstl.\n}\n1\n2\n3\n3\nFlock's Gambit\nThoth's Gambit";
+        assert_eq!(run(source).unwrap(), vec![numbers(&[2.0, 4.0, 6.0]).into()]);
+    }
+
+    //Charon's Gambit stops the whole Thoth's Gambit but not what comes after it
+    #[test]
+    fn charons_gambit_in_thoths_gambit() {
+        let source = "{\n  10\n  Multiplicative Dstl.\n  Charon's Gambit\n  5\n}\n\
+            1\n2\n3\n3\nFlock's Gambit\nThoth's Gambit\n7";
+        let stack = run(source).unwrap();
+        assert_eq!(stack, vec![numbers(&[10.0]).into(), Iota::Number(7.0)]);
+    }
+
+    #[test]
+    fn fishermans_gambits() {
+        let stack = run("1\n2\n3\n3\nFisherman's Gambit");
+        assert_eq!(stack.unwrap(), numbers(&[2.0, 3.0, 1.0]));
+
+        let stack = run("1\n2\n3\n0\nFisherman's Gambit II\n2\nFisherman's Gambit II");
+        assert_eq!(stack.unwrap(), numbers(&[1.0, 2.0, 3.0, 3.0, 2.0]));
+
+        let mishap = run("1\n1\nFisherman's Gambit II").unwrap_err();
+        assert!(matches!(mishap.kind, MishapKind::NotEnoughIotas { .. }));
+    }
+
+    #[test]
+    fn bookkeepers_gambit() {
+        let stack = run("1\n2\n3\n4\n-v-");
+        assert_eq!(stack.unwrap(), numbers(&[1.0, 2.0, 4.0]));
+    }
+
+    #[test]
+    fn introspection_and_escapes() {
+        let source = "{\n  2\n  Consideration: Retrospection\n  { Hermes' Gambit }\n}\nConsideration: Hermes' Gambit";
+        let stack = run(source).unwrap();
+
+        let Iota::List(quoted) = &stack[0] else {
+            panic!("{stack:?}");
+        };
+        assert_eq!(quoted.len(), 5);
+        assert_eq!(quoted[1], named("Retrospection"));
+        assert_eq!(
+            quoted[2..],
+            [
+                named("Introspection"),
+                named("Hermes' Gambit"),
+                named("Retrospection")
+            ]
+        );
+        assert_eq!(stack[1], named("Hermes' Gambit"));
+    }
+
+    #[test]
+    fn hasty_retrospection() {
+        let mishap = run("Retrospection").unwrap_err();
+        assert_eq!(mishap.kind, MishapKind::HastyRetrospection);
+    }
+
+    //a Hermes' Gambit that runs itself forever
+    #[test]
+    fn too_many_ops() {
+        let source =
+            "{\n  Gemini Decomposition\n  Hermes' Gambit\n}\nGemini Decomposition\nHermes' Gambit";
+        let mishap = run(source).unwrap_err();
+        assert_eq!(mishap.kind, MishapKind::TooManyOps);
+    }
+
+    #[test]
+    fn too_many_iotas() {
+        let stack = run(&format!("1\n{MAX_IOTAS}\nGemini Gambit")).unwrap();
+        assert_eq!(stack.len(), MAX_IOTAS);
+
+        let mishap = run(&format!("1\n{}\nGemini Gambit", MAX_IOTAS + 1)).unwrap_err();
+        assert_eq!(mishap.kind, MishapKind::TooManyIotas);
+
+        let mishap = run("1\n100000000\nGemini Gambit").unwrap_err();
+        assert_eq!(mishap.kind, MishapKind::TooManyIotas);
+        assert_eq!(mishap.pattern, "Gemini Gambit");
+    }
+
+    #[test]
+    fn swindlers_gambit() {
+        let stack = run("1\n2\n3\n1\nSwindler's Gambit").unwrap();
+        assert_eq!(stack, [1.0.into(), 3.0.into(), 2.0.into()]);
+
+        //codes too big for i64 need more iotas than the stack can ever hold
+        let source = "1000000\n1000000\nMultiplicative Dstl.\n1000000\nMultiplicative Dstl.\n\
+            1000000\nMultiplicative Dstl.\n1000000\nMultiplicative Dstl.\n\
+            1000000\nMultiplicative Dstl.\nSwindler's Gambit";
+        let mishap = run(source).unwrap_err();
+        assert!(matches!(mishap.kind, MishapKind::NotEnoughIotas { .. }));
+        assert_eq!(mishap.pattern, "Swindler's Gambit");
+    }
+
+    //lists count towards the limit too, a list that doubles itself every time runs out of room
+    #[test]
+    fn too_many_iotas_in_lists() {
+        let source = "1\nSingle's Purification\n{\n  Jester's Gambit\n  Gemini Decomposition\n\
+            Combination Distillation\n  Jester's Gambit\n  Gemini Decomposition\n  Hermes' Gambit\n}\n\
+            Gemini Decomposition\nHermes' Gambit";
+        let mishap = run(source).unwrap_err();
+        assert_eq!(mishap.kind, MishapKind::TooManyIotas);
+    }
+}
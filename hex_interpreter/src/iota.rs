@@ -0,0 +1,297 @@
+use std::{
+    fmt::{self, Display},
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use hex_renderer::{parse, Pattern};
+
+use crate::MishapKind;
+
+//the game treats numbers this close together as equal
+pub const TOLERANCE: f64 = 0.0001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vector {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+//a handle to an entity in the world, what it refers to is up to the World
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Entity(pub u64);
+
+#[derive(Debug, Clone)]
+pub enum Iota {
+    Number(f64),
+    Vector(Vector),
+    Boolean(bool),
+    Null,
+    //what's left behind after a mishap
+    Garbage,
+    Pattern(Pattern),
+    List(Vec<Iota>),
+    String(String),
+    Entity(Entity),
+}
+
+//patterns are compared the way the game does, by their angles and not the way they're facing
+impl PartialEq for Iota {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Iota::Number(a), Iota::Number(b)) => a == b,
+            (Iota::Vector(a), Iota::Vector(b)) => a == b,
+            (Iota::Boolean(a), Iota::Boolean(b)) => a == b,
+            (Iota::Null, Iota::Null) | (Iota::Garbage, Iota::Garbage) => true,
+            (Iota::Pattern(a), Iota::Pattern(b)) => a.canonical() == b.canonical(),
+            (Iota::List(a), Iota::List(b)) => a == b,
+            (Iota::String(a), Iota::String(b)) => a == b,
+            (Iota::Entity(a), Iota::Entity(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+//an iota from the parser that can't be cast
+#[derive(Debug, Clone)]
+pub struct UnknownIota(pub String);
+
+impl Vector {
+    pub const ZERO: Vector = Vector::new(0.0, 0.0, 0.0);
+
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+    pub fn splat(num: f64) -> Self {
+        Self::new(num, num, num)
+    }
+    pub fn map(self, f: impl Fn(f64) -> f64) -> Self {
+        Self::new(f(self.x), f(self.y), f(self.z))
+    }
+    pub fn zip(self, other: Vector, f: impl Fn(f64, f64) -> f64) -> Self {
+        Self::new(f(self.x, other.x), f(self.y, other.y), f(self.z, other.z))
+    }
+    pub fn dot(self, other: Vector) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+    pub fn cross(self, other: Vector) -> Vector {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+    pub fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+    pub fn is_zero(self) -> bool {
+        self == Self::ZERO
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+    fn add(self, rhs: Vector) -> Vector {
+        self.zip(rhs, |a, b| a + b)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+    fn sub(self, rhs: Vector) -> Vector {
+        self.zip(rhs, |a, b| a - b)
+    }
+}
+
+impl Mul<f64> for Vector {
+    type Output = Vector;
+    fn mul(self, rhs: f64) -> Vector {
+        self.map(|a| a * rhs)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+    fn neg(self) -> Vector {
+        self.map(|a| -a)
+    }
+}
+
+impl Iota {
+    //the name used in mishaps
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Iota::Number(_) => "number",
+            Iota::Vector(_) => "vector",
+            Iota::Boolean(_) => "bool",
+            Iota::Null => "null",
+            Iota::Garbage => "garbage",
+            Iota::Pattern(_) => "pattern",
+            Iota::List(_) => "list",
+            Iota::String(_) => "string",
+            Iota::Entity(_) => "entity",
+        }
+    }
+
+    //equality the way Equality Distillation sees it (numbers and vectors within TOLERANCE)
+    pub fn tolerates(&self, other: &Iota) -> bool {
+        match (self, other) {
+            (Iota::Number(a), Iota::Number(b)) => (a - b).abs() < TOLERANCE,
+            (Iota::Vector(a), Iota::Vector(b)) => (*a - *b).length() < TOLERANCE,
+            (Iota::List(a), Iota::List(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.tolerates(b))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    //1 for the iota itself plus everything inside it if it's a list
+    pub fn size(&self) -> usize {
+        match self {
+            Iota::List(list) => 1 + list.iter().map(Iota::size).sum::<usize>(),
+            _ => 1,
+        }
+    }
+
+    //false, null, 0, the zero vector, empty lists and garbage are false, everything else is true
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Iota::Boolean(bool) => *bool,
+            Iota::Null | Iota::Garbage => false,
+            Iota::Number(num) => *num != 0.0,
+            Iota::Vector(vector) => !vector.is_zero(),
+            Iota::List(list) => !list.is_empty(),
+            _ => true,
+        }
+    }
+
+    //argument is the position of the iota in the pattern's parameters (the bottom one is 0)
+    pub fn as_number(&self, argument: usize) -> Result<f64, MishapKind> {
+        match self {
+            Iota::Number(num) => Ok(*num),
+            _ => Err(self.incorrect(argument, "number")),
+        }
+    }
+    pub fn as_vector(&self, argument: usize) -> Result<Vector, MishapKind> {
+        match self {
+            Iota::Vector(vector) => Ok(*vector),
+            _ => Err(self.incorrect(argument, "vector")),
+        }
+    }
+    pub fn as_bool(&self, argument: usize) -> Result<bool, MishapKind> {
+        match self {
+            Iota::Boolean(bool) => Ok(*bool),
+            _ => Err(self.incorrect(argument, "bool")),
+        }
+    }
+    pub fn as_list(&self, argument: usize) -> Result<&[Iota], MishapKind> {
+        match self {
+            Iota::List(list) => Ok(list),
+            _ => Err(self.incorrect(argument, "list")),
+        }
+    }
+    pub fn as_entity(&self, argument: usize) -> Result<Entity, MishapKind> {
+        match self {
+            Iota::Entity(entity) => Ok(*entity),
+            _ => Err(self.incorrect(argument, "entity")),
+        }
+    }
+
+    //a number that has to be a whole number (within TOLERANCE)
+    pub fn as_int(&self, argument: usize) -> Result<i64, MishapKind> {
+        let num = self.as_number(argument)?;
+        let rounded = num.round();
+        if (num - rounded).abs() >= TOLERANCE {
+            return Err(self.incorrect(argument, "integer"));
+        }
+        Ok(rounded as i64)
+    }
+
+    pub fn incorrect(&self, argument: usize, expected: &'static str) -> MishapKind {
+        MishapKind::IncorrectIota {
+            argument,
+            expected,
+            found: Box::new(self.clone()),
+        }
+    }
+}
+
+impl From<f64> for Iota {
+    fn from(num: f64) -> Self {
+        Iota::Number(num)
+    }
+}
+
+impl From<Vector> for Iota {
+    fn from(vector: Vector) -> Self {
+        Iota::Vector(vector)
+    }
+}
+
+impl From<bool> for Iota {
+    fn from(bool: bool) -> Self {
+        Iota::Boolean(bool)
+    }
+}
+
+impl From<Pattern> for Iota {
+    fn from(pattern: Pattern) -> Self {
+        Iota::Pattern(pattern)
+    }
+}
+
+impl From<Vec<Iota>> for Iota {
+    fn from(list: Vec<Iota>) -> Self {
+        Iota::List(list)
+    }
+}
+
+impl TryFrom<&parse::Iota> for Iota {
+    type Error = UnknownIota;
+
+    fn try_from(iota: &parse::Iota) -> Result<Self, Self::Error> {
+        Ok(match iota {
+            parse::Iota::Pattern(pattern) => Iota::Pattern(pattern.clone()),
+            parse::Iota::Number(num) => Iota::Number(*num),
+            parse::Iota::Vector(x, y, z) => Iota::Vector(Vector::new(*x, *y, *z)),
+            parse::Iota::String(string) => Iota::String(string.clone()),
+            parse::Iota::List(list) => Iota::List(
+                list.iter()
+                    .map(|parsed| Iota::try_from(&parsed.iota))
+                    .collect::<Result<_, _>>()?,
+            ),
+            parse::Iota::Unknown(text) => return Err(UnknownIota(text.clone())),
+        })
+    }
+}
+
+impl Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl Display for Iota {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Iota::Number(num) => write!(f, "{num}"),
+            Iota::Vector(vector) => write!(f, "{vector}"),
+            Iota::Boolean(bool) => write!(f, "{bool}"),
+            Iota::Null => f.write_str("NULL"),
+            Iota::Garbage => f.write_str("GARBAGE"),
+            Iota::Pattern(pattern) => write!(f, "{pattern}"),
+            Iota::List(list) => {
+                f.write_str("[")?;
+                for (i, iota) in list.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{iota}")?;
+                }
+                f.write_str("]")
+            }
+            Iota::String(string) => write!(f, "\"{string}\""),
+            Iota::Entity(Entity(id)) => write!(f, "Entity({id})"),
+        }
+    }
+}
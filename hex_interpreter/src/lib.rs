@@ -0,0 +1,20 @@
+mod iota;
+pub use iota::{Entity, Iota, UnknownIota, Vector, TOLERANCE};
+
+mod mishap;
+pub use mishap::{Mishap, MishapKind};
+
+mod world;
+pub use world::{stack_size, take_args, EmptyWorld, World};
+
+mod ops;
+
+mod interpreter;
+pub use interpreter::{Interpreter, MAX_IOTAS, MAX_OPS};
+
+//casts the iotas on an empty stack with the builtin patterns and no world
+pub fn cast(iotas: &[Iota]) -> Result<Vec<Iota>, Mishap> {
+    let mut interpreter = Interpreter::new(EmptyWorld);
+    interpreter.run(iotas)?;
+    Ok(interpreter.stack)
+}
@@ -0,0 +1,68 @@
+use std::fmt::{self, Display};
+
+use crate::Iota;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MishapKind {
+    NotEnoughIotas {
+        needed: usize,
+        available: usize,
+    },
+    //the argument is counted from the bottom (the same order as the parameters)
+    IncorrectIota {
+        argument: usize,
+        expected: &'static str,
+        found: Box<Iota>,
+    },
+    DivideByZero,
+    //a pattern that isn't in the registry (and isn't a number or Bookkeeper's Gambit)
+    InvalidPattern,
+    //a known pattern the interpreter (or the World) can't run
+    Unsupported,
+    //a Retrospection without an Introspection
+    HastyRetrospection,
+    //a non-pattern iota that was run without being escaped
+    UnescapedValue(Box<Iota>),
+    TooManyOps,
+    //the stack would hold more than MAX_IOTAS
+    TooManyIotas,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mishap {
+    //the name of the pattern (or the iota) that caused it
+    pub pattern: String,
+    pub kind: MishapKind,
+}
+
+impl Display for MishapKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MishapKind::NotEnoughIotas { needed, available } => {
+                write!(f, "expected {needed} iotas but there were {available}")
+            }
+            MishapKind::IncorrectIota {
+                argument,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {expected} for argument {argument} but got {} ({found})",
+                found.type_name()
+            ),
+            MishapKind::DivideByZero => f.write_str("divided by zero"),
+            MishapKind::InvalidPattern => f.write_str("not a valid pattern"),
+            MishapKind::Unsupported => f.write_str("not supported outside the game"),
+            MishapKind::HastyRetrospection => f.write_str("Retrospection without Introspection"),
+            MishapKind::UnescapedValue(iota) => write!(f, "tried to cast {iota}"),
+            MishapKind::TooManyOps => f.write_str("ran too many patterns"),
+            MishapKind::TooManyIotas => f.write_str("the stack got too big"),
+        }
+    }
+}
+
+impl Display for Mishap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.pattern, self.kind)
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{take_args, Iota, MishapKind};
+
+use super::{binary, constant, index, unary};
+
+pub(super) fn operate(name: &str, stack: &mut Vec<Iota>) -> Option<Result<(), MishapKind>> {
+    let result = match name {
+        "Vacant Reflection" => constant(stack, Vec::new()),
+        "Single's Purification" => unary(stack, |a| Ok(vec![a.clone()].into())),
+        "Abacus Purification" => unary(stack, |a| Ok((a.as_list(0)?.len() as f64).into())),
+        "Retrograde Purification" => unary(stack, |a| {
+            Ok(a.as_list(0)?
+                .iter()
+                .rev()
+                .cloned()
+                .collect::<Vec<_>>()
+                .into())
+        }),
+        //Null if the index is past the end
+        "Selection Distillation" => binary(stack, |a, b| {
+            let list = a.as_list(0)?;
+            let index = b.as_number(1)?.round();
+            Ok(if (0.0..list.len() as f64).contains(&index) {
+                list[index as usize].clone()
+            } else {
+                Iota::Null
+            })
+        }),
+        //the indices can be given in either order
+        "Selection Exaltation" => take_args(stack, 3).and_then(|args| {
+            let list = args[0].as_list(0)?;
+            let start = index(&args[1], 1, list.len())?;
+            let end = index(&args[2], 2, list.len())?;
+            constant(stack, list[start.min(end)..start.max(end)].to_vec())
+        }),
+        "Integration Distillation" => binary(stack, |a, b| {
+            let mut list = a.as_list(0)?.to_vec();
+            list.push(b.clone());
+            Ok(list.into())
+        }),
+        "Speaker's Distillation" => binary(stack, |a, b| {
+            let mut list = a.as_list(0)?.to_vec();
+            list.insert(0, b.clone());
+            Ok(list.into())
+        }),
+        //pushes the rest of the list and then its first iota (Null if it's empty)
+        "Speaker's Decomposition" => take_args(stack, 1).and_then(|args| {
+            let list = args[0].as_list(0)?;
+            let (first, rest) = match list.split_first() {
+                Some((first, rest)) => (first.clone(), rest.to_vec()),
+                None => (Iota::Null, Vec::new()),
+            };
+            stack.extend([rest.into(), first]);
+            Ok(())
+        }),
+        "Combination Distillation" => binary(stack, |a, b| {
+            Ok([a.as_list(0)?, b.as_list(1)?].concat().into())
+        }),
+        //-1 if it isn't in the list
+        "Locator's Distillation" => binary(stack, |a, b| {
+            let found = a.as_list(0)?.iter().position(|iota| iota.tolerates(b));
+            Ok(found.map_or(-1.0, |index| index as f64).into())
+        }),
+        //does nothing if the index is past the end
+        "Excisor's Distillation" => binary(stack, |a, b| {
+            let mut list = a.as_list(0)?.to_vec();
+            let index = b.as_int(1)?;
+            if index < 0 {
+                return Err(b.incorrect(1, "index in range"));
+            }
+            if (index as usize) < list.len() {
+                list.remove(index as usize);
+            }
+            Ok(list.into())
+        }),
+        "Surgeon's Exaltation" => take_args(stack, 3).and_then(|args| {
+            let mut list = args[0].as_list(0)?.to_vec();
+            let index = match list.len() {
+                0 => return Err(args[1].incorrect(1, "index in range")),
+                len => index(&args[1], 1, len - 1)?,
+            };
+            list[index] = args[2].clone();
+            constant(stack, list)
+        }),
+        //takes the number of iotas under the number off the stack and puts them in a list
+        "Flock's Gambit" => take_args(stack, 1).and_then(|args| {
+            let count = args[0].as_int(0)?;
+            if count < 0 {
+                return Err(args[0].incorrect(0, "index in range"));
+            }
+            let list =
+                take_args(stack, count as usize).map_err(|_| MishapKind::NotEnoughIotas {
+                    needed: count as usize + 1,
+                    available: stack.len() + 1,
+                })?;
+            constant(stack, list)
+        }),
+        "Flock's Disintegration" => take_args(stack, 1).and_then(|args| {
+            stack.extend_from_slice(args[0].as_list(0)?);
+            Ok(())
+        }),
+        //set operations on lists and bitwise operations on whole numbers
+        "Unifying Distillation" => set_operation(
+            stack,
+            |a, b| a | b,
+            |a, b| {
+                let mut union = Vec::new();
+                for iota in a.iter().chain(b) {
+                    if !contains(&union, iota) {
+                        union.push(iota.clone());
+                    }
+                }
+                union
+            },
+        ),
+        "Intersection Distillation" => set_operation(
+            stack,
+            |a, b| a & b,
+            |a, b| a.iter().filter(|iota| contains(b, iota)).cloned().collect(),
+        ),
+        "Exclusionary Distillation" => set_operation(
+            stack,
+            |a, b| a ^ b,
+            |a, b| {
+                let only_a = a.iter().filter(|iota| !contains(b, iota));
+                let only_b = b.iter().filter(|iota| !contains(a, iota));
+                only_a.chain(only_b).cloned().collect()
+            },
+        ),
+        "Inversion Purification" => unary(stack, |a| Ok((!a.as_int(0)? as f64).into())),
+        "Uniqueness Purification" => unary(stack, |a| {
+            let mut unique = Vec::new();
+            for iota in a.as_list(0)? {
+                if !contains(&unique, iota) {
+                    unique.push(iota.clone());
+                }
+            }
+            Ok(unique.into())
+        }),
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn contains(list: &[Iota], iota: &Iota) -> bool {
+    list.iter().any(|other| other.tolerates(iota))
+}
+
+fn set_operation(
+    stack: &mut Vec<Iota>,
+    numbers: impl Fn(i64, i64) -> i64,
+    lists: impl Fn(&[Iota], &[Iota]) -> Vec<Iota>,
+) -> Result<(), MishapKind> {
+    binary(stack, |a, b| match a {
+        Iota::List(a) => Ok(lists(a, b.as_list(1)?).into()),
+        _ => {
+            let a = a.as_int(0).map_err(|_| a.incorrect(0, "integer or list"))?;
+            Ok((numbers(a, b.as_int(1)?) as f64).into())
+        }
+    })
+}
@@ -0,0 +1,43 @@
+use crate::{take_args, Iota, MishapKind};
+
+use super::{binary, constant, unary};
+
+pub(super) fn operate(name: &str, stack: &mut Vec<Iota>) -> Option<Result<(), MishapKind>> {
+    let result = match name {
+        "True Reflection" => constant(stack, true),
+        "False Reflection" => constant(stack, false),
+        "Nullary Reflection" => constant(stack, Iota::Null),
+        "Augur's Purification" => unary(stack, |a| Ok(a.is_truthy().into())),
+        "Negation Purification" => unary(stack, |a| Ok((!a.is_truthy()).into())),
+        "Numerologist's Prfn." => {
+            unary(stack, |a| Ok(if a.as_bool(0)? { 1.0 } else { 0.0 }.into()))
+        }
+        "Disjunction Distillation" => {
+            binary(stack, |a, b| Ok((a.as_bool(0)? || b.as_bool(1)?).into()))
+        }
+        "Conjunction Distillation" => {
+            binary(stack, |a, b| Ok((a.as_bool(0)? && b.as_bool(1)?).into()))
+        }
+        "Exclusion Distillation" => {
+            binary(stack, |a, b| Ok((a.as_bool(0)? != b.as_bool(1)?).into()))
+        }
+        //picks the first iota if the bool is true and the second one otherwise
+        "Augur's Exaltation" => take_args(stack, 3).and_then(|mut args| {
+            let choice = if args[0].as_bool(0)? { 1 } else { 2 };
+            stack.push(args.swap_remove(choice));
+            Ok(())
+        }),
+        "Equality Distillation" => binary(stack, |a, b| Ok(a.tolerates(b).into())),
+        "Inequality Distillation" => binary(stack, |a, b| Ok((!a.tolerates(b)).into())),
+        "Maximus Distillation" => compare(stack, |a, b| a > b),
+        "Minimus Distillation" => compare(stack, |a, b| a < b),
+        "Maximus Distillation II" => compare(stack, |a, b| a >= b),
+        "Minimus Distillation II" => compare(stack, |a, b| a <= b),
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn compare(stack: &mut Vec<Iota>, f: impl Fn(f64, f64) -> bool) -> Result<(), MishapKind> {
+    binary(stack, |a, b| Ok(f(a.as_number(0)?, b.as_number(1)?).into()))
+}
@@ -0,0 +1,194 @@
+use std::f64::consts::{E, PI, TAU};
+
+use crate::{take_args, Iota, MishapKind, Vector};
+
+use super::{binary, constant, unary};
+
+const NUM_OR_VEC: &str = "number or vector";
+
+//numbers and vectors mix in most of the arithmetic patterns
+#[derive(Clone, Copy)]
+enum Operand {
+    Number(f64),
+    Vector(Vector),
+}
+
+fn operand(iota: &Iota, argument: usize) -> Result<Operand, MishapKind> {
+    match iota {
+        Iota::Number(num) => Ok(Operand::Number(*num)),
+        Iota::Vector(vector) => Ok(Operand::Vector(*vector)),
+        _ => Err(iota.incorrect(argument, NUM_OR_VEC)),
+    }
+}
+
+fn operands(a: &Iota, b: &Iota) -> Result<(Operand, Operand), MishapKind> {
+    Ok((operand(a, 0)?, operand(b, 1)?))
+}
+
+pub(super) fn operate(name: &str, stack: &mut Vec<Iota>) -> Option<Result<(), MishapKind>> {
+    let result = match name {
+        "Additive Distillation" => binary(stack, |a, b| {
+            Ok(match operands(a, b)? {
+                (Operand::Number(a), Operand::Number(b)) => (a + b).into(),
+                (Operand::Number(a), Operand::Vector(b)) => (Vector::splat(a) + b).into(),
+                (Operand::Vector(a), Operand::Number(b)) => (a + Vector::splat(b)).into(),
+                (Operand::Vector(a), Operand::Vector(b)) => (a + b).into(),
+            })
+        }),
+        "Subtractive Distillation" => binary(stack, |a, b| {
+            Ok(match operands(a, b)? {
+                (Operand::Number(a), Operand::Number(b)) => (a - b).into(),
+                (Operand::Number(a), Operand::Vector(b)) => (Vector::splat(a) - b).into(),
+                (Operand::Vector(a), Operand::Number(b)) => (a - Vector::splat(b)).into(),
+                (Operand::Vector(a), Operand::Vector(b)) => (a - b).into(),
+            })
+        }),
+        //two vectors give the dot product
+        "Multiplicative Dstl." => binary(stack, |a, b| {
+            Ok(match operands(a, b)? {
+                (Operand::Number(a), Operand::Number(b)) => (a * b).into(),
+                (Operand::Number(a), Operand::Vector(b)) => (b * a).into(),
+                (Operand::Vector(a), Operand::Number(b)) => (a * b).into(),
+                (Operand::Vector(a), Operand::Vector(b)) => a.dot(b).into(),
+            })
+        }),
+        //two vectors give the cross product
+        "Division Dstl." => binary(stack, |a, b| {
+            Ok(match operands(a, b)? {
+                (Operand::Number(a), Operand::Number(b)) => (a / nonzero(b)?).into(),
+                (Operand::Number(a), Operand::Vector(b)) => Vector::splat(a)
+                    .zip(nonzero_vector(b)?, |a, b| a / b)
+                    .into(),
+                (Operand::Vector(a), Operand::Number(b)) => {
+                    let b = nonzero(b)?;
+                    a.map(|a| a / b).into()
+                }
+                (Operand::Vector(a), Operand::Vector(b)) => a.cross(b).into(),
+            })
+        }),
+        //two vectors give the projection of the first onto the second
+        "Power Distillation" => binary(stack, |a, b| {
+            Ok(match operands(a, b)? {
+                (Operand::Number(a), Operand::Number(b)) => power(a, b)?.into(),
+                (Operand::Number(a), Operand::Vector(b)) => {
+                    Vector::new(power(a, b.x)?, power(a, b.y)?, power(a, b.z)?).into()
+                }
+                (Operand::Vector(a), Operand::Number(b)) => {
+                    Vector::new(power(a.x, b)?, power(a.y, b)?, power(a.z, b)?).into()
+                }
+                (Operand::Vector(a), Operand::Vector(b)) => {
+                    if b.is_zero() {
+                        return Err(MishapKind::DivideByZero);
+                    }
+                    (b * (a.dot(b) / b.dot(b))).into()
+                }
+            })
+        }),
+        "Length Purification" => unary(stack, |a| {
+            Ok(match operand(a, 0)? {
+                Operand::Number(a) => a.abs().into(),
+                Operand::Vector(a) => a.length().into(),
+            })
+        }),
+        "Axial Purification" => unary(stack, |a| {
+            Ok(match operand(a, 0)? {
+                Operand::Number(0.0) => 0.0.into(),
+                Operand::Number(a) => a.signum().into(),
+                Operand::Vector(a) => axis(a).into(),
+            })
+        }),
+        "Floor Purification" => unary(stack, |a| Ok(a.as_number(0)?.floor().into())),
+        "Ceiling Purification" => unary(stack, |a| Ok(a.as_number(0)?.ceil().into())),
+        "Modulus Distillation" => binary(stack, |a, b| {
+            let (a, b) = (a.as_number(0)?, b.as_number(1)?);
+            Ok((a % nonzero(b)?).into())
+        }),
+        "Vector Exaltation" => take_args(stack, 3).and_then(|args| {
+            let vector = Vector::new(
+                args[0].as_number(0)?,
+                args[1].as_number(1)?,
+                args[2].as_number(2)?,
+            );
+            constant(stack, vector)
+        }),
+        "Vector Disintegration" => take_args(stack, 1).and_then(|args| {
+            let vector = args[0].as_vector(0)?;
+            stack.extend([vector.x, vector.y, vector.z].map(Iota::Number));
+            Ok(())
+        }),
+        "Sine Purification" => unary(stack, |a| Ok(a.as_number(0)?.sin().into())),
+        "Cosine Purification" => unary(stack, |a| Ok(a.as_number(0)?.cos().into())),
+        "Tangent Purification" => unary(stack, |a| {
+            let angle = a.as_number(0)?;
+            if angle.cos() == 0.0 {
+                return Err(MishapKind::DivideByZero);
+            }
+            Ok(angle.tan().into())
+        }),
+        "Inverse Sine Prfn." => unary(stack, |a| Ok(unit(a)?.asin().into())),
+        "Inverse Cosine Prfn." => unary(stack, |a| Ok(unit(a)?.acos().into())),
+        "Inverse Tangent Prfn." => unary(stack, |a| Ok(a.as_number(0)?.atan().into())),
+        "Inverse Tan. Prfn. II" => binary(stack, |y, x| {
+            Ok(y.as_number(0)?.atan2(x.as_number(1)?).into())
+        }),
+        //the number first, then the base
+        "Logarithmic Distillation" => binary(stack, |a, b| {
+            let (value, base) = (a.as_number(0)?, b.as_number(1)?);
+            if value <= 0.0 || base <= 0.0 || base == 1.0 {
+                return Err(MishapKind::DivideByZero);
+            }
+            Ok((value.ln() / base.ln()).into())
+        }),
+        "Vector Reflection Zero" => constant(stack, Vector::ZERO),
+        "Circle's Reflection" => constant(stack, TAU),
+        "Arc's Reflection" => constant(stack, PI),
+        "Euler's Reflection" => constant(stack, E),
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn nonzero(num: f64) -> Result<f64, MishapKind> {
+    if num == 0.0 {
+        return Err(MishapKind::DivideByZero);
+    }
+    Ok(num)
+}
+
+fn nonzero_vector(vector: Vector) -> Result<Vector, MishapKind> {
+    nonzero(vector.x)?;
+    nonzero(vector.y)?;
+    nonzero(vector.z)?;
+    Ok(vector)
+}
+
+fn power(base: f64, exponent: f64) -> Result<f64, MishapKind> {
+    if base == 0.0 && exponent < 0.0 {
+        return Err(MishapKind::DivideByZero);
+    }
+    Ok(base.powf(exponent))
+}
+
+//a number from -1 to 1 for the inverse sine and cosine
+fn unit(iota: &Iota) -> Result<f64, MishapKind> {
+    let num = iota.as_number(0)?;
+    if !(-1.0..=1.0).contains(&num) {
+        return Err(iota.incorrect(0, "number from -1 to 1"));
+    }
+    Ok(num)
+}
+
+//the unit vector along the vector's longest axis
+fn axis(vector: Vector) -> Vector {
+    if vector.is_zero() {
+        return vector;
+    }
+    let (x, y, z) = (vector.x.abs(), vector.y.abs(), vector.z.abs());
+    if x >= y && x >= z {
+        Vector::new(vector.x.signum(), 0.0, 0.0)
+    } else if y >= z {
+        Vector::new(0.0, vector.y.signum(), 0.0)
+    } else {
+        Vector::new(0.0, 0.0, vector.z.signum())
+    }
+}
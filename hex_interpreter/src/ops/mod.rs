@@ -0,0 +1,46 @@
+use crate::{take_args, Iota, MishapKind};
+
+mod lists;
+mod logic;
+mod math;
+mod stack;
+
+//runs one of the patterns that only needs the stack (by its registry name)
+//None if it isn't one of them
+pub(crate) fn operate(name: &str, stack: &mut Vec<Iota>) -> Option<Result<(), MishapKind>> {
+    math::operate(name, stack)
+        .or_else(|| logic::operate(name, stack))
+        .or_else(|| lists::operate(name, stack))
+        .or_else(|| self::stack::operate(name, stack))
+}
+
+fn constant(stack: &mut Vec<Iota>, iota: impl Into<Iota>) -> Result<(), MishapKind> {
+    stack.push(iota.into());
+    Ok(())
+}
+
+fn unary(
+    stack: &mut Vec<Iota>,
+    f: impl Fn(&Iota) -> Result<Iota, MishapKind>,
+) -> Result<(), MishapKind> {
+    let args = take_args(stack, 1)?;
+    stack.push(f(&args[0])?);
+    Ok(())
+}
+
+fn binary(
+    stack: &mut Vec<Iota>,
+    f: impl Fn(&Iota, &Iota) -> Result<Iota, MishapKind>,
+) -> Result<(), MishapKind> {
+    let args = take_args(stack, 2)?;
+    stack.push(f(&args[0], &args[1])?);
+    Ok(())
+}
+
+//a whole number from 0 up to max (inclusive)
+fn index(iota: &Iota, argument: usize, max: usize) -> Result<usize, MishapKind> {
+    match iota.as_int(argument)? {
+        index if (0..=max as i64).contains(&index) => Ok(index as usize),
+        _ => Err(iota.incorrect(argument, "index in range")),
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{stack_size, take_args, Iota, MishapKind, MAX_IOTAS};
+
+use super::constant;
+
+//patterns that only move iotas around, as (name, iotas taken, which of them are put back)
+const SHUFFLES: [(&str, usize, &[usize]); 7] = [
+    ("Jester's Gambit", 2, &[1, 0]),
+    ("Rotation Gambit", 3, &[1, 2, 0]),
+    ("Rotation Gambit II", 3, &[2, 0, 1]),
+    ("Gemini Decomposition", 1, &[0, 0]),
+    ("Prospector's Gambit", 2, &[0, 1, 0]),
+    ("Undertaker's Gambit", 2, &[1, 0, 1]),
+    ("Dioscuri Gambit", 2, &[0, 1, 0, 1]),
+];
+
+pub(super) fn operate(name: &str, stack: &mut Vec<Iota>) -> Option<Result<(), MishapKind>> {
+    if let Some((_, count, order)) = SHUFFLES.iter().find(|(shuffle, ..)| *shuffle == name) {
+        return Some(take_args(stack, *count).map(|args| {
+            stack.extend(order.iter().map(|&i| args[i].clone()));
+        }));
+    }
+
+    let result = match name {
+        "Flock's Reflection" => constant(stack, stack.len() as f64),
+        "Gemini Gambit" => take_args(stack, 2).and_then(|args| {
+            let count = args[1].as_int(1)?;
+            if count < 0 {
+                return Err(args[1].incorrect(1, "index in range"));
+            }
+            //checked before the copies are made since they could take up all the memory
+            let size = (count as usize).saturating_mul(args[0].size());
+            if size.saturating_add(stack_size(stack)) > MAX_IOTAS {
+                return Err(MishapKind::TooManyIotas);
+            }
+            stack.extend(std::iter::repeat_n(args[0].clone(), count as usize));
+            Ok(())
+        }),
+        //1 is the iota under the number, negative numbers move the top iota down to that depth instead
+        "Fisherman's Gambit" => take_args(stack, 1).and_then(|args| {
+            let depth = fishing_depth(stack, &args[0])?;
+            if depth > 0 {
+                let fish = stack.remove(stack.len() - depth.unsigned_abs() as usize);
+                stack.push(fish);
+            } else {
+                let lure = stack.pop().unwrap();
+                stack.insert(stack.len() + 1 - depth.unsigned_abs() as usize, lure);
+            }
+            Ok(())
+        }),
+        //unlike Fisherman's Gambit, 0 is the iota under the number
+        "Fisherman's Gambit II" => take_args(stack, 1).and_then(|args| {
+            let depth = args[0].as_int(0)?;
+            if depth < 0 {
+                return Err(args[0].incorrect(0, "index in range"));
+            }
+            if depth as usize >= stack.len() {
+                return Err(MishapKind::NotEnoughIotas {
+                    needed: depth as usize + 2,
+                    available: stack.len() + 1,
+                });
+            }
+            stack.push(stack[stack.len() - 1 - depth as usize].clone());
+            Ok(())
+        }),
+        //rearranges the iotas under the number into the permutation with that index
+        "Swindler's Gambit" => take_args(stack, 1).and_then(|args| swindle(stack, &args[0])),
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn fishing_depth(stack: &[Iota], iota: &Iota) -> Result<i64, MishapKind> {
+    let depth = iota.as_int(0)?;
+    if depth == 0 {
+        return Err(iota.incorrect(0, "index in range"));
+    }
+    if depth.unsigned_abs() as usize > stack.len() {
+        return Err(MishapKind::NotEnoughIotas {
+            needed: depth.unsigned_abs() as usize + 1,
+            available: stack.len() + 1,
+        });
+    }
+    Ok(depth)
+}
+
+fn swindle(stack: &mut Vec<Iota>, iota: &Iota) -> Result<(), MishapKind> {
+    let code = iota.as_int(0)?;
+    if code < 0 {
+        return Err(iota.incorrect(0, "index in range"));
+    }
+
+    //the factorials up to the code say how many iotas it moves
+    let mut strides = Vec::new();
+    let mut factorial = 1i64;
+    while factorial <= code {
+        strides.push(factorial);
+        //past 20! every code fits in the strides there already are
+        match factorial.checked_mul(strides.len() as i64) {
+            Some(next) => factorial = next,
+            None => break,
+        }
+    }
+
+    let mut remaining =
+        take_args(stack, strides.len()).map_err(|_| MishapKind::NotEnoughIotas {
+            needed: strides.len() + 1,
+            available: stack.len() + 1,
+        })?;
+    let mut radix = code;
+    for stride in strides.iter().rev() {
+        let index = (radix / stride) as usize;
+        radix %= stride;
+        stack.push(remaining.remove(index));
+    }
+    Ok(())
+}
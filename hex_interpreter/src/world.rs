@@ -0,0 +1,45 @@
+use hex_decoder::RegistryPattern;
+
+use crate::{Iota, MishapKind};
+
+//everything that needs the game (entities, blocks, items, spells, randomness, ...)
+//the interpreter only runs the patterns that are pure math and stack manipulation
+//and hands the rest to the World, so tests can stub in whatever they need
+pub trait World {
+    //takes the pattern's arguments off the stack and pushes its results
+    fn operate(
+        &mut self,
+        pattern: &RegistryPattern,
+        stack: &mut Vec<Iota>,
+    ) -> Result<(), MishapKind> {
+        let _ = (pattern, stack);
+        Err(MishapKind::Unsupported)
+    }
+
+    //Reveal, the iota stays on the stack
+    fn reveal(&mut self, iota: &Iota) {
+        let _ = iota;
+    }
+}
+
+//a World where none of the patterns that need the game work
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmptyWorld;
+
+impl World for EmptyWorld {}
+
+//how many iotas are on the stack, counting the ones inside lists
+pub fn stack_size(stack: &[Iota]) -> usize {
+    stack.iter().map(Iota::size).sum()
+}
+
+//takes the top count iotas off the stack, bottom first
+pub fn take_args(stack: &mut Vec<Iota>, count: usize) -> Result<Vec<Iota>, MishapKind> {
+    if count > stack.len() {
+        return Err(MishapKind::NotEnoughIotas {
+            needed: count,
+            available: stack.len(),
+        });
+    }
+    Ok(stack.split_off(stack.len() - count))
+}
@@ -1,5 +1,7 @@
+use hex_renderer::grids::BlockStyle;
 use hex_renderer::grids::GridDraw;
 use hex_renderer::grids::HexGrid;
+use hex_renderer::grids::NestedGrid;
 use hex_renderer::grids::SquareGrid;
 use hex_renderer::pattern_utils;
 use hex_renderer::Pattern;
@@ -102,9 +104,28 @@ fn main() {
     //that should be devoted to padding
     let x_pad = 0.2;
     let y_pad = 0.1;
-    let square_grid =
-        SquareGrid::new_normal(built_pattern_list, max_width, max_scale, x_pad, y_pad)
-            .expect("Failed to make Square Grid!");
+    let square_grid = SquareGrid::new_normal(
+        built_pattern_list.clone(),
+        max_width,
+        max_scale,
+        x_pad,
+        y_pad,
+    )
+    .expect("Failed to make Square Grid!");
+
+    //the nested grid uses the same tiles as the square grid
+    //but puts the contents of each intro/retro block on their own indented rows
+    //and outlines them (with either boxes or brackets)
+    //so the structure of a spell is easier to follow
+    let nested_grid = NestedGrid::new_normal(
+        built_pattern_list,
+        max_width,
+        max_scale,
+        x_pad,
+        0.3,
+        BlockStyle::Boxes,
+    )
+    .expect("Failed to make Nested Grid!");
 
     //now to draw the grid, there are 3 main functions
     //draw_grid -- simply draws the grid to a tiny_skia::Pixmap
@@ -135,6 +156,11 @@ fn main() {
         .draw_grid_to_file("segment_square_grid.png", scale, segment)
         .expect("Unable to write to file!");
 
+    //the nested grid is scaled the same way as the square grid
+    nested_grid
+        .draw_grid_to_file("segment_nested_grid.png", scale, segment)
+        .expect("Unable to write to file!");
+
     //if you would rather draw things based on the size of the final image,
     //you can call grid.get_bound_scale to get the scale that fits
     //within the bounds you have provided
@@ -1,8 +1,8 @@
 use hex_renderer::{
     grids::{GridDraw, HexGrid},
     options::{
-        palettes, CollisionOption, EndPoint, GridOptions, GridPatternOptions, Intersections, Lines,
-        Marker, OverloadOptions, Point, Triangle,
+        palettes, Background, CollisionOption, EndPoint, GridOptions, GridPatternOptions,
+        Intersections, Lines, Marker, OverloadOptions, Point, Triangle,
     },
     pattern_utils::Angle,
     Pattern,
@@ -38,6 +38,13 @@ fn main() {
     //      -- The center dot is a dot put in the center of semi-contained
     //      -- patterns
     //      -- it is defined by a point which will be explained later.
+    //  4. background
+    //      -- what to fill the image with before drawing the patterns
+    //      -- Transparent, Solid(color), Checker or Paper
+    //  5. padding
+    //      -- extra space around the patterns in line lengths
+    //  6. label_color
+    //      -- the color of any labels drawn under or over the patterns
 
     //example
     let _options = GridOptions {
@@ -57,6 +64,12 @@ fn main() {
         ),
         //no center dot
         center_dot: Point::None,
+        //leave the background transparent
+        background: Background::Transparent,
+        //no extra space around the patterns
+        padding: 0.0,
+        //white labels (if the grid has any)
+        label_color: Color::WHITE,
     };
 
     //now, on the next step down, you have the line renderers
@@ -284,6 +297,9 @@ fn main() {
         line_thickness: 0.12,
         pattern_options: GridPatternOptions::Uniform(_end_and_middle_points, _segment.clone()),
         center_dot: _collision_point,
+        background: Background::Transparent,
+        padding: 0.0,
+        label_color: Color::WHITE,
     };
     hex_grid
         .draw_grid_to_file("uniform_options_example.png", 50.0, &_uniform_options)
@@ -312,6 +328,9 @@ fn main() {
             retros: _regress_patterns,
         },
         center_dot: _collision_point,
+        background: Background::Transparent,
+        padding: 0.0,
+        label_color: Color::WHITE,
     };
 
     hex_grid
@@ -351,6 +370,9 @@ fn main() {
         line_thickness: 0.12,
         pattern_options: _changing_gradient,
         center_dot: _single_point,
+        background: Background::Solid(Color::BLACK),
+        padding: 0.2,
+        label_color: Color::WHITE,
     };
 
     hex_grid
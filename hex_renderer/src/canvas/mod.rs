@@ -0,0 +1,55 @@
+mod pixmap_canvas;
+pub use pixmap_canvas::PixmapWindow;
+
+mod svg_canvas;
+pub use svg_canvas::SvgCanvas;
+
+use tiny_skia::{Color, LineCap, LineJoin, Path};
+
+use crate::{pattern::Text, pattern_utils::HexCoord};
+
+/// A surface that patterns can be drawn onto.
+///
+/// Implemented for tiny-skia's `Pixmap` for raster output and for [`SvgCanvas`] for vector output.
+pub trait Canvas {
+    fn fill_path(&mut self, path: &Path, brush: &Brush);
+    fn stroke_path(&mut self, path: &Path, brush: &Brush, stroke: &Stroke);
+
+    /// Draws text, by default by filling in the outlines of its glyphs.
+    fn fill_text(&mut self, text: &Text) {
+        if let Some(path) = text.outline() {
+            self.fill_path(&path, &Brush::Solid(text.color()));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Brush {
+    Solid(Color),
+    //gradients are drawn without anti-aliasing so consecutive segments don't leave seams
+    LinearGradient {
+        start: HexCoord,
+        end: HexCoord,
+        start_color: Color,
+        end_color: Color,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Stroke {
+    pub width: f32,
+    pub line_cap: LineCap,
+    pub line_join: LineJoin,
+    pub dash: Option<Vec<f32>>,
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            line_cap: LineCap::default(),
+            line_join: LineJoin::default(),
+            dash: None,
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use tiny_skia::{
+    FillRule, GradientStop, LinearGradient, Paint, Path, Pixmap, PixmapPaint, SpreadMode,
+    StrokeDash, Transform,
+};
+
+use super::{Brush, Canvas, Stroke};
+use crate::pattern::Text;
+
+impl Canvas for Pixmap {
+    fn fill_path(&mut self, path: &Path, brush: &Brush) {
+        fill_path(self, path, brush, Transform::identity());
+    }
+
+    fn stroke_path(&mut self, path: &Path, brush: &Brush, stroke: &Stroke) {
+        stroke_path(self, path, brush, stroke, Transform::identity());
+    }
+
+    fn fill_text(&mut self, text: &Text) {
+        fill_text(self, text, Transform::identity());
+    }
+}
+
+/// A pixmap that only covers part of a bigger canvas, starting at `(x, y)`.
+///
+/// Everything drawn onto it is moved so that point ends up at the pixmap's top left,
+/// anything outside of the pixmap is clipped.
+pub struct PixmapWindow<'a> {
+    pixmap: &'a mut Pixmap,
+    transform: Transform,
+}
+
+impl<'a> PixmapWindow<'a> {
+    pub fn new(pixmap: &'a mut Pixmap, x: f32, y: f32) -> Self {
+        Self {
+            pixmap,
+            transform: Transform::from_translate(-x, -y),
+        }
+    }
+}
+
+impl Canvas for PixmapWindow<'_> {
+    fn fill_path(&mut self, path: &Path, brush: &Brush) {
+        fill_path(self.pixmap, path, brush, self.transform);
+    }
+
+    fn stroke_path(&mut self, path: &Path, brush: &Brush, stroke: &Stroke) {
+        stroke_path(self.pixmap, path, brush, stroke, self.transform);
+    }
+
+    fn fill_text(&mut self, text: &Text) {
+        fill_text(self.pixmap, text, self.transform);
+    }
+}
+
+fn fill_path(pixmap: &mut Pixmap, path: &Path, brush: &Brush, transform: Transform) {
+    pixmap.fill_path(path, &brush.to_paint(), FillRule::Winding, transform, None);
+}
+
+fn stroke_path(
+    pixmap: &mut Pixmap,
+    path: &Path,
+    brush: &Brush,
+    stroke: &Stroke,
+    transform: Transform,
+) {
+    pixmap.stroke_path(
+        path,
+        &brush.to_paint(),
+        &stroke.to_tiny_skia(),
+        transform,
+        None,
+    );
+}
+
+//text is drawn pixel by pixel instead of from its outlines (which look different when they're this small)
+fn fill_text(pixmap: &mut Pixmap, text: &Text, transform: Transform) {
+    if let Some((x, y, glyphs)) = text.rasterize() {
+        pixmap.draw_pixmap(
+            x,
+            y,
+            glyphs.as_ref(),
+            &PixmapPaint::default(),
+            transform,
+            None,
+        );
+    }
+}
+
+impl Brush {
+    fn to_paint(self) -> Paint<'static> {
+        match self {
+            Brush::Solid(color) => {
+                let mut paint = Paint::default();
+                paint.set_color(color);
+                paint
+            }
+            Brush::LinearGradient {
+                start,
+                end,
+                start_color,
+                end_color,
+            } => Paint {
+                shader: LinearGradient::new(
+                    tiny_skia::Point::from_xy(start.0, start.1),
+                    tiny_skia::Point::from_xy(end.0, end.1),
+                    vec![
+                        GradientStop::new(0.0, start_color),
+                        GradientStop::new(1.0, end_color),
+                    ],
+                    SpreadMode::Pad,
+                    Transform::identity(),
+                )
+                .unwrap(),
+                anti_alias: false,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Stroke {
+    fn to_tiny_skia(&self) -> tiny_skia::Stroke {
+        tiny_skia::Stroke {
+            width: self.width,
+            line_cap: self.line_cap,
+            line_join: self.line_join,
+            dash: self
+                .dash
+                .as_ref()
+                .and_then(|dash| StrokeDash::new(dash.clone(), 0.0)),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::fmt::Write;
+
+use tiny_skia::{Color, LineCap, LineJoin, Path, PathSegment};
+
+use super::{Brush, Canvas, Stroke};
+
+pub struct SvgCanvas {
+    width: f32,
+    height: f32,
+    defs: String,
+    body: String,
+    gradient_count: usize,
+}
+
+impl SvgCanvas {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            defs: String::new(),
+            body: String::new(),
+            gradient_count: 0,
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    //embeds a png image, for things that can only be drawn as pixels
+    pub fn draw_png(&mut self, x: f32, y: f32, width: f32, height: f32, png: &[u8]) {
+        let _ = writeln!(
+            self.body,
+            "<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" href=\"data:image/png;base64,{}\"/>",
+            num(x),
+            num(y),
+            num(width),
+            num(height),
+            base64(png)
+        );
+    }
+
+    pub fn finish(self) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+            num(self.width),
+            num(self.height)
+        );
+        if !self.defs.is_empty() {
+            svg += "<defs>\n";
+            svg += &self.defs;
+            svg += "</defs>\n";
+        }
+        svg += &self.body;
+        svg += "</svg>\n";
+        svg
+    }
+
+    fn paint_attributes(&mut self, brush: &Brush, kind: &str) -> String {
+        match brush {
+            Brush::Solid(color) => color_attributes(kind, &format!("{kind}-opacity"), *color),
+            Brush::LinearGradient {
+                start,
+                end,
+                start_color,
+                end_color,
+            } => {
+                let id = format!("gradient{}", self.gradient_count);
+                self.gradient_count += 1;
+
+                let _ = writeln!(
+                    self.defs,
+                    "<linearGradient id=\"{id}\" gradientUnits=\"userSpaceOnUse\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"><stop offset=\"0\"{}/><stop offset=\"1\"{}/></linearGradient>",
+                    num(start.0),
+                    num(start.1),
+                    num(end.0),
+                    num(end.1),
+                    color_attributes("stop-color", "stop-opacity", *start_color),
+                    color_attributes("stop-color", "stop-opacity", *end_color),
+                );
+                format!(" {kind}=\"url(#{id})\"")
+            }
+        }
+    }
+}
+
+impl Canvas for SvgCanvas {
+    fn fill_path(&mut self, path: &Path, brush: &Brush) {
+        let paint = self.paint_attributes(brush, "fill");
+        let _ = writeln!(self.body, "<path d=\"{}\"{paint}/>", path_data(path));
+    }
+
+    fn stroke_path(&mut self, path: &Path, brush: &Brush, stroke: &Stroke) {
+        let paint = self.paint_attributes(brush, "stroke");
+
+        let line_cap = match stroke.line_cap {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        };
+        let line_join = match stroke.line_join {
+            LineJoin::Miter | LineJoin::MiterClip => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        };
+        let dash = match &stroke.dash {
+            Some(dash) => format!(
+                " stroke-dasharray=\"{}\"",
+                dash.iter()
+                    .map(|len| num(*len))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            None => String::new(),
+        };
+
+        let _ = writeln!(
+            self.body,
+            "<path d=\"{}\" fill=\"none\"{paint} stroke-width=\"{}\" stroke-linecap=\"{line_cap}\" stroke-linejoin=\"{line_join}\"{dash}/>",
+            path_data(path),
+            num(stroke.width),
+        );
+    }
+}
+
+fn path_data(path: &Path) -> String {
+    let mut data = String::new();
+    for segment in path.segments() {
+        let _ = match segment {
+            PathSegment::MoveTo(p) => write!(data, "M{} {}", num(p.x), num(p.y)),
+            PathSegment::LineTo(p) => write!(data, "L{} {}", num(p.x), num(p.y)),
+            PathSegment::QuadTo(p1, p) => write!(
+                data,
+                "Q{} {} {} {}",
+                num(p1.x),
+                num(p1.y),
+                num(p.x),
+                num(p.y)
+            ),
+            PathSegment::CubicTo(p1, p2, p) => write!(
+                data,
+                "C{} {} {} {} {} {}",
+                num(p1.x),
+                num(p1.y),
+                num(p2.x),
+                num(p2.y),
+                num(p.x),
+                num(p.y)
+            ),
+            PathSegment::Close => write!(data, "Z"),
+        };
+    }
+    data
+}
+
+fn color_attributes(color_attr: &str, opacity_attr: &str, color: Color) -> String {
+    let color = color.to_color_u8();
+    let mut attributes = format!(
+        " {color_attr}=\"#{:02x}{:02x}{:02x}\"",
+        color.red(),
+        color.green(),
+        color.blue()
+    );
+    if color.alpha() != 255 {
+        let _ = write!(
+            attributes,
+            " {opacity_attr}=\"{}\"",
+            num(color.alpha() as f32 / 255.0)
+        );
+    }
+    attributes
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let block = chunk.iter().enumerate().fold(0u32, |block, (i, byte)| {
+            block | (*byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(block >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn num(val: f32) -> String {
+    let val = format!("{:.3}", val);
+    let val = val.trim_end_matches('0').trim_end_matches('.');
+    if val == "-0" {
+        "0".to_string()
+    } else {
+        val.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0xff, 0xfe]), "//4=");
+    }
+}
@@ -0,0 +1,234 @@
+use gif::{Encoder, Frame, Repeat};
+use tiny_skia::Pixmap;
+
+use crate::options::GridOptions;
+
+use super::{format::demultiplied, GridDraw, GridDrawError};
+
+//how fast the stroke order is drawn when animating a grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationOptions {
+    //frames per second, every frame draws one more segment
+    pub frame_rate: f32,
+    //how long the finished grid stays up before the animation loops (in seconds)
+    pub hold_time: f32,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self {
+            frame_rate: 10.0,
+            hold_time: 2.0,
+        }
+    }
+}
+
+//how much of a grid has been drawn, everything before the pattern at index pattern
+//is drawn fully and only the first segments of that pattern are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrawProgress {
+    pub pattern: usize,
+    pub segments: usize,
+}
+
+//a frame along with how long it's shown for (in milliseconds)
+struct TimedFrame {
+    pixmap: Pixmap,
+    delay: u32,
+}
+
+//every frame of the animation, one segment at a time and pattern by pattern
+fn draw_frames<'a, G: GridDraw + ?Sized>(
+    grid: &'a G,
+    scale: f32,
+    options: &GridOptions,
+    animation: &AnimationOptions,
+) -> Result<impl ExactSizeIterator<Item = Result<TimedFrame, GridDrawError>> + 'a, GridDrawError> {
+    if !(animation.frame_rate > 0.0 && animation.frame_rate.is_finite()) {
+        return Err(GridDrawError::ImproperFrameRate(animation.frame_rate));
+    }
+    let frame_delay = (1000.0 / animation.frame_rate).round().max(1.0) as u32;
+    let hold_delay = (animation.hold_time.max(0.0) * 1000.0).round() as u32;
+
+    let counts = grid.get_segment_counts();
+    let mut progress: Vec<DrawProgress> = counts
+        .iter()
+        .enumerate()
+        .flat_map(|(pattern, &count)| {
+            (1..=count).map(move |segments| DrawProgress { pattern, segments })
+        })
+        .collect();
+    //the last frame is the finished grid, which includes the chips after the last pattern
+    //(and is the only frame for a grid that has no patterns)
+    progress.pop();
+    progress.push(DrawProgress {
+        pattern: counts.len(),
+        segments: 0,
+    });
+    let last = progress.len() - 1;
+    let options = options.clone();
+
+    Ok(progress.into_iter().enumerate().map(move |(i, progress)| {
+        Ok(TimedFrame {
+            pixmap: grid.draw_grid_frame(scale, &options, progress)?,
+            delay: if i == last {
+                frame_delay.max(hold_delay)
+            } else {
+                frame_delay
+            },
+        })
+    }))
+}
+
+pub(crate) fn encode_gif<G: GridDraw + ?Sized>(
+    grid: &G,
+    scale: f32,
+    options: &GridOptions,
+    animation: &AnimationOptions,
+) -> Result<Vec<u8>, GridDrawError> {
+    let (first, frames) = first_frame(draw_frames(grid, scale, options, animation)?)?;
+
+    let (width, height) = (first.pixmap.width(), first.pixmap.height());
+    //gifs can't be more than 65535 pixels wide or tall
+    let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+        return Err(GridDrawError::EncodeError);
+    };
+
+    let mut data = Vec::new();
+    {
+        let mut encoder =
+            Encoder::new(&mut data, width, height, &[]).map_err(|_| GridDrawError::EncodeError)?;
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(|_| GridDrawError::EncodeError)?;
+
+        for frame in std::iter::once(Ok(first)).chain(frames) {
+            let frame = frame?;
+            let mut pixels = demultiplied(&frame.pixmap);
+            let mut gif_frame = Frame::from_rgba_speed(width, height, &mut pixels, 10);
+            //gif delays are in hundredths of a second
+            gif_frame.delay = (frame.delay / 10).clamp(1, u16::MAX as u32) as u16;
+            encoder
+                .write_frame(&gif_frame)
+                .map_err(|_| GridDrawError::EncodeError)?;
+        }
+    }
+    Ok(data)
+}
+
+pub(crate) fn encode_apng<G: GridDraw + ?Sized>(
+    grid: &G,
+    scale: f32,
+    options: &GridOptions,
+    animation: &AnimationOptions,
+) -> Result<Vec<u8>, GridDrawError> {
+    let frames = draw_frames(grid, scale, options, animation)?;
+    let frame_count = frames.len();
+    let (first, frames) = first_frame(frames)?;
+
+    let (width, height) = (first.pixmap.width(), first.pixmap.height());
+
+    let mut data = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut data, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .set_animated(frame_count as u32, 0)
+            .map_err(|_| GridDrawError::EncodeError)?;
+
+        let mut writer = encoder
+            .write_header()
+            .map_err(|_| GridDrawError::EncodeError)?;
+        for frame in std::iter::once(Ok(first)).chain(frames) {
+            let frame = frame?;
+            writer
+                .set_frame_delay(frame.delay.min(u16::MAX as u32) as u16, 1000)
+                .map_err(|_| GridDrawError::EncodeError)?;
+            writer
+                .write_image_data(&demultiplied(&frame.pixmap))
+                .map_err(|_| GridDrawError::EncodeError)?;
+        }
+        writer.finish().map_err(|_| GridDrawError::EncodeError)?;
+    }
+    Ok(data)
+}
+
+//the size of the animation comes from its first frame
+fn first_frame(
+    mut frames: impl Iterator<Item = Result<TimedFrame, GridDrawError>>,
+) -> Result<
+    (
+        TimedFrame,
+        impl Iterator<Item = Result<TimedFrame, GridDrawError>>,
+    ),
+    GridDrawError,
+> {
+    let first = frames.next().ok_or(GridDrawError::EncodeError)??;
+    Ok((first, frames))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defaults,
+        grids::{GridItem, GridLabels, HexGrid, SquareGrid},
+        parse::{parse_pattern, Iota},
+        pattern::PatternVariant,
+    };
+
+    fn pattern(text: &str) -> GridItem {
+        GridItem::Pattern(PatternVariant::Normal(parse_pattern(text).unwrap()))
+    }
+
+    fn last_frame(grid: &(impl GridDraw + ?Sized)) -> Pixmap {
+        draw_frames(grid, 20.0, &defaults::SEGMENT, &AnimationOptions::default())
+            .unwrap()
+            .last()
+            .unwrap()
+            .unwrap()
+            .pixmap
+    }
+
+    //the chips after the last pattern have to show up once the animation is done
+    #[test]
+    fn last_frame_is_the_whole_grid() {
+        let items = vec![
+            GridItem::Iota(Iota::String("first".to_string())),
+            pattern("HexPattern(EAST qaq)"),
+            GridItem::Iota(Iota::Number(2.0)),
+            pattern("HexPattern(SOUTH_EAST aqaawa)"),
+            GridItem::Iota(Iota::String("last".to_string())),
+        ];
+        let hex = HexGrid::new_items(items.clone(), 10, GridLabels::default()).unwrap();
+        let square =
+            SquareGrid::new_items(items, 10, 1.0, 0.2, 0.1, GridLabels::default()).unwrap();
+
+        for grid in [&hex as &dyn GridDraw, &square] {
+            let whole = grid.draw_grid(20.0, &defaults::SEGMENT).unwrap();
+            assert!(last_frame(grid) == whole);
+        }
+    }
+
+    #[test]
+    fn chips_only_grid() {
+        let items = vec![
+            GridItem::Iota(Iota::Number(1.0)),
+            GridItem::Iota(Iota::String("text".to_string())),
+        ];
+        let grid = HexGrid::new_items(items, 10, GridLabels::default()).unwrap();
+        let animation = AnimationOptions::default();
+
+        let frames = draw_frames(&grid, 20.0, &defaults::SEGMENT, &animation).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert!(last_frame(&grid) == grid.draw_grid(20.0, &defaults::SEGMENT).unwrap());
+
+        assert!(grid
+            .draw_grid_gif(20.0, &defaults::SEGMENT, &animation)
+            .is_ok());
+        assert!(grid
+            .draw_grid_apng(20.0, &defaults::SEGMENT, &animation)
+            .is_ok());
+    }
+}
@@ -0,0 +1,102 @@
+use tiny_skia::{PathBuilder, Rect};
+
+use crate::{
+    canvas::{Brush, Canvas},
+    options::Background,
+};
+
+const GRAIN_PER_CELL: u32 = 24;
+const GRAIN_RADIUS: f32 = 0.008;
+
+//visible is the part of the canvas that's being drawn to, anything outside of it is skipped
+pub fn draw_background(
+    canvas: &mut impl Canvas,
+    background: &Background,
+    width: f32,
+    height: f32,
+    scale: f32,
+    visible: Rect,
+) {
+    let full = match Rect::from_xywh(0.0, 0.0, width, height) {
+        Some(rect) => PathBuilder::from_rect(rect),
+        None => return,
+    };
+
+    match background {
+        Background::Transparent => (),
+        Background::Solid(color) => canvas.fill_path(&full, &Brush::Solid(*color)),
+        Background::Checker { size, colors } => {
+            canvas.fill_path(&full, &Brush::Solid(colors.0));
+
+            let size = size * scale;
+            if size < 1.0 {
+                return;
+            }
+            let mut path = PathBuilder::new();
+            for y in cells(visible.top(), visible.bottom(), size, height) {
+                for x in
+                    cells(visible.left(), visible.right(), size, width).filter(|x| x % 2 == y % 2)
+                {
+                    let x = x as f32 * size;
+                    let y = y as f32 * size;
+                    if let Some(rect) =
+                        Rect::from_xywh(x, y, size.min(width - x), size.min(height - y))
+                    {
+                        path.push_rect(rect);
+                    }
+                }
+            }
+            if let Some(path) = path.finish() {
+                canvas.fill_path(&path, &Brush::Solid(colors.1));
+            }
+        }
+        Background::Paper { color, grain } => {
+            canvas.fill_path(&full, &Brush::Solid(*color));
+
+            //grain is scattered per line length cell so it scales with the patterns
+            let mut path = PathBuilder::new();
+            //grain can spill over into the next cell
+            let (top, bottom) = (visible.top() - scale, visible.bottom() + scale);
+            let (left, right) = (visible.left() - scale, visible.right() + scale);
+            for cell_y in cells(top, bottom, scale, height) {
+                for cell_x in cells(left, right, scale, width) {
+                    for i in 0..GRAIN_PER_CELL {
+                        let seed = hash(cell_x, cell_y, i);
+                        let x = (cell_x as f32 + unit(seed)) * scale;
+                        let y = (cell_y as f32 + unit(seed >> 16)) * scale;
+                        let radius = GRAIN_RADIUS * scale * (0.5 + unit(seed >> 8));
+                        if x < width && y < height {
+                            path.push_circle(x, y, radius);
+                        }
+                    }
+                }
+            }
+            if let Some(path) = path.finish() {
+                canvas.fill_path(&path, &Brush::Solid(*grain));
+            }
+        }
+    }
+}
+
+//the cells of the given size that overlap start to end, stopping at the edge of the canvas
+fn cells(start: f32, end: f32, size: f32, limit: f32) -> std::ops::Range<u32> {
+    let first = (start.max(0.0) / size).floor() as u32;
+    let last = (end.min(limit) / size).ceil() as u32;
+    first..last.max(first)
+}
+
+fn hash(x: u32, y: u32, i: u32) -> u32 {
+    let mut h = x
+        .wrapping_mul(0x9E37_79B1)
+        .wrapping_add(y.wrapping_mul(0x85EB_CA77))
+        .wrapping_add(i.wrapping_mul(0xC2B2_AE3D));
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2C1B_3C6D);
+    h ^= h >> 12;
+    h = h.wrapping_mul(0x297A_2D39);
+    h ^ (h >> 15)
+}
+
+fn unit(seed: u32) -> f32 {
+    (seed & 0xFFFF) as f32 / 65536.0
+}
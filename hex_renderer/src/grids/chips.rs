@@ -0,0 +1,146 @@
+use tiny_skia::{LineCap, LineJoin, Path, PathBuilder};
+
+use crate::{
+    canvas::{Brush, Canvas, Stroke},
+    options::GridOptions,
+    parse::{Iota, ParsedIota},
+    pattern::{draw_label, measure_label, PatternVariant},
+    pattern_utils::HexCoord,
+};
+
+use super::{get_offset, DrawProgress, DEFAULT_LABEL_SIZE};
+
+//the font size of the text on a chip (in the grid's units)
+const CHIP_TEXT_SIZE: f32 = DEFAULT_LABEL_SIZE;
+//the space between the text and the edge of the chip (in the grid's units)
+const CHIP_PADDING: f32 = 0.12;
+
+//something a grid lays out in sequence, either a pattern or any other iota
+//iotas that aren't patterns (strings, numbers, vectors, entities...) are drawn as a chip with their text
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum GridItem {
+    Pattern(PatternVariant),
+    Iota(Iota),
+}
+
+impl From<PatternVariant> for GridItem {
+    fn from(pattern: PatternVariant) -> Self {
+        GridItem::Pattern(pattern)
+    }
+}
+
+impl GridItem {
+    //the contents of lists are laid out in sequence with everything else
+    pub fn from_iotas(iotas: &[ParsedIota]) -> Vec<GridItem> {
+        let mut items = Vec::new();
+        add_iotas(&mut items, iotas);
+        items
+    }
+}
+
+fn add_iotas(items: &mut Vec<GridItem>, iotas: &[ParsedIota]) {
+    for iota in iotas {
+        match &iota.iota {
+            Iota::Pattern(pattern) => {
+                items.push(GridItem::Pattern(PatternVariant::Normal(pattern.clone())))
+            }
+            Iota::List(list) => add_iotas(items, list),
+            other => items.push(GridItem::Iota(other.clone())),
+        }
+    }
+}
+
+//an iota placed in a grid
+#[derive(Debug, Clone)]
+pub(crate) struct Chip {
+    text: String,
+    top_left: HexCoord,
+    size: HexCoord,
+    //how many patterns come before it, chips are drawn along with the pattern after them
+    after: usize,
+}
+
+impl Chip {
+    //the width and height of the chip for the iota (in the grid's units)
+    pub fn get_size(iota: &Iota) -> HexCoord {
+        let (width, height) = measure_label(&get_text(iota), CHIP_TEXT_SIZE);
+        HexCoord(width + CHIP_PADDING * 2.0, height + CHIP_PADDING * 2.0)
+    }
+
+    pub fn new(iota: &Iota, top_left: HexCoord, after: usize) -> Self {
+        Self {
+            text: get_text(iota),
+            top_left,
+            size: Self::get_size(iota),
+            after,
+        }
+    }
+}
+
+fn get_text(iota: &Iota) -> String {
+    iota.to_string()
+}
+
+pub(crate) fn draw_chips(
+    canvas: &mut impl Canvas,
+    chips: &[Chip],
+    options: &GridOptions,
+    scale: f32,
+    progress: Option<DrawProgress>,
+) {
+    let offset = get_offset(options, scale);
+
+    let stroke = Stroke {
+        width: options.line_thickness * scale * 0.5,
+        line_cap: LineCap::Round,
+        line_join: LineJoin::Round,
+        dash: None,
+    };
+
+    for chip in chips {
+        if progress.is_some_and(|progress| chip.after > progress.pattern) {
+            continue;
+        }
+        let top_left = chip.top_left * scale + offset;
+        let size = chip.size * scale;
+
+        if let Some(path) = rounded_rect(top_left, size) {
+            let mut fill = options.label_color;
+            fill.apply_opacity(0.1);
+            canvas.fill_path(&path, &Brush::Solid(fill));
+
+            let mut outline = options.label_color;
+            outline.apply_opacity(0.7);
+            canvas.stroke_path(&path, &Brush::Solid(outline), &stroke);
+        }
+
+        draw_label(
+            canvas,
+            &chip.text,
+            options.label_color,
+            top_left + HexCoord(size.0 / 2.0, CHIP_PADDING * scale),
+            CHIP_TEXT_SIZE * scale,
+        );
+    }
+}
+
+//a rectangle with fully rounded ends
+fn rounded_rect(top_left: HexCoord, size: HexCoord) -> Option<Path> {
+    let radius = size.1.min(size.0) / 2.0;
+    let (left, top) = (top_left.0, top_left.1);
+    let (right, bottom) = (left + size.0, top + size.1);
+
+    let mut builder = PathBuilder::new();
+    builder.move_to(left + radius, top);
+    builder.line_to(right - radius, top);
+    builder.quad_to(right, top, right, top + radius);
+    builder.line_to(right, bottom - radius);
+    builder.quad_to(right, bottom, right - radius, bottom);
+    builder.line_to(left + radius, bottom);
+    builder.quad_to(left, bottom, left, bottom - radius);
+    builder.line_to(left, top + radius);
+    builder.quad_to(left, top, left + radius, top);
+    builder.close();
+    builder.finish()
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+
+use tiny_skia::Pixmap;
+
+use super::{GridDrawError, GridFileError};
+
+//the quality (out of 100) jpegs are saved with
+const JPEG_QUALITY: u8 = 90;
+
+//every extension from_extension understands
+const EXTENSIONS: [&str; 6] = ["png", "svg", "webp", "jpg", "jpeg", "ppm"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Svg,
+    //lossless
+    Webp,
+    //jpeg and ppm have no transparency so the grid is drawn over white
+    Jpeg,
+    Ppm,
+}
+
+impl ImageFormat {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match &extension.to_lowercase()[..] {
+            "png" => Some(ImageFormat::Png),
+            "svg" => Some(ImageFormat::Svg),
+            "webp" => Some(ImageFormat::Webp),
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+            "ppm" => Some(ImageFormat::Ppm),
+            _ => None,
+        }
+    }
+    //png if the path doesn't have an extension, an error if it's not one of the formats
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, GridFileError> {
+        let Some(extension) = path.as_ref().extension() else {
+            return Ok(ImageFormat::Png);
+        };
+        let extension = extension.to_string_lossy();
+        Self::from_extension(&extension).ok_or_else(|| {
+            GridFileError::UnknownFormat(format!(
+                "unknown image format .{extension}, the supported extensions are {}",
+                EXTENSIONS.join(", ")
+            ))
+        })
+    }
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+            ImageFormat::Webp => "webp",
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Ppm => "ppm",
+        }
+    }
+}
+
+//svgs aren't drawn to a pixmap so they're handled by draw_grid_as instead
+pub(crate) fn encode(pixmap: &Pixmap, format: ImageFormat) -> Result<Vec<u8>, GridDrawError> {
+    let (width, height) = (pixmap.width(), pixmap.height());
+    let mut data = Vec::new();
+
+    match format {
+        ImageFormat::Png => return pixmap.encode_png().map_err(|_| GridDrawError::EncodeError),
+        ImageFormat::Svg => return Err(GridDrawError::EncodeError),
+        ImageFormat::Webp => image_webp::WebPEncoder::new(&mut data)
+            .encode(
+                &demultiplied(pixmap),
+                width,
+                height,
+                image_webp::ColorType::Rgba8,
+            )
+            .map_err(|_| GridDrawError::EncodeError)?,
+        ImageFormat::Jpeg => {
+            //jpegs can't be more than u16::MAX pixels across
+            let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+                return Err(GridDrawError::ImproperSize(width, height));
+            };
+            jpeg_encoder::Encoder::new(&mut data, JPEG_QUALITY)
+                .encode(
+                    &over_white(pixmap),
+                    width,
+                    height,
+                    jpeg_encoder::ColorType::Rgb,
+                )
+                .map_err(|_| GridDrawError::EncodeError)?
+        }
+        ImageFormat::Ppm => {
+            data.extend(format!("P6\n{width} {height}\n255\n").as_bytes());
+            data.extend(over_white(pixmap));
+        }
+    }
+    Ok(data)
+}
+
+//pixmaps store premultiplied colors but most formats want them straight
+pub(crate) fn demultiplied(pixmap: &Pixmap) -> Vec<u8> {
+    pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect()
+}
+
+//rgb without alpha, as if the pixmap was drawn over a white background
+fn over_white(pixmap: &Pixmap) -> Vec<u8> {
+    pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let white = 255 - pixel.alpha();
+            [pixel.red(), pixel.green(), pixel.blue()].map(|channel| channel + white)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_from_paths() {
+        assert_eq!(
+            ImageFormat::from_path("grid.PNG").unwrap(),
+            ImageFormat::Png
+        );
+        assert_eq!(
+            ImageFormat::from_path("grid.jpeg").unwrap(),
+            ImageFormat::Jpeg
+        );
+        assert_eq!(
+            ImageFormat::from_path("out/grid.svg").unwrap(),
+            ImageFormat::Svg
+        );
+        //png is only picked when there's no extension at all
+        assert_eq!(ImageFormat::from_path("grid").unwrap(), ImageFormat::Png);
+        assert_eq!(
+            ImageFormat::from_path("out.v2/grid").unwrap(),
+            ImageFormat::Png
+        );
+        assert_eq!(ImageFormat::from_path("-").unwrap(), ImageFormat::Png);
+    }
+
+    #[test]
+    fn unknown_extensions() {
+        for path in ["grid.gif", "grid.bmp", "grid.png.txt"] {
+            let Err(GridFileError::UnknownFormat(message)) = ImageFormat::from_path(path) else {
+                panic!("{path} shouldn't have a format");
+            };
+            assert!(
+                message.contains(path.rsplit('.').next().unwrap()),
+                "{message}"
+            );
+            for extension in EXTENSIONS {
+                assert!(message.contains(extension), "{message}");
+            }
+        }
+    }
+}
@@ -1,18 +1,23 @@
-use tiny_skia::Pixmap;
+use std::collections::HashMap;
 
 use crate::{
-    options::GridOptions,
     pattern::PatternVariant,
     pattern_utils::{Coord, HexCoord},
     Pattern,
 };
 
-use super::{GridCreationError, GridDraw, GridDrawError};
+use super::{
+    Chip, GridContents, GridCreationError, GridItem, GridLabels, GridLayout, Label, LabelPosition,
+    PatternStyle,
+};
 
 #[derive(Debug)]
 pub struct HexGrid {
     patterns: Vec<(PatternVariant, HexCoord, f32)>,
     bottom_right: HexCoord,
+    chips: Vec<Chip>,
+    labels: Vec<Label>,
+    styles: HashMap<usize, PatternStyle>,
 }
 
 impl HexGrid {
@@ -23,11 +28,48 @@ impl HexGrid {
         )
     }
     pub fn new(patterns: Vec<PatternVariant>, max_width: usize) -> Result<Self, GridCreationError> {
-        if patterns.is_empty() {
+        Self::new_labeled(patterns, max_width, GridLabels::default())
+    }
+    //rows are spread out to fit the labels between them and patterns in a row
+    //are pushed apart until their labels don't overlap
+    pub fn new_labeled(
+        patterns: Vec<PatternVariant>,
+        max_width: usize,
+        labels: GridLabels,
+    ) -> Result<Self, GridCreationError> {
+        Self::new_items(
+            patterns.into_iter().map(GridItem::Pattern).collect(),
+            max_width,
+            labels,
+        )
+    }
+    //iotas that aren't patterns are packed in between the patterns as chips
+    //(labels and styles still count patterns only)
+    pub fn new_items(
+        items: Vec<GridItem>,
+        max_width: usize,
+        labels: GridLabels,
+    ) -> Result<Self, GridCreationError> {
+        if items.is_empty() {
             return Err(GridCreationError::EmptyPatternList);
         } else if max_width < 1 {
             return Err(GridCreationError::NegativeInput);
         }
+        let footprints: Vec<Footprint> = items.iter().map(Footprint::new).collect();
+        //which pattern each item is (None for chips)
+        let pattern_indices: Vec<Option<usize>> = items
+            .iter()
+            .scan(0, |count, item| {
+                Some(match item {
+                    GridItem::Pattern(_) => {
+                        *count += 1;
+                        Some(*count - 1)
+                    }
+                    GridItem::Iota(_) => None,
+                })
+            })
+            .collect();
+
         let mut locations = Vec::new();
 
         let max_width = max_width as f32;
@@ -41,8 +83,21 @@ impl HexGrid {
 
         let mut offset_left = true;
 
-        for index in 0..patterns.len() {
-            let pattern = &patterns[index].get_inner();
+        //the extra lattice rows left between rows of patterns for the labels
+        let label_rows = if labels.is_empty() {
+            0
+        } else {
+            ((labels.get_space() + labels.get_gap()) / HexCoord::get_y(1) - 1.0)
+                .ceil()
+                .max(0.0) as i32
+        };
+        //the top and height of each row, and the row each item ends up in
+        let mut rows = Vec::new();
+        let mut item_rows = Vec::new();
+        let mut prev_label_right: Option<f32> = None;
+
+        for index in 0..items.len() {
+            let pattern = &footprints[index];
             let height = pattern.bottom_right.1 - pattern.top_left.1;
 
             if index == 0 {
@@ -61,7 +116,7 @@ impl HexGrid {
                     offset_left = false;
                 }
             } else {
-                let prev_pattern = patterns[index - 1].get_inner();
+                let prev_pattern = &footprints[index - 1];
                 let mut max_distance_decrease = i32::MAX;
                 for i in 0..pattern
                     .left_perimiter
@@ -83,11 +138,29 @@ impl HexGrid {
                 current_x -= max_distance_decrease - 1;
             }
 
-            if HexCoord::from(Coord(current_x + pattern.bottom_right.0, max_y_row)).0 > max_width
+            let label_width = pattern_indices[index].and_then(|i| labels.get_width(i));
+            let label_center = |current_x: i32, current_x_offset: i32| {
+                let loc = Coord(current_x + current_x_offset, current_y - pattern.top_left.1);
+                pattern.get_center(loc)
+            };
+            if let (Some(width), Some(prev_right)) = (label_width, prev_label_right) {
+                let left = label_center(current_x, current_x_offset) - width / 2.0;
+                let overlap = prev_right + labels.get_gap() - left;
+                if overlap > 0.0 {
+                    current_x += overlap.ceil() as i32;
+                }
+            }
+
+            if (HexCoord::from(Coord(current_x + pattern.bottom_right.0, max_y_row)).0 > max_width
+                || label_width.is_some_and(|width| {
+                    label_center(current_x, current_x_offset) + width / 2.0 > max_width
+                }))
                 && index != 0
             {
                 current_x = -pattern.top_left.0;
-                current_y += max_y_row + 1;
+                rows.push((current_y, max_y_row));
+                current_y += max_y_row + 1 + label_rows;
+                prev_label_right = None;
 
                 let mut left_most = f32::MAX;
                 for point in &pattern.left_perimiter {
@@ -105,7 +178,7 @@ impl HexGrid {
 
                 max_y_row = 0;
 
-                for point in &patterns[index - 1].get_inner().right_perimiter {
+                for point in &footprints[index - 1].right_perimiter {
                     let point = HexCoord::from(*point + locations[index - 1]);
                     if point.0 > max_x {
                         max_x = point.0;
@@ -119,11 +192,16 @@ impl HexGrid {
 
             let loc = Coord(current_x + current_x_offset, current_y - pattern.top_left.1);
             locations.push(loc);
+            item_rows.push(rows.len());
+            if let Some(width) = label_width {
+                prev_label_right = Some(pattern.get_center(loc) + width / 2.0);
+            }
         }
+        rows.push((current_y, max_y_row));
 
         if current_y == 0 {
-            let index = patterns.len() - 1;
-            for point in &patterns[index].get_inner().right_perimiter {
+            let index = items.len() - 1;
+            for point in &footprints[index].right_perimiter {
                 let point = HexCoord::from(*point + locations[index]);
                 if point.0 > max_x {
                     max_x = point.0;
@@ -136,27 +214,176 @@ impl HexGrid {
             left_offset.0 = 0.5;
         }
 
+        let mut placed_labels = Vec::new();
+        for (i, pattern_index) in pattern_indices.iter().enumerate() {
+            let Some(pattern_index) = pattern_index else {
+                continue;
+            };
+            let (row_top, row_height) = rows[item_rows[i]];
+            placed_labels.extend(labels.place(
+                *pattern_index,
+                footprints[i].get_center(locations[i]) - left_offset.0,
+                HexCoord::get_y(row_top),
+                HexCoord::get_y(row_top + row_height),
+            ));
+        }
+
+        //labels sticking out past the left or top of the patterns push everything over
+        let label_offset = HexCoord(
+            placed_labels
+                .iter()
+                .map(|label| -label.left())
+                .fold(0.0, f32::max),
+            match labels.position {
+                LabelPosition::Below => 0.0,
+                LabelPosition::Above => labels.get_space(),
+            },
+        );
+        for label in &mut placed_labels {
+            label.translate(label_offset);
+        }
+        let labels_right = placed_labels
+            .iter()
+            .map(Label::right)
+            .fold(f32::MIN, f32::max);
+
         let mut packed_patterns = Vec::new();
-        for (i, pattern) in patterns.into_iter().enumerate() {
-            let location = HexCoord::from(locations[i]) - left_offset;
-            packed_patterns.push((pattern, location, 1.0));
+        let mut chips = Vec::new();
+        for (i, item) in items.into_iter().enumerate() {
+            let location = HexCoord::from(locations[i]) - left_offset + label_offset;
+            match item {
+                GridItem::Pattern(pattern) => packed_patterns.push((pattern, location, 1.0)),
+                GridItem::Iota(iota) => {
+                    //centered in the rows it takes up
+                    let height =
+                        HexCoord::get_y(footprints[i].bottom_right.1 - footprints[i].top_left.1);
+                    let top = (height - Chip::get_size(&iota).1) / 2.0;
+                    chips.push(Chip::new(
+                        &iota,
+                        location + HexCoord(0.0, top),
+                        packed_patterns.len(),
+                    ));
+                }
+            }
         }
 
         Ok(HexGrid {
             patterns: packed_patterns,
             bottom_right: HexCoord(
-                max_x - left_offset.0,
-                HexCoord::get_y(current_y + max_y_row),
+                (max_x - left_offset.0 + label_offset.0).max(labels_right),
+                HexCoord::get_y(current_y + max_y_row) + labels.get_space(),
             ),
+            chips,
+            labels: placed_labels,
+            styles: HashMap::new(),
         })
     }
 }
 
-impl GridDraw for HexGrid {
-    fn draw_grid(&self, scale: f32, options: &GridOptions) -> Result<Pixmap, GridDrawError> {
-        super::draw_grid(self.bottom_right, &self.patterns, options, scale)
+impl HexGrid {
+    //overrides how the pattern at index is drawn (see PatternStyle)
+    pub fn set_pattern_style(
+        &mut self,
+        index: usize,
+        style: PatternStyle,
+    ) -> Result<(), GridCreationError> {
+        super::set_pattern_style(&mut self.styles, &self.patterns, index, style)
+    }
+}
+
+impl GridLayout for HexGrid {
+    fn contents(&self) -> GridContents<'_> {
+        GridContents {
+            size: self.bottom_right,
+            patterns: &self.patterns,
+            blocks: &[],
+            chips: &self.chips,
+            labels: &self.labels,
+            styles: &self.styles,
+        }
     }
-    fn get_unpadded_size(&self) -> (f32, f32) {
-        (self.bottom_right.0, self.bottom_right.1)
+}
+
+//the lattice points an item takes up, so items can be packed as close together as possible
+//(perimiters hold the left/right most point in each row, from the top down)
+struct Footprint {
+    top_left: Coord,
+    bottom_right: Coord,
+    left_perimiter: Vec<Coord>,
+    right_perimiter: Vec<Coord>,
+    //the horizontal middle of the item relative to its location
+    center: f32,
+}
+
+impl Footprint {
+    fn new(item: &GridItem) -> Self {
+        match item {
+            GridItem::Pattern(pattern) => {
+                let pattern = pattern.get_inner();
+                Self {
+                    top_left: pattern.top_left,
+                    bottom_right: pattern.bottom_right,
+                    left_perimiter: pattern.left_perimiter.clone(),
+                    right_perimiter: pattern.right_perimiter.clone(),
+                    center: (pattern.top_left_bound.0 + pattern.bottom_right_bound.0) / 2.0,
+                }
+            }
+            //chips cover every lattice point in a rectangle starting at Coord(0, 0)
+            GridItem::Iota(iota) => {
+                let size = Chip::get_size(iota);
+                let rows = (size.1 / HexCoord::get_y(1)).ceil() as i32 + 1;
+
+                let left_perimiter: Vec<Coord> = (0..rows)
+                    .map(|y| Coord((-y as f32 / 2.0).floor() as i32, y))
+                    .collect();
+                let right_perimiter: Vec<Coord> = (0..rows)
+                    .map(|y| Coord((size.0 - y as f32 / 2.0).ceil() as i32, y))
+                    .collect();
+
+                Self {
+                    top_left: Coord(left_perimiter.iter().map(|c| c.0).min().unwrap(), 0),
+                    bottom_right: Coord(
+                        right_perimiter.iter().map(|c| c.0).max().unwrap(),
+                        rows - 1,
+                    ),
+                    left_perimiter,
+                    right_perimiter,
+                    center: size.0 / 2.0,
+                }
+            }
+        }
+    }
+
+    //the horizontal middle of the item when drawn at loc
+    fn get_center(&self, loc: Coord) -> f32 {
+        HexCoord::from(loc).0 + self.center
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_pattern;
+
+    #[test]
+    fn wide_labels_dont_overlap() {
+        let patterns = (0..6)
+            .map(|_| PatternVariant::Normal(parse_pattern("HexPattern(EAST w)").unwrap()))
+            .collect();
+        let texts = (0..6)
+            .map(|i| Some(format!("Numerical Reflection: {}", i * 1111)))
+            .collect();
+        //a lot wider than the patterns they belong to
+        let labels = GridLabels::new(texts).with_size(0.5);
+        let gap = labels.get_gap();
+        //wide enough that they all end up in one row
+        let grid = HexGrid::new_labeled(patterns, 1000, labels).unwrap();
+
+        assert_eq!(grid.labels.len(), 6);
+        for pair in grid.labels.windows(2) {
+            assert!(pair[0].right() + gap <= pair[1].left() + 1e-4);
+        }
+        assert!(grid.labels[0].left() >= 0.0);
+        assert!(grid.labels[5].right() <= grid.bottom_right.0 + 1e-4);
     }
 }
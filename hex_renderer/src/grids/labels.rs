@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use crate::{
+    canvas::Canvas,
+    options::GridOptions,
+    pattern::{draw_label, measure_label},
+    pattern_utils::HexCoord,
+};
+
+use super::{get_offset, DrawProgress, PatternStyle};
+
+//the font size labels are drawn with unless changed (in the grid's units)
+pub const DEFAULT_LABEL_SIZE: f32 = 0.25;
+//the space between a label and its pattern (or the next label) as a fraction of the font size
+const LABEL_GAP: f32 = 0.8;
+
+//captions drawn under or over the patterns of a HexGrid or SquareGrid
+//the grid leaves enough room for them that they never overlap other patterns or labels
+#[derive(Debug, Clone)]
+pub struct GridLabels {
+    //one for each pattern (in order), None or a missing entry leaves the pattern without a label
+    pub texts: Vec<Option<String>>,
+    pub position: LabelPosition,
+    //the font size, in the grid's units (line lengths for HexGrid and cells for SquareGrid)
+    pub size: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LabelPosition {
+    #[default]
+    Below,
+    Above,
+}
+
+impl Default for GridLabels {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl GridLabels {
+    pub fn new(texts: Vec<Option<String>>) -> Self {
+        Self {
+            texts,
+            position: LabelPosition::Below,
+            size: DEFAULT_LABEL_SIZE,
+        }
+    }
+    //numbers the patterns in the order they were given, starting at 0
+    pub fn indices(count: usize) -> Self {
+        Self::new((0..count).map(|i| Some(i.to_string())).collect())
+    }
+
+    pub fn with_position(mut self, position: LabelPosition) -> Self {
+        self.position = position;
+        self
+    }
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&str> {
+        self.texts
+            .get(index)
+            .and_then(Option::as_deref)
+            .filter(|text| !text.is_empty())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        (0..self.texts.len()).all(|index| self.get(index).is_none())
+    }
+
+    pub(crate) fn get_gap(&self) -> f32 {
+        self.size * LABEL_GAP
+    }
+
+    //the width of the label for the pattern at index
+    pub(crate) fn get_width(&self, index: usize) -> Option<f32> {
+        self.get(index).map(|text| measure_label(text, self.size).0)
+    }
+
+    //how much room a row of labels takes up, including the gap to its patterns
+    pub(crate) fn get_space(&self) -> f32 {
+        if self.is_empty() {
+            0.0
+        } else {
+            self.get_gap() + measure_label("", self.size).1
+        }
+    }
+
+    //places the label of the pattern at index, centered horizontally on center
+    //and under bottom (or over top) of the row the pattern is in
+    pub(crate) fn place(&self, index: usize, center: f32, top: f32, bottom: f32) -> Option<Label> {
+        let text = self.get(index)?;
+        let (width, height) = measure_label(text, self.size);
+        let y = match self.position {
+            LabelPosition::Below => bottom + self.get_gap(),
+            LabelPosition::Above => top - self.get_gap() - height,
+        };
+        Some(Label {
+            index,
+            text: text.to_string(),
+            top: HexCoord(center, y),
+            width,
+            size: self.size,
+        })
+    }
+}
+
+//a label placed in a grid
+#[derive(Debug, Clone)]
+pub(crate) struct Label {
+    index: usize,
+    text: String,
+    //the middle of the label's top edge
+    top: HexCoord,
+    width: f32,
+    size: f32,
+}
+
+impl Label {
+    pub fn left(&self) -> f32 {
+        self.top.0 - self.width / 2.0
+    }
+    pub fn right(&self) -> f32 {
+        self.top.0 + self.width / 2.0
+    }
+    pub fn translate(&mut self, offset: HexCoord) {
+        self.top = self.top + offset;
+    }
+}
+
+//labels are only drawn once the pattern they belong to has been started
+pub(crate) fn draw_labels(
+    canvas: &mut impl Canvas,
+    labels: &[Label],
+    styles: &HashMap<usize, PatternStyle>,
+    options: &GridOptions,
+    scale: f32,
+    progress: Option<DrawProgress>,
+) {
+    let offset = get_offset(options, scale);
+
+    for label in labels {
+        if progress.is_some_and(|progress| label.index > progress.pattern) {
+            continue;
+        }
+        let color = styles
+            .get(&label.index)
+            .map_or(options.label_color, |style| {
+                style.adjust_color(options.label_color)
+            });
+        draw_label(
+            canvas,
+            &label.text,
+            color,
+            label.top * scale + offset,
+            label.size * scale,
+        );
+    }
+}
@@ -4,13 +4,52 @@ pub use hex_grid::HexGrid;
 mod square_grid;
 pub use square_grid::SquareGrid;
 
-use std::{collections::HashSet, fs, io};
+mod nested_grid;
+use nested_grid::{draw_blocks, BlockOutline};
+pub use nested_grid::{BlockStyle, GridNode, NestedGrid};
 
-use tiny_skia::Pixmap;
+mod background;
+use background::draw_background;
+
+mod animation;
+pub use animation::{AnimationOptions, DrawProgress};
+
+#[cfg(feature = "parallel")]
+mod parallel;
+
+mod tiles;
+use tiles::{new_pixmap, MAX_TILE_MARGIN, TILE_MARGIN};
+pub use tiles::{Tile, TileRegion, DEFAULT_TILE_SIZE, MAX_PIXMAP_BYTES};
+
+mod format;
+pub use format::ImageFormat;
+
+mod chips;
+pub use chips::GridItem;
+use chips::{draw_chips, Chip};
+
+mod labels;
+use labels::{draw_labels, Label};
+pub use labels::{GridLabels, LabelPosition, DEFAULT_LABEL_SIZE};
+
+mod style;
+use style::draw_halo;
+pub use style::{Halo, PatternStyle};
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Write},
+};
+
+use tiny_skia::{IntRect, Pixmap, Rect};
 
 use crate::{
-    options::{GridOptions, GridPatternOptions, Intersections, Lines},
-    pattern::PatternVariant,
+    canvas::{Canvas, PixmapWindow, SvgCanvas},
+    options::{
+        CollisionOption, GridOptions, GridPatternOptions, Intersections, Lines, OverloadOptions,
+    },
+    pattern::{Pattern, PatternVariant},
     pattern_utils::HexCoord,
 };
 
@@ -25,27 +64,96 @@ pub enum GridError {
 pub enum GridFileError {
     SaveError(io::Error),
     DrawError(GridDrawError),
+    //the file's extension isn't one of the image formats (the message lists the ones that are)
+    UnknownFormat(String),
 }
 #[derive(Debug)]
 pub enum GridDrawError {
     ImproperScale(f32),
+    ImproperFrameRate(f32),
+    ImproperTileSize(u32),
+    //the canvas is empty or too big for a single pixmap (see MAX_PIXMAP_BYTES, draw it in tiles instead)
+    ImproperSize(u32, u32),
     EncodeError,
 }
 #[derive(Debug)]
 pub enum GridCreationError {
     NegativeInput,
     EmptyPatternList,
+    //there's no pattern at the index a style was given for
+    PatternOutOfRange(usize),
 }
 
 pub trait GridDraw {
     fn draw_grid(&self, scale: f32, options: &GridOptions) -> Result<Pixmap, GridDrawError>;
+    //grids that can't be drawn as vectors have their pixmap embedded in the svg instead
+    fn draw_grid_svg(&self, scale: f32, options: &GridOptions) -> Result<String, GridDrawError> {
+        let pixmap = self.draw_grid(scale, options)?;
+        let png = pixmap
+            .encode_png()
+            .map_err(|_| GridDrawError::EncodeError)?;
+
+        let (width, height) = (pixmap.width() as f32, pixmap.height() as f32);
+        let mut svg = SvgCanvas::new(width, height);
+        svg.draw_png(0.0, 0.0, width, height, &png);
+        Ok(svg.finish())
+    }
+    //draws the grid with only part of it finished (see DrawProgress), used for animations
+    //grids that can't be drawn part way show up finished in every frame
+    fn draw_grid_frame(
+        &self,
+        scale: f32,
+        options: &GridOptions,
+        progress: DrawProgress,
+    ) -> Result<Pixmap, GridDrawError> {
+        let _ = progress;
+        self.draw_grid(scale, options)
+    }
+
+    //draws only the given part of the grid's canvas, for grids too big to fit in one pixmap
+    //(the tile is drawn with a margin of the canvas around it so it matches draw_grid,
+    //apart from the odd edge pixel whose anti-aliasing can be off by a shade)
+    //grids that can only be drawn whole have the tile cut out of the whole canvas
+    fn draw_grid_tile(
+        &self,
+        scale: f32,
+        options: &GridOptions,
+        region: TileRegion,
+    ) -> Result<Pixmap, GridDrawError> {
+        let pixmap = self.draw_grid(scale, options)?;
+        IntRect::from_xywh(
+            region.x as i32,
+            region.y as i32,
+            region.width,
+            region.height,
+        )
+        .and_then(|rect| pixmap.clone_rect(rect))
+        .ok_or(GridDrawError::ImproperSize(region.width, region.height))
+    }
+
+    //how many segments are in each pattern, in the order they're drawn
+    //(none means the grid is animated as a single frame)
+    fn get_segment_counts(&self) -> Vec<usize> {
+        Vec::new()
+    }
 
     fn get_unpadded_size(&self) -> (f32, f32);
     fn get_size(&self, options: &GridOptions) -> (f32, f32) {
-        let max_radius = options.get_max_radius();
+        let border_size = options.get_border_size();
+
+        let size = self.get_unpadded_size();
+        (border_size * 2.0 + size.0, border_size * 2.0 + size.1)
+    }
+
+    //the size of the canvas (in pixels) when drawn at the given scale
+    fn get_pixel_size(&self, scale: f32, options: &GridOptions) -> (u32, u32) {
+        let border_size = options.get_border_size() * scale;
 
         let size = self.get_unpadded_size();
-        (max_radius * 2.0 + size.0, max_radius * 2.0 + size.1)
+        (
+            (border_size * 2.0 + size.0 * scale) as u32,
+            (border_size * 2.0 + size.1 * scale) as u32,
+        )
     }
 
     fn get_bound_scale(&self, bound: (f32, f32), options: &GridOptions) -> f32 {
@@ -60,156 +168,716 @@ pub trait GridDraw {
             .map_err(|_| GridDrawError::EncodeError)
     }
 
+    //the bytes of an image file in the given format
+    fn draw_grid_as(
+        &self,
+        format: ImageFormat,
+        scale: f32,
+        options: &GridOptions,
+    ) -> Result<Vec<u8>, GridDrawError> {
+        match format {
+            ImageFormat::Svg => Ok(self.draw_grid_svg(scale, options)?.into_bytes()),
+            format => format::encode(&self.draw_grid(scale, options)?, format),
+        }
+    }
+
+    //draws the grid tile by tile (left to right, top to bottom), handing each one to on_tile
+    //as soon as it's done so the whole canvas never has to fit in memory
+    fn draw_grid_tiles(
+        &self,
+        scale: f32,
+        options: &GridOptions,
+        tile_size: u32,
+        on_tile: &mut dyn FnMut(Tile) -> Result<(), GridFileError>,
+    ) -> Result<(), GridFileError> {
+        tiles::draw_tiles(self, scale, options, tile_size, on_tile)
+    }
+
+    //animates the grid being drawn one segment at a time
+    fn draw_grid_gif(
+        &self,
+        scale: f32,
+        options: &GridOptions,
+        animation: &AnimationOptions,
+    ) -> Result<Vec<u8>, GridDrawError> {
+        animation::encode_gif(self, scale, options, animation)
+    }
+
+    fn draw_grid_apng(
+        &self,
+        scale: f32,
+        options: &GridOptions,
+        animation: &AnimationOptions,
+    ) -> Result<Vec<u8>, GridDrawError> {
+        animation::encode_apng(self, scale, options, animation)
+    }
+
+    //the format comes from the file's extension (see ImageFormat::from_path)
     fn draw_grid_to_file(
         &self,
         file_name: &str,
         scale: f32,
         options: &GridOptions,
+    ) -> Result<(), GridFileError> {
+        let format = ImageFormat::from_path(file_name)?;
+        fs::write(
+            file_name,
+            self.draw_grid_as(format, scale, options)
+                .map_err(GridFileError::DrawError)?,
+        )
+        .map_err(GridFileError::SaveError)
+    }
+
+    //for streaming the image somewhere other than a file (like stdout)
+    fn draw_grid_to_writer(
+        &self,
+        writer: &mut dyn Write,
+        format: ImageFormat,
+        scale: f32,
+        options: &GridOptions,
+    ) -> Result<(), GridFileError> {
+        writer
+            .write_all(
+                &self
+                    .draw_grid_as(format, scale, options)
+                    .map_err(GridFileError::DrawError)?,
+            )
+            .map_err(GridFileError::SaveError)
+    }
+
+    fn draw_grid_svg_to_file(
+        &self,
+        file_name: &str,
+        scale: f32,
+        options: &GridOptions,
+    ) -> Result<(), GridFileError> {
+        fs::write(
+            file_name,
+            self.draw_grid_svg(scale, options)
+                .map_err(GridFileError::DrawError)?,
+        )
+        .map_err(GridFileError::SaveError)
+    }
+
+    //writes a deep zoom tile pyramid ({dir}/{zoom}/{column}/{row}.png) and returns the highest zoom level,
+    //zoom 0 is the whole grid in one tile and each level after it doubles the size up to the full scale
+    fn draw_grid_tiles_to_dir(
+        &self,
+        dir: &str,
+        scale: f32,
+        options: &GridOptions,
+        tile_size: u32,
+    ) -> Result<u32, GridFileError> {
+        tiles::draw_pyramid(self, dir, scale, options, tile_size)
+    }
+
+    fn draw_grid_gif_to_file(
+        &self,
+        file_name: &str,
+        scale: f32,
+        options: &GridOptions,
+        animation: &AnimationOptions,
     ) -> Result<(), GridFileError> {
         fs::write(
             file_name,
-            self.draw_grid_png(scale, options)
+            self.draw_grid_gif(scale, options, animation)
+                .map_err(GridFileError::DrawError)?,
+        )
+        .map_err(GridFileError::SaveError)
+    }
+
+    fn draw_grid_apng_to_file(
+        &self,
+        file_name: &str,
+        scale: f32,
+        options: &GridOptions,
+        animation: &AnimationOptions,
+    ) -> Result<(), GridFileError> {
+        fs::write(
+            file_name,
+            self.draw_grid_apng(scale, options, animation)
                 .map_err(GridFileError::DrawError)?,
         )
         .map_err(GridFileError::SaveError)
     }
 }
 
-fn draw_grid(
+//everything a grid draws, as laid out by the grid
+struct GridContents<'a> {
     size: HexCoord,
-    patterns: &Vec<(PatternVariant, HexCoord, f32)>,
+    patterns: &'a [(PatternVariant, HexCoord, f32)],
+    blocks: &'a [BlockOutline],
+    chips: &'a [Chip],
+    labels: &'a [Label],
+    styles: &'a HashMap<usize, PatternStyle>,
+}
+
+//the grids in this crate, which are all drawn the same way from what they laid out
+trait GridLayout {
+    fn contents(&self) -> GridContents<'_>;
+}
+
+//overrides how the pattern at index (in the order they were given) is drawn,
+//a halo reaching past the edge of the grid is cut off unless the border is made bigger
+fn set_pattern_style(
+    styles: &mut HashMap<usize, PatternStyle>,
+    patterns: &[(PatternVariant, HexCoord, f32)],
+    index: usize,
+    style: PatternStyle,
+) -> Result<(), GridCreationError> {
+    if index >= patterns.len() {
+        return Err(GridCreationError::PatternOutOfRange(index));
+    }
+    styles.insert(index, style);
+    Ok(())
+}
+
+impl<T: GridLayout> GridDraw for T {
+    fn draw_grid(&self, scale: f32, options: &GridOptions) -> Result<Pixmap, GridDrawError> {
+        draw_grid(self.contents(), options, scale, None, None)
+    }
+    fn draw_grid_svg(&self, scale: f32, options: &GridOptions) -> Result<String, GridDrawError> {
+        draw_grid_svg(self.contents(), options, scale)
+    }
+    fn draw_grid_frame(
+        &self,
+        scale: f32,
+        options: &GridOptions,
+        progress: DrawProgress,
+    ) -> Result<Pixmap, GridDrawError> {
+        draw_grid(self.contents(), options, scale, Some(progress), None)
+    }
+    fn draw_grid_tile(
+        &self,
+        scale: f32,
+        options: &GridOptions,
+        region: TileRegion,
+    ) -> Result<Pixmap, GridDrawError> {
+        draw_grid(self.contents(), options, scale, None, Some(region))
+    }
+    fn get_segment_counts(&self) -> Vec<usize> {
+        self.contents()
+            .patterns
+            .iter()
+            .map(|(pattern, _, _)| pattern.get_inner().path.len() - 1)
+            .collect()
+    }
+    fn get_unpadded_size(&self) -> (f32, f32) {
+        let size = self.contents().size;
+        (size.0, size.1)
+    }
+}
+
+fn draw_grid(
+    contents: GridContents,
     options: &GridOptions,
     scale: f32,
+    progress: Option<DrawProgress>,
+    region: Option<TileRegion>,
 ) -> Result<Pixmap, GridDrawError> {
     if scale < 1.0 {
         return Err(GridDrawError::ImproperScale(scale));
     }
+    let GridContents {
+        size,
+        patterns,
+        blocks,
+        chips,
+        labels,
+        styles,
+    } = contents;
+    let (width, height) = get_canvas_size(size, options, scale);
 
-    let intersections;
-    let lines;
-    let intro_angles;
-    let retro_angles;
+    //tiles are drawn with part of the canvas around them that's cut off at the end
+    let tile = region;
+    let region = match tile {
+        Some(tile) => tile.with_margin(
+            ((TILE_MARGIN * scale).ceil() as u32).min(MAX_TILE_MARGIN),
+            (width as u32, height as u32),
+        ),
+        None => TileRegion {
+            x: 0,
+            y: 0,
+            width: width as u32,
+            height: height as u32,
+        },
+    };
+    let mut pixmap = new_pixmap(region.width, region.height)?;
+    let visible = Rect::from_xywh(
+        region.x as f32,
+        region.y as f32,
+        region.width as f32,
+        region.height as f32,
+    )
+    .ok_or(GridDrawError::ImproperSize(region.width, region.height))?;
 
-    match &options.pattern_options {
-        GridPatternOptions::Uniform(inter, lin) => {
-            intersections = vec![inter];
-            lines = vec![lin];
-            intro_angles = vec![];
-            retro_angles = vec![];
-        }
-        GridPatternOptions::Changing {
-            variations,
-            intros,
-            retros,
-        } => {
-            (intersections, lines) = variations.iter().map(|a| (&a.0, &a.1)).unzip();
-            intro_angles = intros.clone();
-            retro_angles = retros.clone();
-        }
+    let mut canvas = PixmapWindow::new(&mut pixmap, region.x as f32, region.y as f32);
+
+    draw_background(
+        &mut canvas,
+        &options.background,
+        width,
+        height,
+        scale,
+        visible,
+    );
+    draw_blocks(
+        &mut canvas,
+        blocks,
+        options,
+        get_offset(options, scale),
+        scale,
+    );
+    draw_chips(&mut canvas, chips, options, scale, progress);
+    #[cfg(not(feature = "parallel"))]
+    draw_patterns(
+        &mut canvas,
+        patterns,
+        styles,
+        options,
+        scale,
+        progress,
+        Some(visible),
+    );
+    #[cfg(feature = "parallel")]
+    parallel::draw_patterns(
+        &mut pixmap,
+        (region.x, region.y),
+        patterns,
+        styles,
+        options,
+        scale,
+        progress,
+    );
+    #[cfg(feature = "parallel")]
+    let mut canvas = PixmapWindow::new(&mut pixmap, region.x as f32, region.y as f32);
+
+    draw_labels(&mut canvas, labels, styles, options, scale, progress);
+
+    match tile {
+        Some(tile) => IntRect::from_xywh(
+            (tile.x - region.x) as i32,
+            (tile.y - region.y) as i32,
+            tile.width,
+            tile.height,
+        )
+        .and_then(|rect| pixmap.clone_rect(rect))
+        .ok_or(GridDrawError::ImproperSize(tile.width, tile.height)),
+        None => Ok(pixmap),
+    }
+}
+
+fn draw_grid_svg(
+    contents: GridContents,
+    options: &GridOptions,
+    scale: f32,
+) -> Result<String, GridDrawError> {
+    if scale < 1.0 {
+        return Err(GridDrawError::ImproperScale(scale));
     }
-    let (intros, retros) = {
-        let mut intros = HashSet::new();
-        let mut retros = HashSet::new();
-        for intro in intro_angles {
-            intros.insert(intro);
+    let GridContents {
+        size,
+        patterns,
+        blocks,
+        chips,
+        labels,
+        styles,
+    } = contents;
+    let (width, height) = get_canvas_size(size, options, scale);
+
+    let mut svg = SvgCanvas::new(width.floor(), height.floor());
+
+    let visible = Rect::from_xywh(0.0, 0.0, width, height)
+        .ok_or(GridDrawError::ImproperSize(width as u32, height as u32))?;
+
+    draw_background(&mut svg, &options.background, width, height, scale, visible);
+    draw_blocks(&mut svg, blocks, options, get_offset(options, scale), scale);
+    draw_chips(&mut svg, chips, options, scale, None);
+    draw_patterns(&mut svg, patterns, styles, options, scale, None, None);
+    draw_labels(&mut svg, labels, styles, options, scale, None);
+
+    Ok(svg.finish())
+}
+
+fn get_canvas_size(size: HexCoord, options: &GridOptions, scale: f32) -> (f32, f32) {
+    let border_size = options.get_border_size() * scale;
+
+    (
+        border_size * 2.0 + size.0 * scale,
+        border_size * 2.0 + size.1 * scale,
+    )
+}
+
+fn get_offset(options: &GridOptions, scale: f32) -> HexCoord {
+    let border_size = options.get_border_size() * scale;
+    HexCoord(border_size, border_size)
+}
+
+fn draw_patterns(
+    canvas: &mut impl Canvas,
+    patterns: &[(PatternVariant, HexCoord, f32)],
+    overrides: &HashMap<usize, PatternStyle>,
+    options: &GridOptions,
+    scale: f32,
+    progress: Option<DrawProgress>,
+    visible: Option<Rect>,
+) {
+    let styles = PatternStyles::new(options);
+    let offset = get_offset(options, scale);
+
+    for (i, ((pattern, location, local_scale), style)) in patterns
+        .iter()
+        .zip(palette_indices(patterns, options))
+        .enumerate()
+    {
+        if progress.is_some_and(|progress| i > progress.pattern) {
+            break;
         }
-        for retro in retro_angles {
-            retros.insert(retro);
+        let location = *location * scale + offset;
+        let scale = scale * *local_scale;
+        let pattern_style = overrides.get(&i);
+
+        //patterns outside of the tile being drawn are skipped
+        if visible.is_some_and(|visible| {
+            pattern_bounds(pattern.get_inner(), pattern_style, location, scale, options)
+                .intersect(&visible)
+                .is_none()
+        }) {
+            continue;
         }
-        (intros, retros)
+
+        match progress {
+            Some(progress) if i == progress.pattern => styles.draw(
+                canvas,
+                &pattern.truncated(progress.segments),
+                style,
+                pattern_style,
+                location,
+                scale,
+            ),
+            _ => styles.draw(canvas, pattern, style, pattern_style, location, scale),
+        }
+    }
+}
+
+//which of the Changing variations each pattern is drawn with,
+//Introspection moves to the next one after itself and Retrospection moves back one (including itself)
+//this always runs over every pattern in order, even when only some of them are drawn
+fn palette_indices(
+    patterns: &[(PatternVariant, HexCoord, f32)],
+    options: &GridOptions,
+) -> Vec<usize> {
+    let GridPatternOptions::Changing {
+        variations,
+        intros,
+        retros,
+    } = &options.pattern_options
+    else {
+        return vec![0; patterns.len()];
     };
+    let intros: HashSet<_> = intros.iter().collect();
+    let retros: HashSet<_> = retros.iter().collect();
 
-    let monocolor_lines = lines
+    let mut lines_index = 0;
+    patterns
         .iter()
-        .map(|line| Lines::Monocolor {
-            bent: false,
-            color: match line {
-                Lines::Monocolor { color, bent: _ } => *color,
-                Lines::Gradient {
-                    colors,
-                    segments_per_color: _,
-                    bent: _,
-                } => colors[0],
-                Lines::SegmentColors {
-                    colors,
-                    triangles: _,
-                    collisions: _,
-                } => colors[0],
-            },
+        .map(|(pattern, _, _)| {
+            let angles = &pattern.get_inner().angles;
+            let index;
+            if intros.contains(angles) {
+                index = lines_index;
+                lines_index = (lines_index + 1) % variations.len();
+            } else {
+                if retros.contains(angles) {
+                    lines_index = (lines_index + variations.len() - 1) % variations.len();
+                }
+                index = lines_index;
+            }
+            index
         })
-        .collect::<Vec<Lines>>();
+        .collect()
+}
 
-    let monocolor_intersections = intersections
-        .iter()
-        .map(|intersection| match intersection {
-            Intersections::Nothing => Intersections::Nothing,
-            Intersections::UniformPoints(point) => Intersections::UniformPoints(*point),
-            Intersections::EndsAndMiddle {
-                start: _,
-                end: _,
-                middle,
-            } => Intersections::UniformPoints(*middle),
-        })
-        .collect::<Vec<Intersections>>();
+//the lines and intersections for each of the Changing variations (just one when Uniform)
+struct PatternStyles<'a> {
+    options: &'a GridOptions,
+    lines: Vec<&'a Lines>,
+    intersections: Vec<&'a Intersections>,
+    //Monocolor patterns keep the variation's color but not the rest of its style
+    monocolor_lines: Vec<Lines>,
+    monocolor_intersections: Vec<Intersections>,
+}
 
-    let max_radius = options.get_max_radius();
+impl<'a> PatternStyles<'a> {
+    fn new(options: &'a GridOptions) -> Self {
+        let (intersections, lines): (Vec<_>, Vec<_>) = match &options.pattern_options {
+            GridPatternOptions::Uniform(intersections, lines) => (vec![intersections], vec![lines]),
+            GridPatternOptions::Changing { variations, .. } => {
+                variations.iter().map(|a| (&a.0, &a.1)).unzip()
+            }
+        };
 
-    let border_size = max_radius * scale;
+        let monocolor_lines = lines
+            .iter()
+            .map(|line| Lines::Monocolor {
+                bent: false,
+                color: line.get_main_color(),
+            })
+            .collect();
 
-    let offset = HexCoord(border_size, border_size);
+        let monocolor_intersections = intersections
+            .iter()
+            .map(|intersection| match intersection {
+                Intersections::Nothing => Intersections::Nothing,
+                Intersections::UniformPoints(point) => Intersections::UniformPoints(*point),
+                Intersections::EndsAndMiddle {
+                    start: _,
+                    end: _,
+                    middle,
+                } => Intersections::UniformPoints(*middle),
+            })
+            .collect();
 
-    let mut pixmap = Pixmap::new(
-        (border_size * 2.0 + size.0 * scale) as u32,
-        (border_size * 2.0 + size.1 * scale) as u32,
-    )
-    .unwrap();
+        Self {
+            options,
+            lines,
+            intersections,
+            monocolor_lines,
+            monocolor_intersections,
+        }
+    }
 
-    let mut lines_index = 0;
+    //whether the pattern is drawn with a gradient
+    #[cfg(feature = "parallel")]
+    fn is_gradient(
+        &self,
+        pattern: &PatternVariant,
+        index: usize,
+        pattern_style: Option<&PatternStyle>,
+    ) -> bool {
+        matches!(
+            self.get(pattern, index, pattern_style).0,
+            Lines::Gradient { .. }
+        )
+    }
 
-    let mut increment = false;
+    //the lines and intersections the pattern is drawn with, an override is used even for Monocolor patterns
+    fn get(
+        &self,
+        pattern: &PatternVariant,
+        index: usize,
+        pattern_style: Option<&'a PatternStyle>,
+    ) -> (&Lines, &Intersections) {
+        let (lines, intersections) = match pattern {
+            PatternVariant::Normal(_) => (self.lines[index], self.intersections[index]),
+            PatternVariant::Monocolor(_) => (
+                &self.monocolor_lines[index],
+                &self.monocolor_intersections[index],
+            ),
+        };
+        match pattern_style {
+            Some(pattern_style) => (
+                pattern_style.lines.as_ref().unwrap_or(lines),
+                pattern_style
+                    .intersections
+                    .as_ref()
+                    .unwrap_or(intersections),
+            ),
+            None => (lines, intersections),
+        }
+    }
 
-    for (pattern, location, local_scale) in patterns {
-        let location = *location * scale + offset;
+    fn draw(
+        &self,
+        canvas: &mut impl Canvas,
+        pattern: &PatternVariant,
+        index: usize,
+        pattern_style: Option<&'a PatternStyle>,
+        location: HexCoord,
+        scale: f32,
+    ) {
+        let (lines, intersections) = self.get(pattern, index, pattern_style);
+        let inner = pattern.get_inner();
+        let line_thickness = self.options.line_thickness;
 
-        if intros.contains(&pattern.get_inner().angles) {
-            increment = true;
-        } else if retros.contains(&pattern.get_inner().angles) {
-            if lines_index == 0 {
-                lines_index = lines.len() - 1;
-            } else {
-                lines_index -= 1;
-            }
+        let Some(pattern_style) = pattern_style else {
+            inner.draw_pattern(
+                canvas,
+                location,
+                scale,
+                line_thickness,
+                lines,
+                intersections,
+                &self.options.center_dot,
+            );
+            return;
+        };
+
+        let adjust = |color| pattern_style.adjust_color(color);
+        if let Some(halo) = &pattern_style.halo {
+            let halo = Halo {
+                color: adjust(halo.color),
+                ..*halo
+            };
+            draw_halo(canvas, inner, location, scale, line_thickness, &halo);
         }
-        match pattern {
-            PatternVariant::Normal(pattern) => {
-                pattern.draw_pattern(
-                    &mut pixmap,
-                    location,
-                    scale * *local_scale,
-                    options.line_thickness,
-                    lines[lines_index],
-                    intersections[lines_index],
-                    &options.center_dot,
-                );
-            }
-            PatternVariant::Monocolor(pattern) => {
-                pattern.draw_pattern(
-                    &mut pixmap,
-                    location,
-                    scale * *local_scale,
-                    options.line_thickness,
-                    &monocolor_lines[lines_index],
-                    &monocolor_intersections[lines_index],
-                    &options.center_dot,
-                );
-            }
+        if pattern_style.changes_colors() {
+            inner.draw_pattern(
+                canvas,
+                location,
+                scale,
+                line_thickness,
+                &lines.map_colors(&adjust),
+                &intersections.map_colors(&adjust),
+                &self.options.center_dot.map_colors(&adjust),
+            );
+        } else {
+            inner.draw_pattern(
+                canvas,
+                location,
+                scale,
+                line_thickness,
+                lines,
+                intersections,
+                &self.options.center_dot,
+            );
+        }
+    }
+}
+
+//everything the pattern drawn at location could touch
+fn pattern_bounds(
+    pattern: &Pattern,
+    pattern_style: Option<&PatternStyle>,
+    location: HexCoord,
+    scale: f32,
+    options: &GridOptions,
+) -> Rect {
+    let overhang = pattern_style.map_or(0.0, |pattern_style| {
+        pattern_style.get_max_radius(options.line_thickness)
+    });
+    //tiny-skia rounds a path's bounds out by a couple of pixels before deciding whether it needs clipping
+    let margin = get_overhang(options).max(overhang) * scale + 3.0;
+    let top_left = location + pattern.top_left_bound * scale - HexCoord(margin, margin);
+    let bottom_right = location + pattern.bottom_right_bound * scale + HexCoord(margin, margin);
+
+    Rect::from_ltrb(top_left.0, top_left.1, bottom_right.0, bottom_right.1).unwrap()
+}
+
+//how far lines, points and collision labels can stick out past a pattern's bounds (in line lengths)
+fn get_overhang(options: &GridOptions) -> f32 {
+    let lines: Vec<&Lines> = match &options.pattern_options {
+        GridPatternOptions::Uniform(_, lines) => vec![lines],
+        GridPatternOptions::Changing { variations, .. } => {
+            variations.iter().map(|variation| &variation.1).collect()
         }
+    };
+    lines
+        .into_iter()
+        .map(|lines| get_label_overhang(lines, options.line_thickness))
+        .fold(options.get_max_radius(), f32::max)
+}
+
+//how far the labels on overloaded collisions can reach past the lines (in line lengths)
+fn get_label_overhang(lines: &Lines, line_thickness: f32) -> f32 {
+    match lines {
+        Lines::SegmentColors {
+            collisions:
+                CollisionOption::OverloadedParallel {
+                    overload: OverloadOptions::LabeledDashes { label, .. },
+                    ..
+                },
+            ..
+        } => line_thickness / 2.0 + label.radius * 2.0,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{defaults, parse::parse_pattern};
+    use tiny_skia::{Paint, Transform};
+
+    //a grid from outside the crate that only knows how to draw pixmaps
+    struct PixelGrid;
 
-        if increment {
-            increment = false;
-            lines_index = (lines_index + 1) % lines.len();
+    impl GridDraw for PixelGrid {
+        fn draw_grid(&self, scale: f32, options: &GridOptions) -> Result<Pixmap, GridDrawError> {
+            let (width, height) = self.get_pixel_size(scale, options);
+            let mut pixmap = new_pixmap(width, height)?;
+            //the left half is filled in so tiles can be told apart
+            let half = Rect::from_xywh(0.0, 0.0, (width / 2) as f32, height as f32).unwrap();
+            pixmap.fill_rect(half, &Paint::default(), Transform::identity(), None);
+            Ok(pixmap)
         }
+        fn get_unpadded_size(&self) -> (f32, f32) {
+            (4.0, 3.0)
+        }
+    }
+
+    #[test]
+    fn svg_embeds_the_pixmap() {
+        let svg = PixelGrid.draw_grid_svg(10.0, &defaults::SEGMENT).unwrap();
+        let (width, height) = PixelGrid.get_pixel_size(10.0, &defaults::SEGMENT);
+
+        assert!(svg.contains(&format!("width=\"{width}\" height=\"{height}\"")));
+        assert!(svg.contains("<image x=\"0\" y=\"0\""));
+        assert!(svg.contains("href=\"data:image/png;base64,iVBORw0KGgo"));
+    }
+
+    //without segment counts the animation is just the finished grid
+    #[test]
+    fn animates_as_one_frame() {
+        let pixmap = PixelGrid.draw_grid(10.0, &defaults::SEGMENT).unwrap();
+        let apng = PixelGrid
+            .draw_grid_apng(10.0, &defaults::SEGMENT, &AnimationOptions::default())
+            .unwrap();
+
+        let reader = png::Decoder::new(apng.as_slice()).read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height), (pixmap.width(), pixmap.height()));
+        assert_eq!(info.animation_control.unwrap().num_frames, 1);
     }
 
-    Ok(pixmap)
+    #[test]
+    fn tiles_are_cut_out_of_the_pixmap() {
+        let pixmap = PixelGrid.draw_grid(10.0, &defaults::SEGMENT).unwrap();
+        let region = TileRegion {
+            x: pixmap.width() / 2 - 5,
+            y: 3,
+            width: 10,
+            height: 8,
+        };
+        let tile = PixelGrid
+            .draw_grid_tile(10.0, &defaults::SEGMENT, region)
+            .unwrap();
+
+        assert_eq!((tile.width(), tile.height()), (10, 8));
+        assert_eq!(tile.pixel(4, 0).unwrap().alpha(), 255);
+        assert_eq!(tile.pixel(5, 0).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn styled_patterns_are_drawn_differently() {
+        let patterns = vec![
+            parse_pattern("HexPattern(EAST qaq)").unwrap(),
+            parse_pattern("HexPattern(EAST aa)").unwrap(),
+        ];
+        let mut grid = HexGrid::new_normal(patterns, 10).unwrap();
+        let plain = grid.draw_grid(20.0, &defaults::GRADIENT).unwrap();
+
+        let style = PatternStyle {
+            greyscale: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            grid.set_pattern_style(2, style.clone()),
+            Err(GridCreationError::PatternOutOfRange(2))
+        ));
+        assert_eq!(grid.draw_grid(20.0, &defaults::GRADIENT).unwrap(), plain);
+
+        grid.set_pattern_style(1, style).unwrap();
+        assert_ne!(grid.draw_grid(20.0, &defaults::GRADIENT).unwrap(), plain);
+    }
 }
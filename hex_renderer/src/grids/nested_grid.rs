@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+
+use tiny_skia::{LineCap, LineJoin, PathBuilder, Rect};
+
+use crate::{
+    canvas::{Brush, Canvas, Stroke},
+    options::{GridOptions, GridPatternOptions},
+    parse::{Iota, ParsedIota},
+    pattern::PatternVariant,
+    pattern_utils::{Angle, HexCoord},
+    Pattern,
+};
+
+use super::{Chip, GridContents, GridCreationError, GridItem, GridLayout, PatternStyle};
+
+//how far each nesting level is indented (in cells)
+const INDENT: f32 = 0.5;
+//the largest gap left between a block's outline and its contents (in cells)
+const BLOCK_MARGIN: f32 = 0.2;
+//length of the top and bottom strokes of a bracket (in cells)
+const BRACKET_TICK: f32 = 0.15;
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum GridNode {
+    Pattern(PatternVariant),
+    //anything that isn't a pattern, drawn as a chip
+    Iota(Iota),
+    //open/close are the Introspection/Retrospection patterns (None for plain lists)
+    Block {
+        open: Option<PatternVariant>,
+        contents: Vec<GridNode>,
+        close: Option<PatternVariant>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockStyle {
+    Boxes,
+    Brackets,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct BlockOutline {
+    top_left: HexCoord,
+    bottom_right: HexCoord,
+    depth: usize,
+    style: BlockStyle,
+}
+
+//lays patterns out in rows like SquareGrid, but every nested block
+//starts on a new, indented row and gets outlined
+#[derive(Debug)]
+pub struct NestedGrid {
+    patterns: Vec<(PatternVariant, HexCoord, f32)>,
+    blocks: Vec<BlockOutline>,
+    chips: Vec<Chip>,
+    size: HexCoord,
+    styles: HashMap<usize, PatternStyle>,
+}
+
+impl GridNode {
+    //pairs up intro and retro patterns into blocks
+    pub fn from_patterns(
+        patterns: Vec<PatternVariant>,
+        intros: &[Vec<Angle>],
+        retros: &[Vec<Angle>],
+    ) -> Vec<GridNode> {
+        nest(patterns.into_iter().map(GridNode::Pattern), intros, retros)
+    }
+
+    //lists become blocks of their own, anything else that isn't a pattern becomes a chip
+    pub fn from_iotas(
+        iotas: &[ParsedIota],
+        intros: &[Vec<Angle>],
+        retros: &[Vec<Angle>],
+    ) -> Vec<GridNode> {
+        let nodes = iotas.iter().map(|iota| match &iota.iota {
+            Iota::Pattern(pattern) => GridNode::Pattern(PatternVariant::Normal(pattern.clone())),
+            Iota::List(list) => GridNode::Block {
+                open: None,
+                contents: GridNode::from_iotas(list, intros, retros),
+                close: None,
+            },
+            other => GridNode::Iota(other.clone()),
+        });
+        nest(nodes, intros, retros)
+    }
+}
+
+fn nest(
+    nodes: impl Iterator<Item = GridNode>,
+    intros: &[Vec<Angle>],
+    retros: &[Vec<Angle>],
+) -> Vec<GridNode> {
+    let mut stack: Vec<(Option<PatternVariant>, Vec<GridNode>)> = vec![(None, Vec::new())];
+
+    for node in nodes {
+        match node {
+            GridNode::Pattern(pattern) if intros.contains(&pattern.get_inner().angles) => {
+                stack.push((Some(pattern), Vec::new()));
+            }
+            GridNode::Pattern(pattern)
+                if stack.len() > 1 && retros.contains(&pattern.get_inner().angles) =>
+            {
+                let (open, contents) = stack.pop().unwrap();
+                stack.last_mut().unwrap().1.push(GridNode::Block {
+                    open,
+                    contents,
+                    close: Some(pattern),
+                });
+            }
+            node => stack.last_mut().unwrap().1.push(node),
+        }
+    }
+
+    //unclosed intros still get their own block
+    while stack.len() > 1 {
+        let (open, contents) = stack.pop().unwrap();
+        stack.last_mut().unwrap().1.push(GridNode::Block {
+            open,
+            contents,
+            close: None,
+        });
+    }
+
+    stack.pop().unwrap().1
+}
+
+//chips take up as many cells as their text needs
+fn get_cells(item: &GridItem, cell_width: f32) -> usize {
+    match item {
+        GridItem::Pattern(_) => 1,
+        GridItem::Iota(iota) => {
+            let x_pad = cell_width - 1.0;
+            ((Chip::get_size(iota).0 + x_pad) / cell_width)
+                .ceil()
+                .max(1.0) as usize
+        }
+    }
+}
+
+struct Row {
+    depth: usize,
+    items: Vec<GridItem>,
+    //how many cells the items take up
+    cells: usize,
+}
+
+struct Layout {
+    rows: Vec<Row>,
+    //(depth, first row, last row)
+    blocks: Vec<(usize, usize, usize)>,
+    max_width: f32,
+    cell_width: f32,
+}
+
+impl Layout {
+    fn row_width(&self, depth: usize, count: usize) -> f32 {
+        depth as f32 * INDENT + count as f32 * self.cell_width
+    }
+
+    fn new_row(&mut self, depth: usize) {
+        let row = self.rows.last_mut().unwrap();
+        if row.items.is_empty() {
+            row.depth = depth;
+        } else {
+            self.rows.push(Row {
+                depth,
+                items: Vec::new(),
+                cells: 0,
+            });
+        }
+    }
+
+    fn push(&mut self, item: GridItem, depth: usize) {
+        let cells = get_cells(&item, self.cell_width);
+        let row = self.rows.last().unwrap();
+        if row.depth != depth || self.row_width(depth, row.cells + cells) > self.max_width {
+            self.new_row(depth);
+        }
+        let row = self.rows.last_mut().unwrap();
+        row.items.push(item);
+        row.cells += cells;
+    }
+
+    fn add_nodes(&mut self, nodes: Vec<GridNode>, depth: usize) {
+        for node in nodes {
+            match node {
+                GridNode::Pattern(pattern) => self.push(GridItem::Pattern(pattern), depth),
+                GridNode::Iota(iota) => self.push(GridItem::Iota(iota), depth),
+                GridNode::Block {
+                    open,
+                    contents,
+                    close,
+                } => {
+                    if let Some(open) = open {
+                        self.push(GridItem::Pattern(open), depth);
+                    }
+
+                    self.new_row(depth + 1);
+                    let first_row = self.rows.len() - 1;
+
+                    self.add_nodes(contents, depth + 1);
+
+                    //empty blocks don't get outlined
+                    if !self.rows[first_row].items.is_empty() {
+                        //nested blocks can leave an empty row behind
+                        let last_row = (first_row..self.rows.len())
+                            .rev()
+                            .find(|&row| !self.rows[row].items.is_empty())
+                            .unwrap();
+                        self.blocks.push((depth, first_row, last_row));
+                    }
+
+                    self.new_row(depth);
+                    if let Some(close) = close {
+                        self.push(GridItem::Pattern(close), depth);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl NestedGrid {
+    pub fn new_normal(
+        patterns: Vec<Pattern>,
+        max_width: usize,
+        max_scale: f32,
+        x_pad: f32,
+        y_pad: f32,
+        style: BlockStyle,
+    ) -> Result<Self, GridCreationError> {
+        let nodes = GridNode::from_patterns(
+            patterns.into_iter().map(PatternVariant::Normal).collect(),
+            &crate::defaults::INTRO_ANGLES,
+            &crate::defaults::RETRO_ANGLES,
+        );
+        Self::new(nodes, max_width, max_scale, x_pad, y_pad, style)
+    }
+    pub fn new(
+        nodes: Vec<GridNode>,
+        max_width: usize,
+        max_scale: f32,
+        x_pad: f32,
+        y_pad: f32,
+        style: BlockStyle,
+    ) -> Result<Self, GridCreationError> {
+        if max_width == 0 || x_pad < 0.0 || y_pad < 0.0 {
+            return Err(GridCreationError::NegativeInput);
+        }
+
+        let mut layout = Layout {
+            rows: vec![Row {
+                depth: 0,
+                items: Vec::new(),
+                cells: 0,
+            }],
+            blocks: Vec::new(),
+            max_width: max_width as f32 * (1.0 + x_pad),
+            cell_width: 1.0 + x_pad,
+        };
+        layout.add_nodes(nodes, 0);
+
+        if layout.rows.last().unwrap().items.is_empty() {
+            layout.rows.pop();
+        }
+        if layout.rows.is_empty() {
+            return Err(GridCreationError::EmptyPatternList);
+        }
+
+        let margin = |depth: usize| (y_pad / 2.0).min(BLOCK_MARGIN) / (depth + 1) as f32;
+        let offset = HexCoord(0.0, margin(0));
+        let row_y = |row: usize| row as f32 * (1.0 + y_pad) + offset.1;
+
+        let height = row_y(layout.rows.len()) - y_pad + offset.1;
+
+        let mut patterns = Vec::new();
+        let mut chips = Vec::new();
+        let mut width: f32 = 0.0;
+
+        for (i, row) in layout.rows.into_iter().enumerate() {
+            let row_x = row.depth as f32 * INDENT;
+            width = width.max(row_x + row.cells as f32 * (1.0 + x_pad) - x_pad);
+
+            let mut column = 0;
+            for item in row.items {
+                let pos = HexCoord(row_x + column as f32 * (1.0 + x_pad), row_y(i));
+                let cells = get_cells(&item, 1.0 + x_pad);
+                column += cells;
+
+                let pattern = match item {
+                    GridItem::Pattern(pattern) => pattern,
+                    GridItem::Iota(iota) => {
+                        //centered in the cells it takes up
+                        let width = cells as f32 * (1.0 + x_pad) - x_pad;
+                        let size = Chip::get_size(&iota);
+                        let top_left = pos + (HexCoord(width, 1.0) - size) / 2.0;
+                        chips.push(Chip::new(&iota, top_left, patterns.len()));
+                        continue;
+                    }
+                };
+                let pattern_ref = pattern.get_inner();
+
+                let area = pattern_ref.bottom_right_bound - pattern_ref.top_left_bound;
+                let scale = (1.0 / area.0.max(area.1)).min(max_scale);
+                let center = area / 2.0 + pattern_ref.top_left_bound;
+
+                let pattern_loc = pos + HexCoord(0.5, 0.5) - center * scale;
+                patterns.push((pattern, pattern_loc, scale));
+            }
+        }
+
+        let blocks = layout
+            .blocks
+            .into_iter()
+            .map(|(depth, first_row, last_row)| {
+                let margin = margin(depth);
+                BlockOutline {
+                    top_left: HexCoord(
+                        (depth + 1) as f32 * INDENT - margin,
+                        row_y(first_row) - margin,
+                    ),
+                    bottom_right: HexCoord(width + margin, row_y(last_row) + 1.0 + margin),
+                    depth,
+                    style,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            patterns,
+            blocks,
+            chips,
+            size: HexCoord(width + margin(0), height),
+            styles: HashMap::new(),
+        })
+    }
+}
+
+impl NestedGrid {
+    //overrides how the pattern at index is drawn (see PatternStyle)
+    pub fn set_pattern_style(
+        &mut self,
+        index: usize,
+        style: PatternStyle,
+    ) -> Result<(), GridCreationError> {
+        super::set_pattern_style(&mut self.styles, &self.patterns, index, style)
+    }
+}
+
+impl GridLayout for NestedGrid {
+    fn contents(&self) -> GridContents<'_> {
+        GridContents {
+            size: self.size,
+            patterns: &self.patterns,
+            blocks: &self.blocks,
+            chips: &self.chips,
+            labels: &[],
+            styles: &self.styles,
+        }
+    }
+}
+
+pub(crate) fn draw_blocks(
+    canvas: &mut impl Canvas,
+    blocks: &[BlockOutline],
+    options: &GridOptions,
+    offset: HexCoord,
+    scale: f32,
+) {
+    let lines = match &options.pattern_options {
+        GridPatternOptions::Uniform(_, lines) => vec![lines],
+        GridPatternOptions::Changing { variations, .. } => {
+            variations.iter().map(|variation| &variation.1).collect()
+        }
+    };
+
+    let stroke = Stroke {
+        width: options.line_thickness * scale * 0.5,
+        line_cap: LineCap::Round,
+        line_join: LineJoin::Round,
+        dash: None,
+    };
+
+    for block in blocks {
+        //matches the colors Changing gives to the patterns inside the block
+        let color = lines[(block.depth + 1) % lines.len()].get_main_color();
+
+        let top_left = block.top_left * scale + offset;
+        let bottom_right = block.bottom_right * scale + offset;
+
+        match block.style {
+            BlockStyle::Boxes => {
+                let Some(rect) =
+                    Rect::from_ltrb(top_left.0, top_left.1, bottom_right.0, bottom_right.1)
+                else {
+                    continue;
+                };
+                let path = PathBuilder::from_rect(rect);
+
+                let mut fill = color;
+                fill.apply_opacity(0.1);
+                canvas.fill_path(&path, &Brush::Solid(fill));
+
+                let mut outline = color;
+                outline.apply_opacity(0.7);
+                canvas.stroke_path(&path, &Brush::Solid(outline), &stroke);
+            }
+            BlockStyle::Brackets => {
+                let tick = BRACKET_TICK * scale;
+                let mut builder = PathBuilder::new();
+
+                builder.move_to(top_left.0 + tick, top_left.1);
+                builder.line_to(top_left.0, top_left.1);
+                builder.line_to(top_left.0, bottom_right.1);
+                builder.line_to(top_left.0 + tick, bottom_right.1);
+
+                builder.move_to(bottom_right.0 - tick, top_left.1);
+                builder.line_to(bottom_right.0, top_left.1);
+                builder.line_to(bottom_right.0, bottom_right.1);
+                builder.line_to(bottom_right.0 - tick, bottom_right.1);
+
+                if let Some(path) = builder.finish() {
+                    canvas.stroke_path(&path, &Brush::Solid(color), &stroke);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use rayon::prelude::*;
+use tiny_skia::{IntRect, Pixmap, Rect};
+
+use crate::{
+    canvas::PixmapWindow, options::GridOptions, pattern::PatternVariant, pattern_utils::HexCoord,
+};
+
+use super::{
+    get_offset, palette_indices, pattern_bounds, DrawProgress, PatternStyle, PatternStyles,
+};
+
+//a pattern ready to be drawn, with everything that depends on the patterns before it already worked out
+struct Job<'a> {
+    pattern: Cow<'a, PatternVariant>,
+    style: usize,
+    pattern_style: Option<&'a PatternStyle>,
+    location: HexCoord,
+    scale: f32,
+}
+
+//patterns that overlap have to be drawn one after the other on the same layer
+//but groups that don't touch can be drawn at the same time
+struct Cluster<'a> {
+    bounds: IntRect,
+    jobs: Vec<(usize, Job<'a>)>,
+    gradient: bool,
+}
+
+//draws the patterns the same way draw_patterns does but spread over multiple threads
+//
+//every layer starts as a copy of the part of the canvas it covers and is copied back once it's done,
+//and every pattern is drawn onto exactly one layer in the same order as before,
+//so the result is the same as drawing them one by one (no blending between layers)
+pub(super) fn draw_patterns(
+    pixmap: &mut Pixmap,
+    origin: (u32, u32),
+    patterns: &[(PatternVariant, HexCoord, f32)],
+    overrides: &HashMap<usize, PatternStyle>,
+    options: &GridOptions,
+    scale: f32,
+    progress: Option<DrawProgress>,
+) {
+    let styles = PatternStyles::new(options);
+    let offset = get_offset(options, scale);
+    let canvas = IntRect::from_xywh(0, 0, pixmap.width(), pixmap.height()).unwrap();
+
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for (i, ((pattern, location, local_scale), style)) in patterns
+        .iter()
+        .zip(palette_indices(patterns, options))
+        .enumerate()
+    {
+        if progress.is_some_and(|progress| i > progress.pattern) {
+            break;
+        }
+        let location = *location * scale + offset;
+        let scale = scale * *local_scale;
+        let pattern_style = overrides.get(&i);
+
+        let bounds = pattern_bounds(pattern.get_inner(), pattern_style, location, scale, options);
+        let Some(bounds) = to_pixels(bounds, origin).intersect(&canvas) else {
+            continue;
+        };
+
+        let pattern = match progress {
+            Some(progress) if i == progress.pattern => {
+                Cow::Owned(pattern.truncated(progress.segments))
+            }
+            _ => Cow::Borrowed(pattern),
+        };
+        let gradient = styles.is_gradient(&pattern, style, pattern_style);
+        let job = Job {
+            pattern,
+            style,
+            pattern_style,
+            location,
+            scale,
+        };
+        add_cluster(
+            &mut clusters,
+            Cluster {
+                bounds,
+                jobs: vec![(i, job)],
+                gradient,
+            },
+        );
+    }
+
+    //the gradient shader doesn't give exactly the same colors once the layer is moved
+    //so anything with a gradient is drawn straight onto the canvas instead
+    let (in_place, layered): (Vec<_>, Vec<_>) =
+        clusters.into_iter().partition(|cluster| cluster.gradient);
+
+    let layers: Vec<(IntRect, Pixmap)> = layered
+        .into_par_iter()
+        .map(|cluster| {
+            let bounds = cluster.bounds;
+            let mut layer = pixmap.clone_rect(bounds).unwrap();
+            let mut window = PixmapWindow::new(
+                &mut layer,
+                (origin.0 + bounds.x() as u32) as f32,
+                (origin.1 + bounds.y() as u32) as f32,
+            );
+            for (_, job) in &cluster.jobs {
+                styles.draw(
+                    &mut window,
+                    &job.pattern,
+                    job.style,
+                    job.pattern_style,
+                    job.location,
+                    job.scale,
+                );
+            }
+            (bounds, layer)
+        })
+        .collect();
+
+    for (bounds, layer) in layers {
+        copy_layer(pixmap, bounds, &layer);
+    }
+
+    let mut canvas = PixmapWindow::new(pixmap, origin.0 as f32, origin.1 as f32);
+    for cluster in in_place {
+        for (_, job) in &cluster.jobs {
+            styles.draw(
+                &mut canvas,
+                &job.pattern,
+                job.style,
+                job.pattern_style,
+                job.location,
+                job.scale,
+            );
+        }
+    }
+}
+
+//merges the new cluster with every cluster it overlaps, until none of the clusters overlap each other
+fn add_cluster<'a>(clusters: &mut Vec<Cluster<'a>>, mut merged: Cluster<'a>) {
+    while let Some(overlapping) = clusters
+        .iter()
+        .position(|cluster| cluster.bounds.intersect(&merged.bounds).is_some())
+    {
+        let cluster = clusters.swap_remove(overlapping);
+        merged.bounds = union(merged.bounds, cluster.bounds);
+        merged.jobs.extend(cluster.jobs);
+        merged.gradient |= cluster.gradient;
+    }
+    //keep the patterns in the order they'd be drawn in
+    merged.jobs.sort_by_key(|(index, _)| *index);
+    clusters.push(merged);
+}
+
+//the whole pixels the rect touches, relative to the pixmap starting at origin
+fn to_pixels(rect: Rect, origin: (u32, u32)) -> IntRect {
+    let left = (rect.left().floor() - origin.0 as f32).max(i32::MIN as f32) as i32;
+    let top = (rect.top().floor() - origin.1 as f32).max(i32::MIN as f32) as i32;
+    let right = (rect.right().ceil() - origin.0 as f32).min(i32::MAX as f32) as i32;
+    let bottom = (rect.bottom().ceil() - origin.1 as f32).min(i32::MAX as f32) as i32;
+    IntRect::from_ltrb(left, top, right.max(left + 1), bottom.max(top + 1)).unwrap()
+}
+
+fn union(a: IntRect, b: IntRect) -> IntRect {
+    IntRect::from_ltrb(
+        a.left().min(b.left()),
+        a.top().min(b.top()),
+        a.right().max(b.right()),
+        a.bottom().max(b.bottom()),
+    )
+    .unwrap()
+}
+
+fn copy_layer(pixmap: &mut Pixmap, bounds: IntRect, layer: &Pixmap) {
+    let stride = pixmap.width() as usize * 4;
+    let row_len = layer.width() as usize * 4;
+    let start = bounds.x() as usize * 4;
+    for (y, row) in layer.data().chunks_exact(row_len).enumerate() {
+        let offset = (bounds.y() as usize + y) * stride + start;
+        pixmap.data_mut()[offset..offset + row_len].copy_from_slice(row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defaults,
+        grids::draw_patterns as draw_sequential,
+        options::{palettes, Lines},
+        parse::parse_pattern,
+    };
+    use tiny_skia::Color;
+
+    fn patterns() -> Vec<(PatternVariant, HexCoord, f32)> {
+        let pattern = |text: &str| PatternVariant::Normal(parse_pattern(text).unwrap());
+        vec![
+            //the first three overlap each other and end up in one cluster
+            (pattern("HexPattern(EAST qaqqaq)"), HexCoord(1.0, 1.0), 1.0),
+            (
+                pattern("HexPattern(NORTH_EAST qqq)"),
+                HexCoord(1.5, 1.5),
+                1.0,
+            ),
+            (pattern("HexPattern(WEST aadaa)"), HexCoord(2.0, 1.0), 1.0),
+            //the rest are far enough away to be drawn on their own
+            (pattern("HexPattern(EAST da)"), HexCoord(8.0, 1.0), 1.0),
+            (
+                pattern("HexPattern(SOUTH_EAST wwaqqw)"),
+                HexCoord(2.0, 6.0),
+                0.5,
+            ),
+            (pattern("HexPattern(EAST qwe)"), HexCoord(8.0, 6.0), 1.0),
+        ]
+    }
+
+    fn draw_both(origin: (u32, u32), width: u32, height: u32) -> (Pixmap, Pixmap) {
+        let patterns = patterns();
+        let options = &*defaults::SEGMENT;
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            3,
+            PatternStyle::default().with_lines(Lines::Gradient {
+                colors: palettes::DEFAULT.to_vec(),
+                segments_per_color: 3,
+                bent: true,
+            }),
+        );
+        let scale = 30.0;
+
+        let mut background = Pixmap::new(width, height).unwrap();
+        background.fill(Color::from_rgba8(40, 60, 80, 200));
+
+        let mut sequential = background.clone();
+        draw_sequential(
+            &mut PixmapWindow::new(&mut sequential, origin.0 as f32, origin.1 as f32),
+            &patterns,
+            &overrides,
+            options,
+            scale,
+            None,
+            None,
+        );
+        let mut parallel = background;
+        draw_patterns(
+            &mut parallel,
+            origin,
+            &patterns,
+            &overrides,
+            options,
+            scale,
+            None,
+        );
+        (sequential, parallel)
+    }
+
+    #[test]
+    fn same_as_sequential() {
+        let (sequential, parallel) = draw_both((0, 0), 400, 300);
+        assert!(sequential.data().iter().any(|&byte| byte != 0));
+        assert!(sequential.data() == parallel.data());
+    }
+
+    #[test]
+    fn same_as_sequential_in_a_tile() {
+        //a tile that cuts through the clusters
+        let (sequential, parallel) = draw_both((70, 50), 150, 120);
+        assert!(sequential.data() == parallel.data());
+    }
+}
@@ -1,14 +1,20 @@
-use tiny_skia::Pixmap;
+use std::collections::HashMap;
 
 use crate::pattern::PatternVariant;
 use crate::pattern_utils::HexCoord;
-use crate::{options::GridOptions, Pattern};
+use crate::Pattern;
 
-use super::{GridCreationError, GridDraw, GridDrawError};
+use super::{
+    Chip, GridContents, GridCreationError, GridItem, GridLabels, GridLayout, Label, LabelPosition,
+    PatternStyle,
+};
 
 pub struct SquareGrid {
     patterns: Vec<(PatternVariant, HexCoord, f32)>,
     size: HexCoord,
+    chips: Vec<Chip>,
+    labels: Vec<Label>,
+    styles: HashMap<usize, PatternStyle>,
 }
 
 impl SquareGrid {
@@ -34,23 +40,106 @@ impl SquareGrid {
         x_pad: f32,
         y_pad: f32,
     ) -> Result<Self, GridCreationError> {
-        if patterns.is_empty() {
+        Self::new_labeled(
+            patterns,
+            max_width,
+            max_scale,
+            x_pad,
+            y_pad,
+            GridLabels::default(),
+        )
+    }
+    //every cell is made wide enough for the widest label and tall enough to fit a label under/over it
+    pub fn new_labeled(
+        patterns: Vec<PatternVariant>,
+        max_width: usize,
+        max_scale: f32,
+        x_pad: f32,
+        y_pad: f32,
+        labels: GridLabels,
+    ) -> Result<Self, GridCreationError> {
+        Self::new_items(
+            patterns.into_iter().map(GridItem::Pattern).collect(),
+            max_width,
+            max_scale,
+            x_pad,
+            y_pad,
+            labels,
+        )
+    }
+    //iotas that aren't patterns are drawn as chips spanning as many cells as their text needs
+    //(labels and styles still count patterns only)
+    pub fn new_items(
+        items: Vec<GridItem>,
+        max_width: usize,
+        max_scale: f32,
+        x_pad: f32,
+        y_pad: f32,
+        labels: GridLabels,
+    ) -> Result<Self, GridCreationError> {
+        if items.is_empty() {
             return Err(GridCreationError::EmptyPatternList);
         } else if max_width == 0 || x_pad < 0.0 || y_pad < 0.0 {
             return Err(GridCreationError::NegativeInput);
         }
         let mut new_patterns: Vec<(PatternVariant, HexCoord, f32)> = Vec::new();
-
-        for (i, pattern) in patterns.into_iter().enumerate() {
-            let pattern_ref = pattern.get_inner();
-            let y = i / max_width;
-            let x = i - y * max_width;
-
-            let x = x as f32 * (1.0 + x_pad);
-            let y = y as f32 * (1.0 + y_pad);
+        let mut chips = Vec::new();
+        let mut placed_labels = Vec::new();
+
+        let pattern_count = items
+            .iter()
+            .filter(|item| matches!(item, GridItem::Pattern(_)))
+            .count();
+        let label_space = labels.get_space();
+        let cell_width = (0..pattern_count)
+            .filter_map(|i| labels.get_width(i))
+            .fold(1.0, f32::max);
+        let cell_height = 1.0 + label_space;
+        //how far the pattern is pushed down to make room for a label over it
+        let label_shift = match labels.position {
+            LabelPosition::Below => 0.0,
+            LabelPosition::Above => label_space,
+        };
+
+        let mut column = 0;
+        let mut row = 0;
+        let mut max_columns = 0;
+
+        for item in items {
+            let cells = match &item {
+                GridItem::Pattern(_) => 1,
+                GridItem::Iota(iota) => ((Chip::get_size(iota).0 + x_pad) / (cell_width + x_pad))
+                    .ceil()
+                    .max(1.0) as usize,
+            };
+            if column > 0 && column + cells > max_width {
+                column = 0;
+                row += 1;
+            }
+
+            let x = column as f32 * (cell_width + x_pad);
+            let y = row as f32 * (cell_height + y_pad);
 
             let pos = HexCoord(x, y);
 
+            column += cells;
+            max_columns = max_columns.max(column);
+
+            let pattern = match item {
+                GridItem::Pattern(pattern) => pattern,
+                GridItem::Iota(iota) => {
+                    //centered in the cells it takes up
+                    let width = cells as f32 * (cell_width + x_pad) - x_pad;
+                    let size = Chip::get_size(&iota);
+                    let top_left =
+                        pos + HexCoord((width - size.0) / 2.0, label_shift + (1.0 - size.1) / 2.0);
+                    chips.push(Chip::new(&iota, top_left, new_patterns.len()));
+                    continue;
+                }
+            };
+            let i = new_patterns.len();
+            let pattern_ref = pattern.get_inner();
+
             let area = pattern_ref.bottom_right_bound - pattern_ref.top_left_bound;
 
             let largest_bound = area.0.max(area.1);
@@ -59,28 +148,78 @@ impl SquareGrid {
 
             let center = area / 2.0 + pattern_ref.top_left_bound;
 
-            let pattern_loc = pos + HexCoord(0.5, 0.5) - center * scale;
+            let pattern_loc = pos + HexCoord(cell_width / 2.0, label_shift + 0.5) - center * scale;
 
             new_patterns.push((pattern, pattern_loc, scale));
+            placed_labels.extend(labels.place(
+                i,
+                pos.0 + cell_width / 2.0,
+                pos.1 + label_shift,
+                pos.1 + label_shift + 1.0,
+            ));
         }
 
         let size = HexCoord(
-            max_width.min(new_patterns.len()) as f32 * (1.0 + x_pad) - x_pad,
-            (new_patterns.len() as f32 / max_width as f32).ceil() * (1.0 + y_pad) - y_pad,
+            max_columns as f32 * (cell_width + x_pad) - x_pad,
+            (row + 1) as f32 * (cell_height + y_pad) - y_pad,
         );
 
         Ok(Self {
             patterns: new_patterns,
             size,
+            chips,
+            labels: placed_labels,
+            styles: HashMap::new(),
         })
     }
 }
 
-impl GridDraw for SquareGrid {
-    fn draw_grid(&self, scale: f32, options: &GridOptions) -> Result<Pixmap, GridDrawError> {
-        super::draw_grid(self.size, &self.patterns, options, scale)
+impl SquareGrid {
+    //overrides how the pattern at index is drawn (see PatternStyle)
+    pub fn set_pattern_style(
+        &mut self,
+        index: usize,
+        style: PatternStyle,
+    ) -> Result<(), GridCreationError> {
+        super::set_pattern_style(&mut self.styles, &self.patterns, index, style)
     }
-    fn get_unpadded_size(&self) -> (f32, f32) {
-        (self.size.0, self.size.1)
+}
+
+impl GridLayout for SquareGrid {
+    fn contents(&self) -> GridContents<'_> {
+        GridContents {
+            size: self.size,
+            patterns: &self.patterns,
+            blocks: &[],
+            chips: &self.chips,
+            labels: &self.labels,
+            styles: &self.styles,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_pattern;
+
+    #[test]
+    fn wide_labels_dont_overlap() {
+        let patterns = (0..6)
+            .map(|_| PatternVariant::Normal(parse_pattern("HexPattern(EAST w)").unwrap()))
+            .collect();
+        let texts = (0..6)
+            .map(|i| Some(format!("Numerical Reflection: {}", i * 1111)))
+            .collect();
+        //a lot wider than the patterns they belong to
+        let labels = GridLabels::new(texts).with_size(0.5);
+        let grid = SquareGrid::new_labeled(patterns, 10, 0.4, 0.2, 0.1, labels).unwrap();
+
+        assert_eq!(grid.labels.len(), 6);
+        for pair in grid.labels.windows(2) {
+            assert!(pair[0].right() <= pair[1].left() + 1e-4);
+        }
+        assert!(grid.labels[0].left() >= 0.0);
+        assert!(grid.labels[5].right() <= grid.size.0 + 1e-4);
     }
 }
@@ -0,0 +1,134 @@
+use tiny_skia::{Color, LineCap, LineJoin, PathBuilder};
+
+use crate::{
+    canvas::{Brush, Canvas, Stroke},
+    options::{Intersections, Lines},
+    pattern::Pattern,
+    pattern_utils::HexCoord,
+};
+
+use super::get_label_overhang;
+
+//changes how a single pattern in a grid is drawn, on top of the grid's options
+//(see set_pattern_style on the grids)
+#[derive(Debug, Clone, Default)]
+pub struct PatternStyle {
+    //used instead of the lines/intersections the pattern would've gotten from the GridPatternOptions
+    pub lines: Option<Lines>,
+    pub intersections: Option<Intersections>,
+    //a glow drawn behind the pattern
+    pub halo: Option<Halo>,
+    //how far the pattern fades out, 0 leaves it alone and 1 makes it invisible
+    pub dim: f32,
+    //drains the color out of the pattern
+    pub greyscale: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Halo {
+    pub color: Color,
+    //how far past the lines the halo reaches (in line lengths)
+    pub radius: f32,
+}
+
+impl PatternStyle {
+    //a bright halo around the pattern to draw attention to it
+    pub fn highlight(color: Color) -> Self {
+        Self {
+            halo: Some(Halo { color, radius: 0.2 }),
+            ..Default::default()
+        }
+    }
+    //faded out and grey, for patterns that are disabled or don't matter
+    pub fn disabled() -> Self {
+        Self {
+            dim: 0.6,
+            greyscale: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_lines(mut self, lines: Lines) -> Self {
+        self.lines = Some(lines);
+        self
+    }
+    pub fn with_intersections(mut self, intersections: Intersections) -> Self {
+        self.intersections = Some(intersections);
+        self
+    }
+    pub fn with_halo(mut self, halo: Halo) -> Self {
+        self.halo = Some(halo);
+        self
+    }
+    pub fn with_dim(mut self, dim: f32) -> Self {
+        self.dim = dim;
+        self
+    }
+    pub fn with_greyscale(mut self, greyscale: bool) -> Self {
+        self.greyscale = greyscale;
+        self
+    }
+
+    //whether the colors have to be changed at all
+    pub(crate) fn changes_colors(&self) -> bool {
+        self.dim > 0.0 || self.greyscale
+    }
+
+    pub(crate) fn adjust_color(&self, color: Color) -> Color {
+        let (mut r, mut g, mut b) = (color.red(), color.green(), color.blue());
+        if self.greyscale {
+            let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+            (r, g, b) = (luma, luma, luma);
+        }
+        let alpha = color.alpha() * (1.0 - self.dim.clamp(0.0, 1.0));
+        Color::from_rgba(r, g, b, alpha).unwrap_or(color)
+    }
+
+    //how far the overrides can reach past the pattern's lines (in line lengths)
+    pub(crate) fn get_max_radius(&self, line_thickness: f32) -> f32 {
+        let lines = self.lines.as_ref().map_or(0.0, |lines| {
+            lines
+                .get_max_radius()
+                .max(get_label_overhang(lines, line_thickness))
+        });
+        let intersections = self
+            .intersections
+            .as_ref()
+            .map_or(0.0, Intersections::get_max_radius);
+        let halo = self
+            .halo
+            .map_or(0.0, |halo| line_thickness / 2.0 + halo.radius);
+
+        lines.max(intersections).max(halo)
+    }
+}
+
+pub(crate) fn draw_halo(
+    canvas: &mut impl Canvas,
+    pattern: &Pattern,
+    origin: HexCoord,
+    scale: f32,
+    line_thickness: f32,
+    halo: &Halo,
+) {
+    let mut builder = PathBuilder::new();
+    for (i, point) in pattern.path.iter().enumerate() {
+        let point = HexCoord::from(*point) * scale + origin;
+        if i == 0 {
+            builder.move_to(point.0, point.1);
+        } else {
+            builder.line_to(point.0, point.1);
+        }
+    }
+    let Some(path) = builder.finish() else {
+        return;
+    };
+
+    let stroke = Stroke {
+        width: (line_thickness + halo.radius * 2.0) * scale,
+        line_cap: LineCap::Round,
+        line_join: LineJoin::Round,
+        ..Default::default()
+    };
+    canvas.stroke_path(&path, &Brush::Solid(halo.color), &stroke);
+}
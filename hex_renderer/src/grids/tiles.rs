@@ -0,0 +1,239 @@
+use std::fs;
+
+use tiny_skia::{FilterQuality, Pixmap, PixmapPaint, Transform};
+
+use crate::options::GridOptions;
+
+use super::{GridDraw, GridDrawError, GridFileError};
+
+pub const DEFAULT_TILE_SIZE: u32 = 256;
+
+//how much of the canvas (in line lengths) is drawn around each tile and then cut off
+//tiny-skia moves lines slightly when it clips them to the edge of a pixmap (bent lines the most),
+//so the edges have to be far enough away that the tile itself isn't affected
+//(capped in pixels so huge scales don't make every tile huge too)
+pub(crate) const TILE_MARGIN: f32 = 4.0;
+pub(crate) const MAX_TILE_MARGIN: u32 = 1024;
+
+//the most memory (in bytes) a single pixmap is allowed to take up,
+//anything bigger has to be drawn with draw_grid_tiles instead
+pub const MAX_PIXMAP_BYTES: u64 = 1 << 30;
+
+//a pixmap of the given size, or ImproperSize if it's empty or past MAX_PIXMAP_BYTES
+//(checked before allocating since a failed allocation aborts instead of returning None)
+pub(crate) fn new_pixmap(width: u32, height: u32) -> Result<Pixmap, GridDrawError> {
+    let bytes = (width as u64)
+        .checked_mul(height as u64)
+        .and_then(|pixels| pixels.checked_mul(4));
+    if bytes.is_none_or(|bytes| bytes > MAX_PIXMAP_BYTES) {
+        return Err(GridDrawError::ImproperSize(width, height));
+    }
+    Pixmap::new(width, height).ok_or(GridDrawError::ImproperSize(width, height))
+}
+
+//part of a grid's canvas (in pixels)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TileRegion {
+    //the region grown by margin on every side, without going past the edges of the canvas
+    pub(crate) fn with_margin(self, margin: u32, (width, height): (u32, u32)) -> Self {
+        let (right, bottom) = (self.x + self.width, self.y + self.height);
+        let x = self.x.saturating_sub(margin);
+        let y = self.y.saturating_sub(margin);
+        Self {
+            x,
+            y,
+            width: right.saturating_add(margin).min(width).max(right) - x,
+            height: bottom.saturating_add(margin).min(height).max(bottom) - y,
+        }
+    }
+}
+
+//a rendered tile, column and row count tiles from the top left
+#[derive(Debug, Clone)]
+pub struct Tile {
+    pub column: u32,
+    pub row: u32,
+    pub pixmap: Pixmap,
+}
+
+//splits a canvas into tiles, the ones on the right and bottom edges can be smaller
+struct TileLayout {
+    width: u32,
+    height: u32,
+    tile_size: u32,
+}
+
+impl TileLayout {
+    fn new(width: u32, height: u32, tile_size: u32) -> Result<Self, GridDrawError> {
+        if tile_size == 0 {
+            return Err(GridDrawError::ImproperTileSize(tile_size));
+        }
+        Ok(Self {
+            width,
+            height,
+            tile_size,
+        })
+    }
+
+    fn columns(&self) -> u32 {
+        self.width.div_ceil(self.tile_size)
+    }
+    fn rows(&self) -> u32 {
+        self.height.div_ceil(self.tile_size)
+    }
+
+    fn region(&self, column: u32, row: u32) -> Option<TileRegion> {
+        if column >= self.columns() || row >= self.rows() {
+            return None;
+        }
+        let (x, y) = (column * self.tile_size, row * self.tile_size);
+        Some(TileRegion {
+            x,
+            y,
+            width: self.tile_size.min(self.width - x),
+            height: self.tile_size.min(self.height - y),
+        })
+    }
+
+    //the same canvas shrunk by 2^levels
+    fn shrink(&self, levels: u32) -> Self {
+        Self {
+            width: (self.width as u64).div_ceil(1 << levels) as u32,
+            height: (self.height as u64).div_ceil(1 << levels) as u32,
+            tile_size: self.tile_size,
+        }
+    }
+}
+
+pub(crate) fn draw_tiles<G: GridDraw + ?Sized>(
+    grid: &G,
+    scale: f32,
+    options: &GridOptions,
+    tile_size: u32,
+    on_tile: &mut dyn FnMut(Tile) -> Result<(), GridFileError>,
+) -> Result<(), GridFileError> {
+    let (width, height) = grid.get_pixel_size(scale, options);
+    let layout = TileLayout::new(width, height, tile_size).map_err(GridFileError::DrawError)?;
+
+    for row in 0..layout.rows() {
+        for column in 0..layout.columns() {
+            let region = layout.region(column, row).unwrap();
+            let pixmap = grid
+                .draw_grid_tile(scale, options, region)
+                .map_err(GridFileError::DrawError)?;
+            on_tile(Tile {
+                column,
+                row,
+                pixmap,
+            })?;
+        }
+    }
+    Ok(())
+}
+
+//writes an XYZ tile pyramid ({dir}/{zoom}/{column}/{row}.png) and returns the highest zoom level
+//the highest level is the grid at full scale and every level below it is half the size of the next
+pub(crate) fn draw_pyramid<G: GridDraw + ?Sized>(
+    grid: &G,
+    dir: &str,
+    scale: f32,
+    options: &GridOptions,
+    tile_size: u32,
+) -> Result<u32, GridFileError> {
+    let (width, height) = grid.get_pixel_size(scale, options);
+    let layout = TileLayout::new(width, height, tile_size).map_err(GridFileError::DrawError)?;
+
+    //zoom out until the whole grid fits in a single tile
+    let mut max_zoom = 0;
+    while layout.shrink(max_zoom).columns() > 1 || layout.shrink(max_zoom).rows() > 1 {
+        max_zoom += 1;
+    }
+
+    let pyramid = Pyramid {
+        grid,
+        dir,
+        scale,
+        options,
+        layout,
+        max_zoom,
+    };
+    pyramid.build(0, 0, 0)?;
+    Ok(max_zoom)
+}
+
+struct Pyramid<'a, G: GridDraw + ?Sized> {
+    grid: &'a G,
+    dir: &'a str,
+    scale: f32,
+    options: &'a GridOptions,
+    layout: TileLayout,
+    max_zoom: u32,
+}
+
+impl<G: GridDraw + ?Sized> Pyramid<'_, G> {
+    //builds the tile depth first by stitching its four children together at half size,
+    //so only a few tiles per level are ever held in memory
+    fn build(&self, zoom: u32, column: u32, row: u32) -> Result<Option<Pixmap>, GridFileError> {
+        let Some(region) = self.layout.shrink(self.max_zoom - zoom).region(column, row) else {
+            return Ok(None);
+        };
+
+        let pixmap = if zoom == self.max_zoom {
+            self.grid
+                .draw_grid_tile(self.scale, self.options, region)
+                .map_err(GridFileError::DrawError)?
+        } else {
+            let mut pixmap =
+                new_pixmap(region.width, region.height).map_err(GridFileError::DrawError)?;
+            let half = self.layout.tile_size as f32 / 2.0;
+            for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                if let Some(child) = self.build(zoom + 1, column * 2 + dx, row * 2 + dy)? {
+                    pixmap.draw_pixmap(
+                        0,
+                        0,
+                        child.as_ref(),
+                        &PixmapPaint {
+                            quality: FilterQuality::Bilinear,
+                            ..Default::default()
+                        },
+                        Transform::from_row(0.5, 0.0, 0.0, 0.5, dx as f32 * half, dy as f32 * half),
+                        None,
+                    );
+                }
+            }
+            pixmap
+        };
+
+        let dir = format!("{}/{zoom}/{column}", self.dir);
+        fs::create_dir_all(&dir).map_err(GridFileError::SaveError)?;
+        let data = pixmap
+            .encode_png()
+            .map_err(|_| GridFileError::DrawError(GridDrawError::EncodeError))?;
+        fs::write(format!("{dir}/{row}.png"), data).map_err(GridFileError::SaveError)?;
+
+        Ok(Some(pixmap))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_pixmaps_are_improper() {
+        for (width, height) in [(u32::MAX, u32::MAX), (u32::MAX, 1), (1 << 16, 1 << 16)] {
+            assert!(matches!(
+                new_pixmap(width, height),
+                Err(GridDrawError::ImproperSize(w, h)) if (w, h) == (width, height)
+            ));
+        }
+        assert!(new_pixmap(16, 16).is_ok());
+    }
+}
@@ -6,6 +6,12 @@ pub use pattern::*;
 
 pub mod grids;
 
+pub mod canvas;
+
+pub mod parse;
+
+pub mod recognize;
+
 pub mod options;
 pub use options::defaults;
 
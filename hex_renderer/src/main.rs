@@ -1,7 +1,7 @@
 use hex_renderer::{
-    grids::{GridDraw, HexGrid},
+    grids::{GridDraw, GridItem, GridLabels, HexGrid},
     options::{self, GridOptions, Marker},
-    Pattern, PatternVariant,
+    parse, PatternVariant,
 };
 use tiny_skia::Color;
 
@@ -36,10 +36,7 @@ fn main() {
 
     //let patterns_str = "NORTH_EAST qaq, EAST aa, NORTH_EAST qaq, EAST wa, WEST qqq, SOUTH_EAST a, SOUTH_EAST wwwdwdwwwawqqeqwqqwqeqwqq, EAST eee, SOUTH_EAST aqaaeaqaa, NORTH_EAST wdwaw, NORTH_EAST dadad";
 
-    let patterns: Vec<Pattern> = patterns_str
-        .split(", ")
-        .filter_map(|str| Pattern::try_from(str).ok())
-        .collect();
+    let iotas = parse::parse_iotas(patterns_str).unwrap();
 
     let global_scale = 100.0;
 
@@ -48,21 +45,19 @@ fn main() {
     //let x_pad = 0.2;
     //let y_pad = 0.1;
 
-    let mut count = 0;
+    let monocolor = false;
 
-    let variants = patterns
+    let items = GridItem::from_iotas(&iotas)
         .into_iter()
-        .map(|pattern| {
-            count += 1;
-            if false {
-                PatternVariant::Monocolor(pattern)
-            } else {
-                PatternVariant::Normal(pattern)
+        .map(|item| match item {
+            GridItem::Pattern(PatternVariant::Normal(pattern)) if monocolor => {
+                GridItem::Pattern(PatternVariant::Monocolor(pattern))
             }
+            item => item,
         })
         .collect::<Vec<_>>();
 
-    /*let grid = SquareGrid::new(variants.clone(), 10, max_scale, x_pad, y_pad).unwrap();
+    /*let grid = SquareGrid::new_items(items.clone(), 10, max_scale, x_pad, y_pad, GridLabels::default()).unwrap();
     grid.draw_grid_to_file("square.png", global_scale, &defaults::SEGMENT)
         .unwrap();*/
 
@@ -77,8 +72,11 @@ fn main() {
             true,
         ),
         center_dot: options::Point::None,
+        background: options::Background::Transparent,
+        padding: 0.0,
+        label_color: Color::WHITE,
     };
-    let grid = HexGrid::new(variants, 40).unwrap();
+    let grid = HexGrid::new_items(items, 40, GridLabels::default()).unwrap();
     grid.draw_grid_to_file("image.png", global_scale, &grid_options)
         .unwrap();
 }
@@ -1,18 +1,25 @@
+use serde::{Deserialize, Serialize};
 use tiny_skia::Color;
 
+use super::hex_color;
+
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Lines {
     Monocolor {
+        #[serde(with = "hex_color::color")]
         color: Color,
         bent: bool,
     },
     Gradient {
+        #[serde(with = "hex_color::colors")]
         colors: Vec<Color>,
         segments_per_color: usize,
         bent: bool,
     },
     SegmentColors {
+        #[serde(with = "hex_color::colors")]
         colors: Vec<Color>,
         triangles: Triangle,
         collisions: CollisionOption,
@@ -20,7 +27,8 @@ pub enum Lines {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Triangle {
     None,
     Match { radius: f32 },
@@ -29,9 +37,10 @@ pub enum Triangle {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CollisionOption {
-    Dashes(Color),
+    Dashes(#[serde(with = "hex_color::color")] Color),
     MatchedDashes,
     ParallelLines,
     OverloadedParallel {
@@ -41,29 +50,37 @@ pub enum CollisionOption {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum OverloadOptions {
-    Dashes(Color),
-    LabeledDashes { color: Color, label: Marker },
+    Dashes(#[serde(with = "hex_color::color")] Color),
+    LabeledDashes {
+        #[serde(with = "hex_color::color")]
+        color: Color,
+        label: Marker,
+    },
     MatchedDashes,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Point {
     None,
     Single(Marker),
     Double { inner: Marker, outer: Marker },
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Marker {
+    #[serde(with = "hex_color::color")]
     pub color: Color,
     pub radius: f32,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EndPoint {
     Point(Point),
     Match { radius: f32 },
@@ -71,7 +88,8 @@ pub enum EndPoint {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Intersections {
     Nothing,
     UniformPoints(Point),
@@ -260,3 +278,148 @@ impl Lines {
         }
     }
 }
+impl Lines {
+    //the color used when a single color has to stand in for the whole pattern
+    pub fn get_main_color(&self) -> Color {
+        match self {
+            Lines::Monocolor { color, bent: _ } => *color,
+            Lines::Gradient {
+                colors,
+                segments_per_color: _,
+                bent: _,
+            } => colors[0],
+            Lines::SegmentColors {
+                colors,
+                triangles: _,
+                collisions: _,
+            } => colors[0],
+        }
+    }
+}
+
+//the same options with every color passed through f (used to dim or grey out a single pattern)
+impl Lines {
+    pub fn map_colors(&self, f: &impl Fn(Color) -> Color) -> Self {
+        match self {
+            Lines::Monocolor { color, bent } => Lines::Monocolor {
+                color: f(*color),
+                bent: *bent,
+            },
+            Lines::Gradient {
+                colors,
+                segments_per_color,
+                bent,
+            } => Lines::Gradient {
+                colors: colors.iter().map(|color| f(*color)).collect(),
+                segments_per_color: *segments_per_color,
+                bent: *bent,
+            },
+            Lines::SegmentColors {
+                colors,
+                triangles,
+                collisions,
+            } => Lines::SegmentColors {
+                colors: colors.iter().map(|color| f(*color)).collect(),
+                triangles: triangles.map_colors(f),
+                collisions: collisions.map_colors(f),
+            },
+        }
+    }
+}
+impl Triangle {
+    pub fn map_colors(&self, f: &impl Fn(Color) -> Color) -> Self {
+        match *self {
+            Triangle::BorderMatch {
+                match_radius,
+                border,
+            } => Triangle::BorderMatch {
+                match_radius,
+                border: border.map_colors(f),
+            },
+            Triangle::BorderStartMatch {
+                match_radius,
+                border,
+            } => Triangle::BorderStartMatch {
+                match_radius,
+                border: border.map_colors(f),
+            },
+            triangle => triangle,
+        }
+    }
+}
+impl CollisionOption {
+    pub fn map_colors(&self, f: &impl Fn(Color) -> Color) -> Self {
+        match *self {
+            CollisionOption::Dashes(color) => CollisionOption::Dashes(f(color)),
+            CollisionOption::OverloadedParallel { max_line, overload } => {
+                CollisionOption::OverloadedParallel {
+                    max_line,
+                    overload: overload.map_colors(f),
+                }
+            }
+            collisions => collisions,
+        }
+    }
+}
+impl OverloadOptions {
+    pub fn map_colors(&self, f: &impl Fn(Color) -> Color) -> Self {
+        match *self {
+            OverloadOptions::Dashes(color) => OverloadOptions::Dashes(f(color)),
+            OverloadOptions::LabeledDashes { color, label } => OverloadOptions::LabeledDashes {
+                color: f(color),
+                label: label.map_colors(f),
+            },
+            OverloadOptions::MatchedDashes => OverloadOptions::MatchedDashes,
+        }
+    }
+}
+impl Intersections {
+    pub fn map_colors(&self, f: &impl Fn(Color) -> Color) -> Self {
+        match self {
+            Intersections::Nothing => Intersections::Nothing,
+            Intersections::UniformPoints(point) => {
+                Intersections::UniformPoints(point.map_colors(f))
+            }
+            Intersections::EndsAndMiddle { start, end, middle } => Intersections::EndsAndMiddle {
+                start: start.map_colors(f),
+                end: end.map_colors(f),
+                middle: middle.map_colors(f),
+            },
+        }
+    }
+}
+impl EndPoint {
+    pub fn map_colors(&self, f: &impl Fn(Color) -> Color) -> Self {
+        match *self {
+            EndPoint::Point(point) => EndPoint::Point(point.map_colors(f)),
+            EndPoint::Match { radius } => EndPoint::Match { radius },
+            EndPoint::BorderedMatch {
+                match_radius,
+                border,
+            } => EndPoint::BorderedMatch {
+                match_radius,
+                border: border.map_colors(f),
+            },
+        }
+    }
+}
+impl Point {
+    pub fn map_colors(&self, f: &impl Fn(Color) -> Color) -> Self {
+        match self {
+            Point::None => Point::None,
+            Point::Single(marker) => Point::Single(marker.map_colors(f)),
+            Point::Double { inner, outer } => Point::Double {
+                inner: inner.map_colors(f),
+                outer: outer.map_colors(f),
+            },
+        }
+    }
+}
+impl Marker {
+    pub fn map_colors(&self, f: &impl Fn(Color) -> Color) -> Self {
+        Marker {
+            color: f(self.color),
+            radius: self.radius,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+use tiny_skia::Color;
+
+#[derive(Debug, Clone)]
+pub enum ColorParseError {
+    MissingHash(String),
+    InvalidLength(String),
+    InvalidDigit(String),
+}
+
+//accepts #rgb, #rgba, #rrggbb and #rrggbbaa
+pub fn parse_hex_color(str: &str) -> Result<Color, ColorParseError> {
+    let digits = str
+        .trim()
+        .strip_prefix('#')
+        .ok_or_else(|| ColorParseError::MissingHash(str.to_string()))?;
+
+    if !digits.is_ascii() {
+        return Err(ColorParseError::InvalidDigit(str.to_string()));
+    }
+
+    let channel = |part: &str| {
+        let val = u8::from_str_radix(part, 16)
+            .map_err(|_| ColorParseError::InvalidDigit(str.to_string()))?;
+        Ok(if part.len() == 1 { val * 17 } else { val })
+    };
+
+    let width = match digits.len() {
+        3 | 4 => 1,
+        6 | 8 => 2,
+        _ => return Err(ColorParseError::InvalidLength(str.to_string())),
+    };
+
+    let mut channels = [255; 4];
+    for (i, channel_val) in channels.iter_mut().enumerate().take(digits.len() / width) {
+        *channel_val = channel(&digits[i * width..(i + 1) * width])?;
+    }
+
+    Ok(Color::from_rgba8(
+        channels[0],
+        channels[1],
+        channels[2],
+        channels[3],
+    ))
+}
+
+pub fn to_hex_color(color: Color) -> String {
+    let color = color.to_color_u8();
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        color.red(),
+        color.green(),
+        color.blue(),
+        color.alpha()
+    )
+}
+
+pub(crate) mod color {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex_color(*color))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let str = String::deserialize(deserializer)?;
+        parse_hex_color(&str).map_err(|err| D::Error::custom(format!("{err:?}")))
+    }
+}
+
+pub(crate) mod colors {
+    use serde::ser::SerializeSeq;
+
+    use super::*;
+
+    pub fn serialize<S: Serializer>(colors: &[Color], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(colors.len()))?;
+        for color in colors {
+            seq.serialize_element(&to_hex_color(*color))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Color>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|str| parse_hex_color(str).map_err(|err| D::Error::custom(format!("{err:?}"))))
+            .collect()
+    }
+}
+
+pub(crate) mod color_pair {
+    use serde::Serialize;
+
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        colors: &(Color, Color),
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        (to_hex_color(colors.0), to_hex_color(colors.1)).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<(Color, Color), D::Error> {
+        let (a, b) = <(String, String)>::deserialize(deserializer)?;
+        let parse =
+            |str: &str| parse_hex_color(str).map_err(|err| D::Error::custom(format!("{err:?}")));
+        Ok((parse(&a)?, parse(&b)?))
+    }
+}
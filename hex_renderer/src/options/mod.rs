@@ -4,5 +4,11 @@ pub use draw_options::*;
 mod pattern_grid_options;
 pub use pattern_grid_options::*;
 
+mod hex_color;
+pub use hex_color::{parse_hex_color, to_hex_color, ColorParseError};
+
+mod theme;
+pub use theme::ThemeError;
+
 pub mod defaults;
 pub mod palettes;
@@ -46,3 +46,16 @@ lazy_static! {
         Color::from_rgba8(23, 190, 207, 255),
     ];
 }
+
+//the names the palettes go by (see get_palette)
+pub const NAMES: [&str; 4] = ["default", "turbo", "dark2", "tab10"];
+
+pub fn get_palette(name: &str) -> Option<&'static [Color]> {
+    match &name.to_lowercase()[..] {
+        "default" => Some(&DEFAULT),
+        "turbo" => Some(&TURBO),
+        "dark2" => Some(&DARK2),
+        "tab10" => Some(&TAB10),
+        _ => None,
+    }
+}
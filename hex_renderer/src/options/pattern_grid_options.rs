@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use tiny_skia::Color;
 
 use crate::{
@@ -6,22 +7,57 @@ use crate::{
     pattern_utils::Angle,
 };
 
-use super::{defaults::constants, CollisionOption, Point};
+use super::{defaults::constants, hex_color, theme, CollisionOption, Point};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GridOptions {
+    #[serde(default = "theme::default_line_thickness")]
     pub line_thickness: f32,
     pub pattern_options: GridPatternOptions,
+    #[serde(default = "theme::default_center_dot")]
     pub center_dot: Point,
+    #[serde(default = "theme::default_background")]
+    pub background: Background,
+    //extra space around the patterns (in line lengths) on top of the max radius
+    #[serde(default)]
+    pub padding: f32,
+    //the color of the labels under/over patterns (see GridLabels)
+    #[serde(default = "theme::default_label_color", with = "hex_color::color")]
+    pub label_color: Color,
 }
 
 #[allow(dead_code)]
-#[derive(Clone)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Background {
+    Transparent,
+    Solid(#[serde(with = "hex_color::color")] Color),
+    //alternating squares, size is in line lengths
+    Checker {
+        size: f32,
+        #[serde(with = "hex_color::color_pair")]
+        colors: (Color, Color),
+    },
+    //base color with a scattering of small grain specks
+    Paper {
+        #[serde(with = "hex_color::color")]
+        color: Color,
+        #[serde(with = "hex_color::color")]
+        grain: Color,
+    },
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum GridPatternOptions {
     Uniform(Intersections, Lines),
     Changing {
+        #[serde(with = "theme::variations")]
         variations: Vec<(Intersections, Lines)>,
+        #[serde(with = "theme::angle_sigs")]
         intros: Vec<Vec<Angle>>,
+        #[serde(with = "theme::angle_sigs")]
         retros: Vec<Vec<Angle>>,
     },
 }
@@ -31,8 +67,23 @@ impl GridOptions {
             line_thickness: constants::LINE_THICKNESS,
             pattern_options,
             center_dot,
+            background: Background::Transparent,
+            padding: 0.0,
+            label_color: Color::WHITE,
         }
     }
+    pub fn with_background(mut self, background: Background) -> Self {
+        self.background = background;
+        self
+    }
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+    pub fn with_label_color(mut self, label_color: Color) -> Self {
+        self.label_color = label_color;
+        self
+    }
 }
 impl GridPatternOptions {
     pub fn generate_changing(
@@ -116,6 +167,9 @@ impl GridOptions {
             .max(self.center_dot.get_max_radius())
             .max(self.pattern_options.get_max_radius())
     }
+    pub fn get_border_size(&self) -> f32 {
+        self.get_max_radius() + self.padding.max(0.0)
+    }
 }
 impl GridPatternOptions {
     pub fn get_max_radius(&self) -> f32 {
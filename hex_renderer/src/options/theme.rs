@@ -0,0 +1,400 @@
+use std::{fs, io, path::Path};
+
+use serde::{de::Error, ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+use tiny_skia::Color;
+
+use crate::pattern_utils::Angle;
+
+use super::{
+    defaults::constants, Background, CollisionOption, EndPoint, GridOptions, GridPatternOptions,
+    Intersections, Lines, Marker, OverloadOptions, Point, Triangle,
+};
+
+#[derive(Debug)]
+pub enum ThemeError {
+    FileError(io::Error),
+    UnknownFormat(String),
+    JsonError(serde_json::Error),
+    TomlParseError(toml::de::Error),
+    TomlWriteError(toml::ser::Error),
+    //the theme was read fine but couldn't be drawn with (eg. a palette without any colors)
+    InvalidTheme(String),
+}
+
+enum ThemeFormat {
+    Json,
+    Toml,
+}
+
+impl ThemeFormat {
+    fn from_path(path: &Path) -> Result<Self, ThemeError> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        match &extension[..] {
+            "json" => Ok(Self::Json),
+            "toml" => Ok(Self::Toml),
+            _ => Err(ThemeError::UnknownFormat(path.display().to_string())),
+        }
+    }
+}
+
+impl GridOptions {
+    pub fn from_json(str: &str) -> Result<Self, ThemeError> {
+        let options: Self = serde_json::from_str(str).map_err(ThemeError::JsonError)?;
+        options.validate()?;
+        Ok(options)
+    }
+    pub fn to_json(&self) -> Result<String, ThemeError> {
+        serde_json::to_string_pretty(self).map_err(ThemeError::JsonError)
+    }
+
+    pub fn from_toml(str: &str) -> Result<Self, ThemeError> {
+        let options: Self = toml::from_str(str).map_err(ThemeError::TomlParseError)?;
+        options.validate()?;
+        Ok(options)
+    }
+    pub fn to_toml(&self) -> Result<String, ThemeError> {
+        toml::to_string_pretty(self).map_err(ThemeError::TomlWriteError)
+    }
+
+    //the format is picked from the file extension (.json or .toml)
+    pub fn load_theme(path: impl AsRef<Path>) -> Result<Self, ThemeError> {
+        let path = path.as_ref();
+        let format = ThemeFormat::from_path(path)?;
+        let data = fs::read_to_string(path).map_err(ThemeError::FileError)?;
+
+        match format {
+            ThemeFormat::Json => Self::from_json(&data),
+            ThemeFormat::Toml => Self::from_toml(&data),
+        }
+    }
+    pub fn save_theme(&self, path: impl AsRef<Path>) -> Result<(), ThemeError> {
+        let path = path.as_ref();
+        let data = match ThemeFormat::from_path(path)? {
+            ThemeFormat::Json => self.to_json()?,
+            ThemeFormat::Toml => self.to_toml()?,
+        };
+        fs::write(path, data).map_err(ThemeError::FileError)
+    }
+
+    //checks for the things a theme file can hold that drawing can't handle
+    //(empty lists of variations or colors, zero counts, negative radii and sizes that aren't finite)
+    pub fn validate(&self) -> Result<(), ThemeError> {
+        //written this way around so NaN isn't let through
+        if !(self.line_thickness > 0.0 && self.line_thickness.is_finite()) {
+            return Err(ThemeError::InvalidTheme(format!(
+                "line_thickness has to be a positive number (got {})",
+                self.line_thickness
+            )));
+        }
+        if !(self.padding >= 0.0 && self.padding.is_finite()) {
+            return Err(ThemeError::InvalidTheme(format!(
+                "padding has to be zero or a positive number (got {})",
+                self.padding
+            )));
+        }
+        if let Background::Checker { size, .. } = self.background {
+            if !(size > 0.0 && size.is_finite()) {
+                return Err(ThemeError::InvalidTheme(format!(
+                    "the checker size has to be a positive number (got {size})"
+                )));
+            }
+        }
+        check_point(&self.center_dot)?;
+        match &self.pattern_options {
+            GridPatternOptions::Uniform(intersections, lines) => {
+                check_intersections(intersections)?;
+                check_lines(lines)
+            }
+            GridPatternOptions::Changing { variations, .. } => {
+                if variations.is_empty() {
+                    return Err(invalid("variations can't be empty"));
+                }
+                for (intersections, lines) in variations {
+                    check_intersections(intersections)?;
+                    check_lines(lines)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn invalid(message: &str) -> ThemeError {
+    ThemeError::InvalidTheme(message.to_string())
+}
+
+fn check_radius(radius: f32) -> Result<(), ThemeError> {
+    //written this way around so NaN isn't let through
+    if radius >= 0.0 && radius.is_finite() {
+        Ok(())
+    } else {
+        Err(ThemeError::InvalidTheme(format!(
+            "radius has to be zero or a positive number (got {radius})"
+        )))
+    }
+}
+
+fn check_marker(marker: &Marker) -> Result<(), ThemeError> {
+    check_radius(marker.radius)
+}
+
+fn check_point(point: &Point) -> Result<(), ThemeError> {
+    match point {
+        Point::None => Ok(()),
+        Point::Single(marker) => check_marker(marker),
+        Point::Double { inner, outer } => {
+            check_marker(inner)?;
+            check_marker(outer)
+        }
+    }
+}
+
+fn check_end_point(end_point: &EndPoint) -> Result<(), ThemeError> {
+    match end_point {
+        EndPoint::Point(point) => check_point(point),
+        EndPoint::Match { radius } => check_radius(*radius),
+        EndPoint::BorderedMatch {
+            match_radius,
+            border,
+        } => {
+            check_radius(*match_radius)?;
+            check_marker(border)
+        }
+    }
+}
+
+fn check_intersections(intersections: &Intersections) -> Result<(), ThemeError> {
+    match intersections {
+        Intersections::Nothing => Ok(()),
+        Intersections::UniformPoints(point) => check_point(point),
+        Intersections::EndsAndMiddle { start, end, middle } => {
+            check_end_point(start)?;
+            check_end_point(end)?;
+            check_point(middle)
+        }
+    }
+}
+
+fn check_lines(lines: &Lines) -> Result<(), ThemeError> {
+    match lines {
+        Lines::Monocolor { .. } => Ok(()),
+        Lines::Gradient {
+            colors,
+            segments_per_color,
+            ..
+        } => {
+            if colors.is_empty() {
+                return Err(invalid("gradient lines need at least one color"));
+            }
+            if *segments_per_color == 0 {
+                return Err(invalid("segments_per_color can't be zero"));
+            }
+            Ok(())
+        }
+        Lines::SegmentColors {
+            colors,
+            triangles,
+            collisions,
+        } => {
+            if colors.is_empty() {
+                return Err(invalid("segment_colors lines need at least one color"));
+            }
+            match triangles {
+                Triangle::None => (),
+                Triangle::Match { radius } => check_radius(*radius)?,
+                Triangle::BorderMatch {
+                    match_radius,
+                    border,
+                }
+                | Triangle::BorderStartMatch {
+                    match_radius,
+                    border,
+                } => {
+                    check_radius(*match_radius)?;
+                    check_marker(border)?;
+                }
+            }
+            match collisions {
+                CollisionOption::OverloadedParallel {
+                    overload: OverloadOptions::LabeledDashes { label, .. },
+                    ..
+                } => check_marker(label),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+pub(crate) fn default_line_thickness() -> f32 {
+    constants::LINE_THICKNESS
+}
+pub(crate) fn default_center_dot() -> Point {
+    Point::None
+}
+pub(crate) fn default_background() -> Background {
+    Background::Transparent
+}
+pub(crate) fn default_label_color() -> Color {
+    Color::WHITE
+}
+
+//intro/retro patterns are stored as angle sigs ("qqq") instead of lists of angle names
+pub(crate) mod angle_sigs {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(sigs: &[Vec<Angle>], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(sigs.len()))?;
+        for sig in sigs {
+            seq.serialize_element(
+                &sig.iter()
+                    .map(|&angle| char::from(angle))
+                    .collect::<String>(),
+            )?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<Angle>>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|sig| {
+                sig.chars()
+                    .map(|ch| {
+                        Angle::try_from(ch)
+                            .map_err(|err| D::Error::custom(format!("invalid angle '{}'", err.0)))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+//variations are stored as tables with named fields so they're readable in theme files
+pub(crate) mod variations {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct VariationRef<'a> {
+        intersections: &'a Intersections,
+        lines: &'a Lines,
+    }
+
+    #[derive(Deserialize)]
+    struct Variation {
+        intersections: Intersections,
+        lines: Lines,
+    }
+
+    pub fn serialize<S: Serializer>(
+        variations: &[(Intersections, Lines)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(variations.len()))?;
+        for (intersections, lines) in variations {
+            seq.serialize_element(&VariationRef {
+                intersections,
+                lines,
+            })?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(Intersections, Lines)>, D::Error> {
+        Ok(Vec::<Variation>::deserialize(deserializer)?
+            .into_iter()
+            .map(|variation| (variation.intersections, variation.lines))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defaults;
+
+    fn is_invalid(options: &GridOptions) -> bool {
+        matches!(options.validate(), Err(ThemeError::InvalidTheme(_)))
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        for options in [
+            &*defaults::MONOCOLOR,
+            &*defaults::GRADIENT,
+            &*defaults::SEGMENT,
+        ] {
+            options.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn line_thickness_has_to_be_positive() {
+        for line_thickness in [0.0, -0.1, f32::NAN, f32::INFINITY] {
+            let options = GridOptions {
+                line_thickness,
+                ..defaults::SEGMENT.clone()
+            };
+            assert!(is_invalid(&options), "{line_thickness}");
+        }
+    }
+
+    #[test]
+    fn padding_cant_be_negative() {
+        for padding in [-0.1, f32::NAN, f32::INFINITY] {
+            let options = defaults::SEGMENT.clone().with_padding(padding);
+            assert!(is_invalid(&options), "{padding}");
+        }
+        defaults::SEGMENT
+            .clone()
+            .with_padding(0.5)
+            .validate()
+            .unwrap();
+    }
+
+    #[test]
+    fn checker_size_has_to_be_positive() {
+        for size in [0.0, -1.0, f32::NAN] {
+            let options = GridOptions {
+                background: Background::Checker {
+                    size,
+                    colors: (Color::WHITE, Color::BLACK),
+                },
+                ..defaults::SEGMENT.clone()
+            };
+            assert!(is_invalid(&options), "{size}");
+        }
+    }
+
+    #[test]
+    fn radius_has_to_be_finite() {
+        for radius in [-0.1, f32::NAN, f32::INFINITY] {
+            let options = GridOptions {
+                center_dot: Point::Single(Marker {
+                    color: Color::WHITE,
+                    radius,
+                }),
+                ..defaults::SEGMENT.clone()
+            };
+            assert!(is_invalid(&options), "{radius}");
+        }
+    }
+
+    //nan and inf can be written in toml
+    #[test]
+    fn loaded_themes_are_checked() {
+        let toml = defaults::SEGMENT.to_toml().unwrap();
+        let toml = toml.replacen("line_thickness = 0.12", "line_thickness = nan", 1);
+        assert!(matches!(
+            GridOptions::from_toml(&toml),
+            Err(ThemeError::InvalidTheme(_))
+        ));
+    }
+}
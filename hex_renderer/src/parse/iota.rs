@@ -0,0 +1,76 @@
+use std::{
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use crate::Pattern;
+
+#[derive(Debug, Clone)]
+pub enum Iota {
+    Pattern(Pattern),
+    Number(f64),
+    Vector(f64, f64, f64),
+    String(String),
+    List(Vec<ParsedIota>),
+    Unknown(String),
+}
+
+//an iota along with the byte range of the input it was parsed from
+#[derive(Debug, Clone)]
+pub struct ParsedIota {
+    pub iota: Iota,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IotaParseError {
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+impl ParsedIota {
+    //all the patterns in order, including the ones nested inside of lists
+    pub fn patterns(&self) -> Vec<&Pattern> {
+        let mut patterns = Vec::new();
+        self.collect_patterns(&mut patterns);
+        patterns
+    }
+    fn collect_patterns<'a>(&'a self, patterns: &mut Vec<&'a Pattern>) {
+        match &self.iota {
+            Iota::Pattern(pattern) => patterns.push(pattern),
+            Iota::List(list) => {
+                for iota in list {
+                    iota.collect_patterns(patterns);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+//writes the iota back out in the notation the parser reads
+impl Display for Iota {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Iota::Pattern(pattern) => write!(f, "{pattern}"),
+            Iota::Number(num) => write!(f, "{num}"),
+            Iota::Vector(x, y, z) => write!(f, "({x}, {y}, {z})"),
+            Iota::String(string) => write!(
+                f,
+                "\"{}\"",
+                string.replace('\\', "\\\\").replace('"', "\\\"")
+            ),
+            Iota::List(list) => {
+                f.write_str("[")?;
+                for (i, iota) in list.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", iota.iota)?;
+                }
+                f.write_str("]")
+            }
+            Iota::Unknown(text) => f.write_str(text),
+        }
+    }
+}
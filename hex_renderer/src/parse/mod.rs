@@ -0,0 +1,5 @@
+mod iota;
+pub use iota::{Iota, IotaParseError, ParsedIota};
+
+mod parser;
+pub use parser::{parse_iotas, parse_pattern};
@@ -0,0 +1,358 @@
+use pest::{error::InputLocation, iterators::Pair, Parser};
+use pest_derive::Parser;
+
+use crate::{
+    pattern_utils::{Angle, Direction, DirectionParseError},
+    Pattern, PatternParseError,
+};
+
+use super::{Iota, IotaParseError, ParsedIota};
+
+#[derive(Parser)]
+#[grammar_inline = r#"
+
+WHITESPACE = _{" " | "\t" | NEWLINE}
+space = _{" " | "\t" | NEWLINE}
+
+//] only ends an iota inside of a list, anything else that's cut off becomes an unknown iota
+iotas = {SOI ~ top_iota_list ~ EOI}
+top_iota_list = _{top_iota? ~ ("," ~ top_iota?)*}
+top_iota = _{known_iota ~ &(space* ~ ("," | EOI)) | unknown}
+iota_list = _{iota? ~ ("," ~ iota?)*}
+iota = _{known_iota ~ &(space* ~ ("," | "]")) | unknown}
+known_iota = _{list | pattern | string | vector | number}
+
+list = {"[" ~ iota_list ~ "]"}
+
+//\" and \\ are the only escapes, any other backslash is kept as it is
+string = ${"\"" ~ string_inner ~ "\""}
+string_inner = @{("\\" ~ ("\"" | "\\") | !"\"" ~ ANY)*}
+
+vector = {"(" ~ number ~ "," ~ number ~ "," ~ number ~ ")"}
+
+number = @{"-"? ~ ASCII_DIGIT+ ~ ("." ~ ASCII_DIGIT+)? ~ (^"e" ~ ("+" | "-")? ~ ASCII_DIGIT+)? ~ !word_char}
+
+unknown = @{(!("," | "[" | "]") ~ ANY)+}
+
+//HexPattern(EAST qaq), <east,qaq> and <EAST qaq>
+hex_pattern = _{^"HexPattern" ~ space* ~ "(" ~ space* ~ pattern_inner ~ space* ~ ")"}
+angle_pattern = _{"<" ~ space* ~ pattern_inner ~ space* ~ ">"}
+pattern_inner = _{direction ~ ((space* ~ "," ~ space* | space+) ~ angles?)?}
+
+//EAST qaq, only accepted in lists when it's made of valid directions and angles
+bare_pattern = _{known_direction ~ (space+ ~ known_angles)?}
+known_direction = _{&(direction_name ~ !word_char) ~ direction}
+direction_name = _{
+    ^"north_east" | ^"northeast" | ^"ne"
+    | ^"south_east" | ^"southeast" | ^"se"
+    | ^"south_west" | ^"southwest" | ^"sw"
+    | ^"north_west" | ^"northwest" | ^"nw"
+    | ^"east" | ^"e" | ^"west" | ^"w"
+}
+known_angles = _{&((^"w" | ^"e" | ^"d" | ^"s" | ^"a" | ^"q")+ ~ !word_char) ~ angles}
+
+word_char = _{ASCII_ALPHANUMERIC | "_"}
+direction = @{ASCII_ALPHA ~ word_char*}
+angles = @{word_char+}
+
+pattern = ${hex_pattern | angle_pattern | bare_pattern}
+loose_pattern = ${hex_pattern | angle_pattern | direction ~ (space+ ~ angles)?}
+single_pattern = ${SOI ~ space* ~ loose_pattern ~ space* ~ EOI}
+
+"#]
+struct IotaParser;
+
+//parses a comma separated list of iotas, anything that can't be understood becomes Iota::Unknown
+pub fn parse_iotas(input: &str) -> Result<Vec<ParsedIota>, IotaParseError> {
+    let parsed = IotaParser::parse(Rule::iotas, input)
+        .map_err(|err| IotaParseError {
+            span: match err.location {
+                InputLocation::Pos(pos) => pos..pos,
+                InputLocation::Span((start, end)) => start..end,
+            },
+            message: err.variant.message().to_string(),
+        })?
+        .next()
+        .unwrap();
+
+    Ok(parse_iota_list(parsed))
+}
+
+//parses exactly one pattern in any of the supported notations
+pub fn parse_pattern(input: &str) -> Result<Pattern, PatternParseError> {
+    let parsed = IotaParser::parse(Rule::single_pattern, input).map_err(|_| {
+        let trimmed = input.trim();
+        if trimmed.to_lowercase().starts_with("hexpattern(") && !trimmed.ends_with(')') {
+            PatternParseError::HangingHexPattern(input.to_string())
+        } else {
+            PatternParseError::InvalidParts(input.to_string())
+        }
+    })?;
+
+    let pattern = parsed
+        .flat_map(|pair| pair.into_inner())
+        .find(|pair| pair.as_rule() == Rule::loose_pattern)
+        .unwrap();
+
+    build_pattern(pattern, input)
+}
+
+fn parse_iota_list(pair: Pair<'_, Rule>) -> Vec<ParsedIota> {
+    pair.into_inner()
+        .filter(|pair| pair.as_rule() != Rule::EOI)
+        .map(parse_iota)
+        .collect()
+}
+
+fn parse_iota(pair: Pair<'_, Rule>) -> ParsedIota {
+    let span = pair.as_span();
+    let mut span = span.start()..span.end();
+
+    let iota = match pair.as_rule() {
+        Rule::list => Iota::List(parse_iota_list(pair)),
+        Rule::pattern => {
+            let text = pair.as_str();
+            build_pattern(pair, text)
+                .map(Iota::Pattern)
+                .unwrap_or_else(|_| Iota::Unknown(text.to_string()))
+        }
+        Rule::string => Iota::String(unescape(pair.into_inner().next().unwrap().as_str())),
+        Rule::vector => {
+            let mut numbers = pair
+                .into_inner()
+                .map(|number| parse_number(number.as_str()));
+            Iota::Vector(
+                numbers.next().unwrap(),
+                numbers.next().unwrap(),
+                numbers.next().unwrap(),
+            )
+        }
+        Rule::number => Iota::Number(parse_number(pair.as_str())),
+        Rule::unknown => {
+            let text = pair.as_str().trim_end();
+            span.end = span.start + text.len();
+            Iota::Unknown(text.to_string())
+        }
+        _ => unreachable!(),
+    };
+
+    ParsedIota { iota, span }
+}
+
+fn build_pattern(pair: Pair<'_, Rule>, input: &str) -> Result<Pattern, PatternParseError> {
+    let mut inner = pair.into_inner();
+
+    let direction: Direction = inner.next().unwrap().as_str().try_into().map_err(|err| {
+        PatternParseError::InvalidStartDirection {
+            input: input.to_string(),
+            direction: match err {
+                DirectionParseError::InvalidNumber(_) => unreachable!(),
+                DirectionParseError::InvalidStr(str) => str,
+            },
+        }
+    })?;
+
+    let angles = match inner.next() {
+        Some(angles) => angles
+            .as_str()
+            .chars()
+            .map(|ch| {
+                Angle::try_from(ch.to_ascii_lowercase()).map_err(|_| {
+                    PatternParseError::InvalidAngle {
+                        input: input.to_string(),
+                        angle: ch,
+                    }
+                })
+            })
+            .collect::<Result<Vec<Angle>, PatternParseError>>()?,
+        None => Vec::new(),
+    };
+
+    Ok(Pattern::new(direction, angles))
+}
+
+fn unescape(str: &str) -> String {
+    let mut unescaped = String::with_capacity(str.len());
+    let mut chars = str.chars().peekable();
+    while let Some(char) = chars.next() {
+        match (char, chars.peek()) {
+            ('\\', Some(&next @ ('"' | '\\'))) => {
+                unescaped.push(next);
+                chars.next();
+            }
+            _ => unescaped.push(char),
+        }
+    }
+    unescaped
+}
+
+fn parse_number(str: &str) -> f64 {
+    str.parse().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(input: &str) -> String {
+        parse_pattern(input).unwrap().to_string()
+    }
+
+    #[test]
+    fn patterns_without_angles() {
+        assert_eq!(parsed("HexPattern(EAST)"), "HexPattern(EAST)");
+        assert_eq!(parsed("<east>"), "HexPattern(EAST)");
+        assert_eq!(parsed("west"), "HexPattern(WEST)");
+    }
+
+    #[test]
+    fn whitespace_is_ignored() {
+        for input in [
+            "HexPattern(EAST )",
+            "HexPattern( EAST)",
+            "HexPattern(\tEAST\n)",
+            "HexPattern (EAST)",
+            "  HexPattern(EAST)  ",
+        ] {
+            assert_eq!(parsed(input), "HexPattern(EAST)", "{input:?}");
+        }
+        for input in [
+            "HexPattern( EAST qaq )",
+            "HexPattern(EAST   qaq)",
+            "HexPattern(EAST\tqaq)",
+            "HexPattern(EAST , qaq)",
+            "< EAST,qaq >",
+        ] {
+            assert_eq!(parsed(input), "HexPattern(EAST qaq)", "{input:?}");
+        }
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        assert!(matches!(
+            parse_pattern("HexPattern(UP qaq)"),
+            Err(PatternParseError::InvalidStartDirection { direction, .. }) if direction == "UP"
+        ));
+        assert!(matches!(
+            parse_pattern("HexPattern(EAST qxq)"),
+            Err(PatternParseError::InvalidAngle { angle: 'x', .. })
+        ));
+        assert!(matches!(
+            parse_pattern("HexPattern(EAST qaq"),
+            Err(PatternParseError::HangingHexPattern(_))
+        ));
+        assert!(matches!(
+            parse_pattern("EAST qaq qaq"),
+            Err(PatternParseError::InvalidParts(_))
+        ));
+    }
+
+    #[test]
+    fn patterns_in_lists() {
+        let iotas =
+            parse_iotas("HexPattern(EAST), [HexPattern(EAST ), HexPattern(UP qaq)]").unwrap();
+        assert!(
+            matches!(&iotas[0].iota, Iota::Pattern(pattern) if pattern.to_string() == "HexPattern(EAST)")
+        );
+
+        let Iota::List(list) = &iotas[1].iota else {
+            panic!("expected a list, got {:?}", iotas[1].iota);
+        };
+        assert!(
+            matches!(&list[0].iota, Iota::Pattern(pattern) if pattern.to_string() == "HexPattern(EAST)")
+        );
+        assert!(matches!(&list[1].iota, Iota::Unknown(text) if text == "HexPattern(UP qaq)"));
+    }
+
+    fn iotas(input: &str) -> Vec<String> {
+        parse_iotas(input)
+            .unwrap()
+            .iter()
+            .map(|iota| match &iota.iota {
+                Iota::Unknown(text) => format!("?{text}"),
+                iota => iota.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(
+            iotas("1, -2, 3.25, 1.5e3, 2E-2, -0.5e+1"),
+            ["1", "-2", "3.25", "1500", "0.02", "-5"]
+        );
+        //numbers have to end where the iota does
+        assert_eq!(iotas("1x, 2.5.3"), ["?1x", "?2.5.3"]);
+    }
+
+    #[test]
+    fn vectors() {
+        assert_eq!(
+            iotas("(1, 2, 3), ( -1.5 ,0,1e2 )"),
+            ["(1, 2, 3)", "(-1.5, 0, 100)"]
+        );
+        assert!(matches!(
+            parse_iotas("(1, 2, 3)").unwrap()[0].iota,
+            Iota::Vector(x, y, z) if (x, y, z) == (1.0, 2.0, 3.0)
+        ));
+    }
+
+    #[test]
+    fn strings() {
+        let parsed =
+            parse_iotas(r#""hello, world", "say \"hi\"", "back\\slash", "C:\path""#).unwrap();
+        let strings: Vec<&str> = parsed
+            .iter()
+            .map(|iota| match &iota.iota {
+                Iota::String(string) => &string[..],
+                other => panic!("expected a string, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            strings,
+            ["hello, world", "say \"hi\"", "back\\slash", "C:\\path"]
+        );
+
+        //strings are written back out escaped so they parse the same way
+        for iota in &parsed {
+            let written = iota.iota.to_string();
+            let reparsed = parse_iotas(&written).unwrap();
+            assert_eq!(reparsed[0].iota.to_string(), written);
+        }
+        assert_eq!(parsed[1].iota.to_string(), r#""say \"hi\"""#);
+    }
+
+    //a broken iota doesn't stop the ones around it from being parsed
+    #[test]
+    fn unknowns() {
+        assert_eq!(
+            iotas("EAST qaq, (1, 2), WEST qqq"),
+            ["HexPattern(EAST qaq)", "?(1", "?2)", "HexPattern(WEST qqq)"]
+        );
+        assert_eq!(
+            iotas("(a,b), 1), Mind's Reflection, [1, foo]"),
+            ["?(a", "?b)", "?1)", "?Mind's Reflection", "[1, foo]"]
+        );
+        assert_eq!(iotas("\"unterminated"), ["?\"unterminated"]);
+    }
+
+    #[test]
+    fn spans() {
+        let parsed = parse_iotas("EAST qaq,  12 , [3]").unwrap();
+        let spans: Vec<_> = parsed.iter().map(|iota| iota.span.clone()).collect();
+        assert_eq!(spans, [0..8, 11..13, 16..19]);
+
+        let Iota::List(list) = &parsed[2].iota else {
+            panic!("expected a list");
+        };
+        assert_eq!(list[0].span, 17..18);
+    }
+
+    #[test]
+    fn error_spans() {
+        //unclosed lists and stray brackets can't be turned into unknown iotas
+        let span = parse_iotas("1, [2, 3").unwrap_err().span;
+        assert!((3..=8).contains(&span.start), "{span:?}");
+        assert_eq!(parse_iotas("1, 2]").unwrap_err().span, 4..4);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::pattern_utils::{Angle, Direction};
+
+use super::Pattern;
+
+//a pattern without the direction it was drawn in
+//the game only uses the angles to tell patterns apart so two patterns with the same
+//canonical form are the same pattern no matter which way they were drawn
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CanonicalPattern(Vec<Angle>);
+
+impl CanonicalPattern {
+    pub fn get_angles(&self) -> &[Angle] {
+        &self.0
+    }
+    pub fn get_angle_sig(&self) -> String {
+        self.0.iter().map(|&angle| char::from(angle)).collect()
+    }
+    //the pattern drawn starting east
+    pub fn to_pattern(&self) -> Pattern {
+        Pattern::new(Direction::East, self.0.clone())
+    }
+}
+
+impl From<&Pattern> for CanonicalPattern {
+    fn from(value: &Pattern) -> Self {
+        Self(value.angles.clone())
+    }
+}
+
+impl Pattern {
+    pub fn canonical(&self) -> CanonicalPattern {
+        CanonicalPattern::from(self)
+    }
+
+    //for when a pattern drawn backwards should count as the same pattern
+    //picks whichever of the two ways of drawing it comes first
+    pub fn canonical_reversible(&self) -> CanonicalPattern {
+        self.canonical().min(self.reversed().canonical())
+    }
+
+    //the same pattern drawn starting east
+    pub fn normalized(&self) -> Pattern {
+        Pattern::new(Direction::East, self.angles.clone())
+    }
+
+    //the same lines drawn from the other end
+    pub fn reversed(&self) -> Pattern {
+        let end = self.path[self.path.len() - 1];
+        let before_end = self.path[self.path.len() - 2];
+        let end_direction =
+            Direction::try_from((before_end.0 - end.0, before_end.1 - end.1)).unwrap();
+
+        let angles = self
+            .angles
+            .iter()
+            .rev()
+            .copied()
+            .map(reverse_angle)
+            .collect();
+
+        Pattern::new(end_direction, angles)
+    }
+}
+
+//the angle a turn becomes when the pattern is drawn from the other end
+//(turning right one way is turning left the other way)
+pub fn reverse_angle(angle: Angle) -> Angle {
+    Angle::try_from((6 - angle as u8) % 6).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::parse::parse_pattern;
+
+    fn pattern(text: &str) -> Pattern {
+        parse_pattern(text).unwrap()
+    }
+
+    #[test]
+    fn reversed() {
+        assert_eq!(
+            pattern("HexPattern(EAST qaq)").reversed().to_string(),
+            "HexPattern(NORTH_EAST ede)"
+        );
+        assert_eq!(
+            pattern("HexPattern(NORTH_EAST w)").reversed().to_string(),
+            "HexPattern(SOUTH_WEST w)"
+        );
+
+        for text in [
+            "HexPattern(EAST qaq)",
+            "HexPattern(NORTH_WEST qwaeawq)",
+            "HexPattern(SOUTH_EAST aqaaedwd)",
+            "HexPattern(WEST)",
+        ] {
+            let pattern = pattern(text);
+            let reversed = pattern.reversed();
+
+            //the same lines, just walked the other way
+            let offset = (
+                pattern.path[pattern.path.len() - 1].0 - reversed.path[0].0,
+                pattern.path[pattern.path.len() - 1].1 - reversed.path[0].1,
+            );
+            for (a, b) in pattern.path.iter().rev().zip(&reversed.path) {
+                assert_eq!((a.0 - b.0, a.1 - b.1), offset, "{text}");
+            }
+
+            let back = reversed.reversed();
+            assert_eq!(back.get_start_direction(), pattern.get_start_direction());
+            assert_eq!(back.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn canonical() {
+        let east = pattern("HexPattern(EAST qaq)");
+        let west = pattern("HexPattern(WEST qaq)");
+        assert_eq!(east.canonical(), west.canonical());
+        assert_eq!(east.canonical().get_angle_sig(), "qaq");
+        assert_eq!(
+            west.canonical().to_pattern().to_string(),
+            "HexPattern(EAST qaq)"
+        );
+        assert_eq!(west.normalized().to_string(), "HexPattern(EAST qaq)");
+
+        //drawn backwards it's a different pattern
+        assert_ne!(east.canonical(), east.reversed().canonical());
+
+        let unique: HashSet<_> = ["EAST qaq", "WEST qaq", "NORTH_EAST qaq", "EAST aqa"]
+            .into_iter()
+            .map(|text| pattern(text).canonical())
+            .collect();
+        assert_eq!(unique.len(), 2);
+    }
+
+    #[test]
+    fn canonical_reversible() {
+        let pattern = pattern("HexPattern(EAST qaq)");
+        let reversed = pattern.reversed();
+        assert_eq!(
+            pattern.canonical_reversible(),
+            reversed.canonical_reversible()
+        );
+        assert_eq!(
+            pattern.canonical_reversible(),
+            pattern.canonical().min(reversed.canonical())
+        );
+        assert_ne!(
+            pattern.canonical_reversible(),
+            self::pattern("HexPattern(EAST aqa)").canonical_reversible()
+        );
+    }
+}
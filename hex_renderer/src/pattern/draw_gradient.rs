@@ -1,17 +1,18 @@
 use std::collections::HashMap;
 
-use tiny_skia::{
-    Color, GradientStop, LinearGradient, Paint, Pixmap, SpreadMode, Stroke, Transform,
-};
+use tiny_skia::Color;
 
-use crate::pattern_utils::{Coord, HexCoord, LineDrawer};
+use crate::{
+    canvas::{Brush, Canvas, Stroke},
+    pattern_utils::{Coord, HexCoord, LineDrawer},
+};
 
 use super::Pattern;
 
 #[allow(clippy::too_many_arguments)]
 pub fn draw_gradient_lines(
     pattern: &Pattern,
-    pixmap: &mut Pixmap,
+    canvas: &mut impl Canvas,
     stroke: &Stroke,
     origin: HexCoord,
     scale: f32,
@@ -55,15 +56,11 @@ pub fn draw_gradient_lines(
             }
         }
     }
-    let paint = Paint::<'_> {
-        anti_alias: false,
-        ..Default::default()
-    };
-    let mut line_drawer = LineDrawer::new(origin, stroke.clone(), paint);
-
     let mut prev_shade_color =
         Color::from_rgba(cur_color[0], cur_color[1], cur_color[2], cur_color[3]).unwrap();
 
+    let mut line_drawer = LineDrawer::new(origin, stroke.clone(), Brush::Solid(prev_shade_color));
+
     for i in 1..pattern.path.len() {
         let mut loc_next = origin + HexCoord::from(pattern.path[i]) * scale;
 
@@ -81,19 +78,12 @@ pub fn draw_gradient_lines(
         let cur_col =
             Color::from_rgba(cur_color[0], cur_color[1], cur_color[2], cur_color[3]).unwrap();
 
-        line_drawer.set_shader(
-            LinearGradient::new(
-                tiny_skia::Point::from_xy(loc_prev.0, loc_prev.1),
-                tiny_skia::Point::from_xy(loc_next.0, loc_next.1),
-                vec![
-                    GradientStop::new(0.0, prev_shade_color),
-                    GradientStop::new(1.0, cur_col),
-                ],
-                SpreadMode::Pad,
-                Transform::identity(),
-            )
-            .unwrap(),
-        );
+        line_drawer.set_brush(Brush::LinearGradient {
+            start: loc_prev,
+            end: loc_next,
+            start_color: prev_shade_color,
+            end_color: cur_col,
+        });
 
         if bent_corners
             && visit_count.get(&pattern.path[i]).unwrap() > &1
@@ -113,7 +103,7 @@ pub fn draw_gradient_lines(
         prev_shade_color = cur_col;
     }
 
-    line_drawer.draw_all(pixmap);
+    line_drawer.draw_all(canvas);
 
     colors[grad_colors.len() - 1]
 }
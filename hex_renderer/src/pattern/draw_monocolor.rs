@@ -1,23 +1,23 @@
 use std::collections::HashMap;
 
-use tiny_skia::{Color, Paint, Pixmap, Stroke};
+use tiny_skia::Color;
 
-use crate::pattern_utils::{Coord, HexCoord, LineDrawer};
+use crate::{
+    canvas::{Brush, Canvas, Stroke},
+    pattern_utils::{Coord, HexCoord, LineDrawer},
+};
 
 use super::Pattern;
 
 pub fn draw_monocolor_lines(
     pattern: &Pattern,
-    pixmap: &mut Pixmap,
+    canvas: &mut impl Canvas,
     stroke: &Stroke,
     origin: HexCoord,
     scale: f32,
     color: Color,
     bent_corners: bool,
 ) {
-    let mut paint = Paint::default();
-    paint.set_color(color);
-
     let mut visit_count: HashMap<Coord, usize> = HashMap::new();
 
     if bent_corners {
@@ -30,7 +30,7 @@ pub fn draw_monocolor_lines(
         }
     }
 
-    let mut line_drawer = LineDrawer::new(origin, stroke.clone(), paint);
+    let mut line_drawer = LineDrawer::new(origin, stroke.clone(), Brush::Solid(color));
 
     for (i, line) in pattern.path.iter().enumerate() {
         let current = HexCoord::from(*line) * scale + origin;
@@ -58,5 +58,5 @@ pub fn draw_monocolor_lines(
         }
     }
 
-    line_drawer.draw_all(pixmap);
+    line_drawer.draw_all(canvas);
 }
@@ -1,10 +1,9 @@
 use std::collections::HashMap;
 
-use tiny_skia::{
-    Color, LineCap, LineJoin, Paint, PathBuilder, Pixmap, Stroke, StrokeDash, Transform,
-};
+use tiny_skia::{Color, LineCap, LineJoin, PathBuilder};
 
 use crate::{
+    canvas::{Brush, Canvas, Stroke},
     options::{CollisionOption, Marker, Triangle},
     pattern::text::draw_text,
     pattern_utils::{ConnectionPoint, Coord, HexCoord, LineDrawer},
@@ -19,11 +18,11 @@ use super::{
 #[allow(clippy::too_many_arguments)]
 pub fn draw_segment_lines(
     pattern: &Pattern,
-    pixmap: &mut Pixmap,
+    canvas: &mut impl Canvas,
     stroke: &Stroke,
     origin: HexCoord,
     scale: f32,
-    colors: &Vec<Color>,
+    colors: &[Color],
     triangles: &Triangle,
     point_radius: f32,
     collisions: &CollisionOption,
@@ -39,9 +38,7 @@ pub fn draw_segment_lines(
 
     let mut last_collision_lane = None;
 
-    let mut paint = Paint::default();
-    paint.set_color(colors[0]);
-    let mut drawer = LineDrawer::new(origin, stroke.clone(), paint);
+    let mut drawer = LineDrawer::new(origin, stroke.clone(), Brush::Solid(colors[0]));
 
     let mut prev_loc = origin;
     let mut prev_point = Coord(0, 0);
@@ -81,7 +78,7 @@ pub fn draw_segment_lines(
     };
 
     let mut collision_stroke = stroke.clone();
-    collision_stroke.dash = StrokeDash::new(vec![scale / 18.0, scale / 16.0], 0.0);
+    collision_stroke.dash = Some(vec![scale / 18.0, scale / 16.0]);
     collision_stroke.line_cap = LineCap::Butt;
     collision_stroke.line_join = LineJoin::Bevel;
 
@@ -209,7 +206,7 @@ pub fn draw_segment_lines(
 
             if collisions >= too_many_lines && !full_dash {
                 if let Some(label) = label {
-                    draw_label(pixmap, label, prev_loc, loc, stroke, scale, collisions);
+                    draw_label(canvas, label, prev_loc, loc, stroke, scale, collisions);
                 }
             }
         }
@@ -234,10 +231,10 @@ pub fn draw_segment_lines(
         prev_point = *point;
     }
 
-    drawer.draw(pixmap);
+    drawer.draw(canvas);
 
     for (triangle, location, next, scaler) in triangle_queue {
-        draw_triangle(triangle, pixmap, location, next, scale * scaler);
+        draw_triangle(triangle, canvas, location, next, scale * scaler);
     }
 
     let cur_loc = origin + HexCoord::from(pattern.path[1]) * scale;
@@ -245,9 +242,9 @@ pub fn draw_segment_lines(
     let mid_point = (cur_loc - prev_loc) / 2.0 + prev_loc;
 
     if let Some(marker) = triangles.to_start_point(colors[0]) {
-        draw_triangle(marker, pixmap, mid_point, cur_loc, scale);
+        draw_triangle(marker, canvas, mid_point, cur_loc, scale);
     }
-    drawer.draw_priority(pixmap);
+    drawer.draw_priority(canvas);
 
     if !ended_on_collision {
         colors[cur_color]
@@ -256,7 +253,7 @@ pub fn draw_segment_lines(
     }
 }
 
-fn get_next_color(cur_color: usize, visited: &Vec<usize>, color_count: usize) -> usize {
+fn get_next_color(cur_color: usize, visited: &[usize], color_count: usize) -> usize {
     if visited.len() >= color_count {
         (cur_color + 1) % color_count
     } else {
@@ -334,7 +331,7 @@ fn calculate_start_end(
 }
 
 fn draw_label(
-    pixmap: &mut Pixmap,
+    canvas: &mut impl Canvas,
     label: &Marker,
     prev_loc: HexCoord,
     loc: HexCoord,
@@ -352,9 +349,6 @@ fn draw_label(
     let point = rotate_point(middle, middle + offset, -90f32.to_radians());
     let line_point = rotate_point(middle, middle + line_offset, -90f32.to_radians());
 
-    let mut paint = Paint::default();
-    paint.set_color(label.color);
-
     let stroke = Stroke {
         width: radius * 2.0,
         line_cap: LineCap::Butt,
@@ -368,11 +362,11 @@ fn draw_label(
     path.line_to(point.0, point.1);
     let path = path.finish().unwrap();
 
-    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    canvas.stroke_path(&path, &Brush::Solid(label.color), &stroke);
 
-    draw_point(pixmap, point, radius, label.color);
+    draw_point(canvas, point, radius, label.color);
     draw_text(
-        pixmap,
+        canvas,
         &format!("{collisions}"),
         Color::BLACK,
         point,
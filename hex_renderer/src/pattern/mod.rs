@@ -1,6 +1,9 @@
 mod pattern_internal;
 pub use pattern_internal::*;
 
+mod canonical;
+pub use canonical::{reverse_angle, CanonicalPattern};
+
 mod point;
 mod triangle;
 
@@ -8,3 +11,5 @@ mod draw_gradient;
 mod draw_monocolor;
 mod draw_segments;
 mod text;
+pub use text::Text;
+pub(crate) use text::{draw_label, measure_label};
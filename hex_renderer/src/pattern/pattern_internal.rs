@@ -1,13 +1,14 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+};
 
-use tiny_skia::{Color, LineCap, LineJoin, Pixmap, Stroke};
+use tiny_skia::{Color, LineCap, LineJoin};
 
 use crate::{
+    canvas::{Canvas, Stroke},
     options::{Intersections, Lines, Point},
-    pattern_utils::{
-        Angle, AngleParseError, ConnectionPoint, Coord, Direction, DirectionParseError,
-        DynamicList, HexCoord,
-    },
+    pattern_utils::{Angle, ConnectionPoint, Coord, Direction, DynamicList, HexCoord},
 };
 
 use super::{
@@ -126,7 +127,7 @@ impl Pattern {
     #[allow(clippy::too_many_arguments)]
     pub fn draw_pattern(
         &self,
-        pixmap: &mut Pixmap,
+        canvas: &mut impl Canvas,
         origin: HexCoord,
         scale: f32,
         line_thickness: f32,
@@ -148,7 +149,7 @@ impl Pattern {
 
         match line_options {
             Lines::Monocolor { color, bent } => {
-                draw_monocolor_lines(self, pixmap, &stroke, origin, scale, *color, *bent);
+                draw_monocolor_lines(self, canvas, &stroke, origin, scale, *color, *bent);
                 end_colors = (*color, *color);
             }
             Lines::Gradient {
@@ -157,15 +158,15 @@ impl Pattern {
                 bent,
             } => {
                 if colors.len() < 2 {
-                    let col = *colors.get(0).unwrap_or(&Color::WHITE);
+                    let col = *colors.first().unwrap_or(&Color::WHITE);
                     end_colors = (col, col);
-                    draw_monocolor_lines(self, pixmap, &stroke, origin, scale, col, *bent);
+                    draw_monocolor_lines(self, canvas, &stroke, origin, scale, col, *bent);
                 } else {
                     end_colors = (
                         colors[0],
                         draw_gradient_lines(
                             self,
-                            pixmap,
+                            canvas,
                             &stroke,
                             origin,
                             scale,
@@ -185,7 +186,7 @@ impl Pattern {
                     colors[0],
                     draw_segment_lines(
                         self,
-                        pixmap,
+                        canvas,
                         &stroke,
                         origin,
                         scale,
@@ -201,7 +202,7 @@ impl Pattern {
         match point_options {
             Intersections::Nothing => (),
             Intersections::UniformPoints(point) => {
-                draw_points(&self.points, pixmap, origin, scale, point);
+                draw_points(&self.points, canvas, origin, scale, point);
             }
             Intersections::EndsAndMiddle { start, end, middle } => {
                 let start_point = self.path[0];
@@ -210,9 +211,9 @@ impl Pattern {
                 let start = start.into_point(end_colors.0);
                 let end = end.into_point(end_colors.1);
 
-                draw_points(&vec![start_point], pixmap, origin, scale, &start);
+                draw_points(&[start_point], canvas, origin, scale, &start);
                 if start_point != end_point {
-                    draw_points(&vec![end_point], pixmap, origin, scale, &end);
+                    draw_points(&[end_point], canvas, origin, scale, &end);
                 }
                 let middle_points: Vec<Coord> = self
                     .points
@@ -221,7 +222,7 @@ impl Pattern {
                     .filter(|&point| point != start_point && point != end_point)
                     .collect();
 
-                draw_points(&middle_points, pixmap, origin, scale, middle);
+                draw_points(&middle_points, canvas, origin, scale, middle);
             }
         }
 
@@ -238,8 +239,8 @@ impl Pattern {
             && coord.0 < self.right_perimiter[index].0
         {
             draw_points(
-                &vec![Coord(x_coord, y_coord)],
-                pixmap,
+                &[Coord(x_coord, y_coord)],
+                canvas,
                 origin,
                 scale,
                 center_dot,
@@ -248,6 +249,31 @@ impl Pattern {
     }
 }
 
+impl Pattern {
+    pub fn get_start_direction(&self) -> Direction {
+        let (start, next) = (self.path[0], self.path[1]);
+        Direction::try_from((next.0 - start.0, next.1 - start.1)).unwrap()
+    }
+    pub fn get_angles(&self) -> &[Angle] {
+        &self.angles
+    }
+    pub fn get_angle_sig(&self) -> String {
+        self.angles.iter().map(|&angle| char::from(angle)).collect()
+    }
+    //whether any line is drawn over more than once
+    pub fn is_self_colliding(&self) -> bool {
+        !self.collisions.is_empty()
+    }
+    //how many times a line is drawn over one that's already there
+    pub fn get_collision_count(&self) -> usize {
+        self.collisions.values().map(|&count| count as usize).sum()
+    }
+    //the top left and bottom right corners of the pattern (in line lengths)
+    pub fn get_bounds(&self) -> (HexCoord, HexCoord) {
+        (self.top_left_bound, self.bottom_right_bound)
+    }
+}
+
 impl PatternVariant {
     pub fn get_inner(&self) -> &Pattern {
         match self {
@@ -255,6 +281,20 @@ impl PatternVariant {
             PatternVariant::Monocolor(pattern) => pattern,
         }
     }
+    //only the first segments lines of the pattern (used when animating the stroke order)
+    pub(crate) fn truncated(&self, segments: usize) -> PatternVariant {
+        let truncate = |pattern: &Pattern| {
+            let links = segments.clamp(1, pattern.angles.len() + 1) - 1;
+            Pattern::new(
+                pattern.get_start_direction(),
+                pattern.angles[..links].to_vec(),
+            )
+        };
+        match self {
+            PatternVariant::Normal(pattern) => PatternVariant::Normal(truncate(pattern)),
+            PatternVariant::Monocolor(pattern) => PatternVariant::Monocolor(truncate(pattern)),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -264,45 +304,26 @@ pub enum PatternParseError {
     InvalidStartDirection { input: String, direction: String },
     InvalidAngle { input: String, angle: char },
 }
+//HexPattern(EAST qaq), the same notation the game uses
+impl Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.angles.is_empty() {
+            write!(f, "HexPattern({})", self.get_start_direction())
+        } else {
+            write!(
+                f,
+                "HexPattern({} {})",
+                self.get_start_direction(),
+                self.get_angle_sig()
+            )
+        }
+    }
+}
+
 impl TryFrom<&str> for Pattern {
     type Error = PatternParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut parts: Vec<&str> = value.trim().split(' ').collect();
-
-        if parts.len() != 2 {
-            return Err(Self::Error::InvalidParts(value.to_string()));
-        }
-
-        if parts[0].to_lowercase().starts_with("hexpattern(") {
-            if parts[1].ends_with(')') {
-                parts[0] = &parts[0]["hexpattern(".len()..];
-                parts[1] = &parts[1][..parts[1].len() - 1];
-            } else {
-                return Err(Self::Error::HangingHexPattern(value.to_string()));
-            }
-        }
-
-        let direction: Direction =
-            parts[0]
-                .try_into()
-                .map_err(|err| PatternParseError::InvalidStartDirection {
-                    input: value.to_string(),
-                    direction: match err {
-                        DirectionParseError::InvalidNumber(_) => unreachable!(),
-                        DirectionParseError::InvalidStr(str) => str,
-                    },
-                })?;
-
-        let angles: Vec<Angle> = parts[1]
-            .chars()
-            .map(Angle::try_from)
-            .collect::<Result<Vec<Angle>, AngleParseError>>()
-            .map_err(|err| PatternParseError::InvalidAngle {
-                input: value.to_string(),
-                angle: err.0,
-            })?;
-
-        Ok(Pattern::new(direction, angles))
+        crate::parse::parse_pattern(value)
     }
 }
@@ -1,54 +1,44 @@
-use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, Transform};
+use tiny_skia::{Color, PathBuilder};
 
 use crate::{
+    canvas::{Brush, Canvas},
     options::Point,
     pattern_utils::{Coord, HexCoord},
 };
 
 pub fn draw_points(
-    points: &Vec<Coord>,
-    pixmap: &mut Pixmap,
+    points: &[Coord],
+    canvas: &mut impl Canvas,
     origin: HexCoord,
     scale: f32,
     point: &Point,
 ) {
-    let mut paint = Paint::default();
-    let mut paint2 = Paint::default();
-
     match point {
         Point::None => (),
         Point::Single(marker) => {
-            paint.set_color(marker.color);
+            let brush = Brush::Solid(marker.color);
             for point in points {
                 let loc = HexCoord::from(*point) * scale + origin;
                 let path = PathBuilder::from_circle(loc.0, loc.1, marker.radius * scale).unwrap();
-                pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), None);
+                canvas.fill_path(&path, &brush);
             }
         }
         Point::Double { inner, outer } => {
-            paint.set_color(outer.color);
-            paint2.set_color(inner.color);
+            let brush = Brush::Solid(outer.color);
+            let brush2 = Brush::Solid(inner.color);
             for point in points {
                 let loc = HexCoord::from(*point) * scale + origin;
                 let path = PathBuilder::from_circle(loc.0, loc.1, outer.radius * scale).unwrap();
-                pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), None);
+                canvas.fill_path(&path, &brush);
 
                 let path = PathBuilder::from_circle(loc.0, loc.1, inner.radius * scale).unwrap();
-                pixmap.fill_path(
-                    &path,
-                    &paint2,
-                    FillRule::Winding,
-                    Transform::default(),
-                    None,
-                );
+                canvas.fill_path(&path, &brush2);
             }
         }
     }
 }
 
-pub fn draw_point(pixmap: &mut Pixmap, center: HexCoord, radius: f32, color: Color) {
+pub fn draw_point(canvas: &mut impl Canvas, center: HexCoord, radius: f32, color: Color) {
     let path = PathBuilder::from_circle(center.0, center.1, radius).unwrap();
-    let mut paint = Paint::default();
-    paint.set_color(color);
-    pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), None);
+    canvas.fill_path(&path, &Brush::Solid(color));
 }
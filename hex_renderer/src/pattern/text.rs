@@ -1,8 +1,11 @@
 use lazy_static::lazy_static;
-use rusttype::{point, Font, Scale};
-use tiny_skia::{Color, Pixmap, Transform};
+use rusttype::{point, Font, OutlineBuilder, PositionedGlyph, Scale};
+use tiny_skia::{Color, Path, PathBuilder, Pixmap};
 
-use crate::pattern_utils::HexCoord;
+use crate::{
+    canvas::{Brush, Canvas},
+    pattern_utils::HexCoord,
+};
 
 const HEIGHT_SCALE: f32 = 22.0 / 30.0;
 const WIDTH_SCALE: f32 = 0.48333326;
@@ -14,7 +17,7 @@ lazy_static! {
     };
 }
 
-pub fn draw_text(pixmap: &mut Pixmap, str: &str, mut color: Color, center: HexCoord, radius: f32) {
+pub fn draw_text(canvas: &mut impl Canvas, str: &str, color: Color, center: HexCoord, radius: f32) {
     let rect_width = radius * 2.0_f32.sqrt();
 
     let scaler = rect_width / (HEIGHT_SCALE).max(WIDTH_SCALE * str.len() as f32);
@@ -26,37 +29,182 @@ pub fn draw_text(pixmap: &mut Pixmap, str: &str, mut color: Color, center: HexCo
 
     let offset = point(0.0, height - 3.0 / 30.0 * scaler);
 
-    let mut tmp_map = Pixmap::new(width as u32, height as u32).unwrap();
+    canvas.fill_text(&Text {
+        glyphs: FONT.layout(str, scale, offset).collect(),
+        color,
+        top_left: center - HexCoord(width, height) / 2.0,
+        size: HexCoord(width, height),
+    });
+}
+
+/// A line of text laid out and ready to be drawn onto a [`Canvas`].
+///
+/// Raster canvases draw its glyphs pixel by pixel and vector canvases draw their outlines.
+pub struct Text {
+    glyphs: Vec<PositionedGlyph<'static>>,
+    color: Color,
+    top_left: HexCoord,
+    size: HexCoord,
+}
+
+impl Text {
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    //the glyphs drawn onto a pixmap the size of the text along with where it goes
+    pub(crate) fn rasterize(&self) -> Option<(i32, i32, Pixmap)> {
+        let (width, height) = (self.size.0, self.size.1);
+        let mut tmp_map = Pixmap::new(width as u32, height as u32)?;
+
+        let pixels = tmp_map.pixels_mut();
+        let mut color = self.color;
+
+        for g in &self.glyphs {
+            if let Some(bb) = g.pixel_bounding_box() {
+                g.draw(|x, y, v| {
+                    let x = x as i32 + bb.min.x;
+                    let y = y as i32 + bb.min.y;
+
+                    if x >= 0 && x < width as i32 && y >= 0 && y < height as i32 {
+                        let x = x as usize;
+                        let y = y as usize;
+                        color.set_alpha(v);
+                        pixels[x + y * width as usize] = color.premultiply().to_color_u8();
+                    }
+                })
+            }
+        }
+        Some((self.top_left.0 as i32, self.top_left.1 as i32, tmp_map))
+    }
 
-    let glyphs: Vec<_> = FONT.layout(str, scale, offset).collect();
+    /// The outlines of the glyphs as a single path.
+    pub fn outline(&self) -> Option<Path> {
+        glyph_outline(&self.glyphs, self.top_left)
+    }
+}
 
-    let pixels = tmp_map.pixels_mut();
+fn glyph_outline(glyphs: &[PositionedGlyph], offset: HexCoord) -> Option<Path> {
+    let mut outline = GlyphOutline {
+        path: PathBuilder::new(),
+        offset,
+    };
 
     for g in glyphs {
         if let Some(bb) = g.pixel_bounding_box() {
-            g.draw(|x, y, v| {
-                let x = x as i32 + bb.min.x;
-                let y = y as i32 + bb.min.y;
-
-                if x >= 0 && x < width as i32 && y >= 0 && y < height as i32 {
-                    let x = x as usize;
-                    let y = y as usize;
-                    color.set_alpha(v);
-                    pixels[x + y * width as usize] = color.premultiply().to_color_u8();
-                }
-            })
+            outline.offset = offset + HexCoord(bb.min.x as f32, bb.min.y as f32);
+            g.build_outline(&mut outline);
         }
     }
-    let map_offset = center - HexCoord(width, height) / 2.0;
+    outline.path.finish()
+}
+
+//the width and height of a line of text drawn by draw_label with the same size
+pub(crate) fn measure_label(str: &str, size: f32) -> (f32, f32) {
+    let scale = Scale::uniform(size);
+    let metrics = FONT.v_metrics(scale);
+
+    let width = FONT
+        .layout(str, scale, point(0.0, 0.0))
+        .last()
+        .map_or(0.0, |g| {
+            g.position().x + g.unpositioned().h_metrics().advance_width
+        });
+
+    (width, metrics.ascent - metrics.descent)
+}
+
+//draws a single line of text (size being the font size) with the middle of its top edge at top
+pub(crate) fn draw_label(
+    canvas: &mut impl Canvas,
+    str: &str,
+    color: Color,
+    top: HexCoord,
+    size: f32,
+) {
+    let scale = Scale::uniform(size);
+    let (width, _) = measure_label(str, size);
+
+    let map_offset = top - HexCoord(width / 2.0, 0.0);
+
+    let glyphs: Vec<_> = FONT
+        .layout(str, scale, point(0.0, FONT.v_metrics(scale).ascent))
+        .collect();
+
+    if let Some(path) = glyph_outline(&glyphs, map_offset) {
+        canvas.fill_path(&path, &Brush::Solid(color));
+    }
+}
+
+struct GlyphOutline {
+    path: PathBuilder,
+    offset: HexCoord,
+}
+
+impl OutlineBuilder for GlyphOutline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.path.move_to(x + self.offset.0, y + self.offset.1);
+    }
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.path.line_to(x + self.offset.0, y + self.offset.1);
+    }
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.path.quad_to(
+            x1 + self.offset.0,
+            y1 + self.offset.1,
+            x + self.offset.0,
+            y + self.offset.1,
+        );
+    }
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.path.cubic_to(
+            x1 + self.offset.0,
+            y1 + self.offset.1,
+            x2 + self.offset.0,
+            y2 + self.offset.1,
+            x + self.offset.0,
+            y + self.offset.1,
+        );
+    }
+    fn close(&mut self) {
+        self.path.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        defaults,
+        grids::{GridDraw, HexGrid},
+        parse::parse_pattern,
+    };
+
+    //fnv-1a, so the snapshot doesn't need an image checked in
+    fn hash(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
 
-    let paint = tiny_skia::PixmapPaint::default();
+    //the labels on overloaded collisions are rasterized the same way they always were in pngs
+    #[test]
+    fn collision_labels_snapshot() {
+        let patterns = [
+            "HexPattern(NORTH_EAST qqqqqqqqqqqqqqqqqq)",
+            "HexPattern(EAST dadadadadadadadada)",
+            "HexPattern(EAST qaqaqaqaqaqaqaqaqaqaqaqa)",
+            "HexPattern(EAST aadaa)",
+        ]
+        .into_iter()
+        .map(|pattern| parse_pattern(pattern).unwrap())
+        .collect();
 
-    pixmap.draw_pixmap(
-        map_offset.0 as i32,
-        map_offset.1 as i32,
-        tmp_map.as_ref(),
-        &paint,
-        Transform::identity(),
-        None,
-    );
+        let pixmap = HexGrid::new_normal(patterns, 10)
+            .unwrap()
+            .draw_grid(40.0, &defaults::SEGMENT)
+            .unwrap();
+
+        assert_eq!((pixmap.width(), pixmap.height()), (340, 401));
+        assert_eq!(hash(pixmap.data()), 0xee4927a64228df);
+    }
 }
@@ -1,31 +1,30 @@
-use tiny_skia::{FillRule, Paint, Path, PathBuilder, Pixmap, Transform};
+use tiny_skia::{Path, PathBuilder};
 
-use crate::{options::Point, pattern_utils::HexCoord};
+use crate::{
+    canvas::{Brush, Canvas},
+    options::Point,
+    pattern_utils::HexCoord,
+};
 
 pub fn draw_triangle(
     triangle: Point,
-    pixmap: &mut Pixmap,
+    canvas: &mut impl Canvas,
     location: HexCoord,
     next: HexCoord,
     scale: f32,
 ) {
-    let mut paint = Paint::default();
-
     match triangle {
         Point::None => (),
         Point::Single(marker) => {
             let path = generate_triangle_path(location, next, marker.radius * scale);
-            paint.set_color(marker.color);
-            pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), None);
+            canvas.fill_path(&path, &Brush::Solid(marker.color));
         }
         Point::Double { inner, outer } => {
             let path = generate_triangle_path(location, next, outer.radius * scale);
-            paint.set_color(outer.color);
-            pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), None);
+            canvas.fill_path(&path, &Brush::Solid(outer.color));
 
             let path = generate_triangle_path(location, next, inner.radius * scale);
-            paint.set_color(inner.color);
-            pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::default(), None);
+            canvas.fill_path(&path, &Brush::Solid(inner.color));
         }
     }
 }
@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Angle {
     Forward = 0,
     Right = 1,
@@ -26,3 +26,32 @@ impl TryFrom<char> for Angle {
         }
     }
 }
+
+impl TryFrom<u8> for Angle {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Angle::Forward),
+            1 => Ok(Angle::Right),
+            2 => Ok(Angle::BackRight),
+            3 => Ok(Angle::Back),
+            4 => Ok(Angle::BackLeft),
+            5 => Ok(Angle::Left),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<Angle> for char {
+    fn from(value: Angle) -> Self {
+        match value {
+            Angle::Forward => 'w',
+            Angle::Right => 'e',
+            Angle::BackRight => 'd',
+            Angle::Back => 's',
+            Angle::BackLeft => 'a',
+            Angle::Left => 'q',
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use super::Angle;
-use std::ops::Add;
+use std::{
+    fmt::{self, Display},
+    ops::{Add, Sub},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
@@ -19,6 +22,15 @@ impl Add<Angle> for Direction {
     }
 }
 
+//the angle that turns rhs into self
+impl Sub for Direction {
+    type Output = Angle;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        ((self as u8 + 6 - rhs as u8) % 6).try_into().unwrap()
+    }
+}
+
 impl TryFrom<(i32, i32)> for Direction {
     type Error = ();
 
@@ -74,6 +86,21 @@ impl TryFrom<u8> for Direction {
     }
 }
 
+//the names the game uses, eg. NORTH_EAST
+impl Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Direction::NorthEast => "NORTH_EAST",
+            Direction::East => "EAST",
+            Direction::SouthEast => "SOUTH_EAST",
+            Direction::SouthWest => "SOUTH_WEST",
+            Direction::West => "WEST",
+            Direction::NorthWest => "NORTH_WEST",
+        };
+        f.write_str(name)
+    }
+}
+
 impl TryFrom<&str> for Direction {
     type Error = DirectionParseError;
 
@@ -1,17 +1,19 @@
-use tiny_skia::{Color, Paint, Path, PathBuilder, Pixmap, Shader, Stroke, Transform};
+use tiny_skia::{Color, Path, PathBuilder};
+
+use crate::canvas::{Brush, Canvas, Stroke};
 
 use super::HexCoord;
 
-pub struct LineDrawer<'a> {
+pub struct LineDrawer {
     prev_point: HexCoord,
-    paths: Vec<(Path, Stroke, Paint<'a>)>,
-    priority_paths: Vec<(Path, Stroke, Paint<'a>)>,
+    paths: Vec<(Path, Stroke, Brush)>,
+    priority_paths: Vec<(Path, Stroke, Brush)>,
     path: PathBuilder,
     stroke: Stroke,
-    paint: Paint<'a>,
+    brush: Brush,
 }
-impl<'a> LineDrawer<'a> {
-    pub fn new(start_point: HexCoord, stroke: Stroke, paint: Paint<'a>) -> Self {
+impl LineDrawer {
+    pub fn new(start_point: HexCoord, stroke: Stroke, brush: Brush) -> Self {
         let mut path = PathBuilder::new();
         path.move_to(start_point.0, start_point.1);
         Self {
@@ -20,7 +22,7 @@ impl<'a> LineDrawer<'a> {
             priority_paths: Vec::new(),
             path,
             stroke,
-            paint,
+            brush,
         }
     }
     pub fn line_to(&mut self, point: HexCoord) {
@@ -28,41 +30,39 @@ impl<'a> LineDrawer<'a> {
         self.path.line_to(point.0, point.1);
     }
 
-    fn new_path(&mut self, start_point: HexCoord, mut stroke: Stroke, mut paint: Paint<'a>) {
+    fn new_path(&mut self, start_point: HexCoord, mut stroke: Stroke, mut brush: Brush) {
         let mut tmp_path = PathBuilder::new();
         tmp_path.move_to(start_point.0, start_point.1);
 
         self.prev_point = start_point;
 
         std::mem::swap(&mut tmp_path, &mut self.path);
-        std::mem::swap(&mut paint, &mut self.paint);
+        std::mem::swap(&mut brush, &mut self.brush);
         std::mem::swap(&mut stroke, &mut self.stroke);
         if let Some(path) = tmp_path.finish() {
-            self.paths.push((path, stroke, paint));
+            self.paths.push((path, stroke, brush));
         }
     }
 
     pub fn move_to(&mut self, point: HexCoord) {
-        self.new_path(point, self.stroke.clone(), self.paint.clone());
+        self.new_path(point, self.stroke.clone(), self.brush);
     }
 
     pub fn set_color(&mut self, color: Color) {
-        self.new_path(self.prev_point, self.stroke.clone(), self.paint.clone());
-        self.paint.set_color(color);
+        self.new_path(self.prev_point, self.stroke.clone(), self.brush);
+        self.brush = Brush::Solid(color);
     }
 
-    pub fn set_shader(&mut self, shader: Shader<'a>) {
-        let mut paint = self.paint.clone();
-        paint.shader = shader;
-        self.new_path(self.prev_point, self.stroke.clone(), paint);
+    pub fn set_brush(&mut self, brush: Brush) {
+        self.new_path(self.prev_point, self.stroke.clone(), brush);
     }
     pub fn set_width(&mut self, width: f32) {
-        self.new_path(self.prev_point, self.stroke.clone(), self.paint.clone());
+        self.new_path(self.prev_point, self.stroke.clone(), self.brush);
         self.stroke.width = width;
     }
 
     pub fn set_stroke(&mut self, stroke: Stroke) {
-        self.new_path(self.prev_point, stroke, self.paint.clone());
+        self.new_path(self.prev_point, stroke, self.brush);
     }
 
     pub fn priority_finish(&mut self) {
@@ -73,23 +73,23 @@ impl<'a> LineDrawer<'a> {
 
         if let Some(path) = tmp.finish() {
             self.priority_paths
-                .push((path, self.stroke.clone(), self.paint.clone()));
+                .push((path, self.stroke.clone(), self.brush));
         }
     }
 
-    pub fn draw(&mut self, pixmap: &mut Pixmap) {
-        self.new_path(self.prev_point, self.stroke.clone(), self.paint.clone());
-        for (path, stroke, paint) in self.paths.iter().rev() {
-            pixmap.stroke_path(path, paint, stroke, Transform::identity(), None)
+    pub fn draw(&mut self, canvas: &mut impl Canvas) {
+        self.new_path(self.prev_point, self.stroke.clone(), self.brush);
+        for (path, stroke, brush) in self.paths.iter().rev() {
+            canvas.stroke_path(path, brush, stroke)
         }
     }
-    pub fn draw_priority(self, pixmap: &mut Pixmap) {
-        for (path, stroke, paint) in self.priority_paths {
-            pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None)
+    pub fn draw_priority(self, canvas: &mut impl Canvas) {
+        for (path, stroke, brush) in self.priority_paths {
+            canvas.stroke_path(&path, &brush, &stroke)
         }
     }
-    pub fn draw_all(mut self, pixmap: &mut Pixmap) {
-        self.draw(pixmap);
-        self.draw_priority(pixmap);
+    pub fn draw_all(mut self, canvas: &mut impl Canvas) {
+        self.draw(canvas);
+        self.draw_priority(canvas);
     }
 }
@@ -0,0 +1,187 @@
+use tiny_skia::{Pixmap, PremultipliedColorU8};
+
+use crate::pattern_utils::{Coord, HexCoord};
+
+//how different (in any channel) a pixel has to be from the background to count as drawn
+const FOREGROUND_THRESHOLD: u8 = 64;
+
+//where along an edge it gets sampled, the ends are skipped since points are drawn there
+const EDGE_SAMPLES: [f32; 3] = [0.35, 0.5, 0.65];
+
+//how much a partially drawn edge counts against a lattice compared to a fully drawn one
+const PARTIAL_PENALTY: f32 = 4.0;
+
+//the three edges leading "forward" from every lattice point (east, south east and south west)
+const FORWARD_EDGES: [(i32, i32); 3] = [(1, 0), (0, 1), (-1, 1)];
+
+pub struct ForegroundMask {
+    width: u32,
+    height: u32,
+    background: PremultipliedColorU8,
+    mask: Vec<bool>,
+}
+
+impl ForegroundMask {
+    //the top left corner is always part of the border so it's used as the background color
+    pub fn new(pixmap: &Pixmap) -> Self {
+        let pixels = pixmap.pixels();
+        let background = pixels[0];
+
+        let mask = pixels
+            .iter()
+            .map(|pixel| {
+                pixel.red().abs_diff(background.red()) > FOREGROUND_THRESHOLD
+                    || pixel.green().abs_diff(background.green()) > FOREGROUND_THRESHOLD
+                    || pixel.blue().abs_diff(background.blue()) > FOREGROUND_THRESHOLD
+                    || pixel.alpha().abs_diff(background.alpha()) > FOREGROUND_THRESHOLD
+            })
+            .collect();
+
+        Self {
+            width: pixmap.width(),
+            height: pixmap.height(),
+            background,
+            mask,
+        }
+    }
+
+    pub fn get_background(&self) -> PremultipliedColorU8 {
+        self.background
+    }
+
+    fn get(&self, point: HexCoord) -> bool {
+        let x = point.0.floor();
+        let y = point.1.floor();
+        if x < 0.0 || y < 0.0 || x >= self.width as f32 || y >= self.height as f32 {
+            return false;
+        }
+        self.mask[y as usize * self.width as usize + x as usize]
+    }
+
+    //how many of the samples along the edge are drawn
+    fn edge_samples(&self, start: HexCoord, end: HexCoord) -> usize {
+        EDGE_SAMPLES
+            .iter()
+            .filter(|&&t| self.get(start + (end - start) * t))
+            .count()
+    }
+}
+
+//the grid every pattern of a HexGrid is drawn on
+#[derive(Debug, Clone, Copy)]
+pub struct Lattice {
+    pub origin: HexCoord,
+    pub scale: f32,
+    rows: i32,
+}
+
+impl Lattice {
+    pub fn get_point(&self, coord: Coord) -> HexCoord {
+        HexCoord::from(coord) * self.scale + self.origin
+    }
+
+    //finds the lattice a HexGrid with the given border size would have been drawn on
+    pub fn find(mask: &ForegroundMask, border_size: f32, min_scale: f32) -> Option<Self> {
+        let mut best: Option<(f32, Lattice)> = None;
+
+        for (scale, rows) in Self::possible_scales(mask, border_size, min_scale) {
+            for left_offset in [0.0, 0.5] {
+                let lattice = Lattice {
+                    origin: HexCoord(border_size - left_offset, border_size) * scale,
+                    scale,
+                    rows,
+                };
+                let (drawn, partial) = lattice.count_edges(mask);
+                if drawn == 0 {
+                    continue;
+                }
+                let score = (drawn as f32 - partial as f32 * PARTIAL_PENALTY) * scale;
+
+                //ties go to the larger scale, which is the one found first
+                if best.is_none_or(|(best_score, _)| score > best_score) {
+                    best = Some((score, lattice));
+                }
+            }
+        }
+
+        best.filter(|(score, _)| *score > 0.0)
+            .map(|(_, lattice)| lattice)
+    }
+
+    //a HexGrid is always a whole number of rows tall and half line lengths wide
+    //(plus the border on each side) before being rounded down to whole pixels
+    //so each combination of the two gives a small range the scale has to be in
+    fn possible_scales(mask: &ForegroundMask, border_size: f32, min_scale: f32) -> Vec<(f32, i32)> {
+        let height = mask.height as f32;
+        let width = mask.width as f32;
+
+        let mut scales = Vec::new();
+        for rows in 0.. {
+            let rows_height = border_size * 2.0 + HexCoord::get_y(rows);
+            let (low, high) = (height / rows_height, (height + 1.0) / rows_height);
+            if high < min_scale {
+                break;
+            }
+
+            let min_columns = ((width / high - border_size * 2.0) * 2.0).ceil().max(0.0) as i32;
+            let max_columns = (((width + 1.0) / low - border_size * 2.0) * 2.0).floor() as i32;
+
+            for columns in min_columns..=max_columns {
+                let columns_width = border_size * 2.0 + columns as f32 / 2.0;
+                let low = low.max(width / columns_width);
+                let high = high.min((width + 1.0) / columns_width);
+                if low < high && high >= min_scale {
+                    scales.push(((low + high) / 2.0, rows));
+                }
+            }
+        }
+        scales
+    }
+
+    fn points(&self, mask: &ForegroundMask) -> impl Iterator<Item = Coord> + '_ {
+        let width = mask.width as f32;
+        (0..=self.rows).flat_map(move |y| {
+            let shift = self.origin.0 / self.scale + y as f32 * 0.5;
+            let start = (-shift).ceil() as i32 - 1;
+            let end = (width / self.scale - shift).floor() as i32 + 1;
+            (start..=end).map(move |x| Coord(x, y))
+        })
+    }
+
+    //every lattice edge inside the image along with how many of its samples are drawn
+    fn edges<'a>(
+        &'a self,
+        mask: &'a ForegroundMask,
+    ) -> impl Iterator<Item = (Coord, Coord, usize)> + 'a {
+        self.points(mask).flat_map(move |point| {
+            FORWARD_EDGES
+                .iter()
+                .map(move |&offset| point + offset)
+                .filter(|end| end.1 <= self.rows)
+                .map(move |end| {
+                    let samples = mask.edge_samples(self.get_point(point), self.get_point(end));
+                    (point, end, samples)
+                })
+        })
+    }
+
+    fn count_edges(&self, mask: &ForegroundMask) -> (usize, usize) {
+        let mut drawn = 0;
+        let mut partial = 0;
+        for (_, _, samples) in self.edges(mask) {
+            if samples == EDGE_SAMPLES.len() {
+                drawn += 1;
+            } else if samples > 0 {
+                partial += 1;
+            }
+        }
+        (drawn, partial)
+    }
+
+    pub fn drawn_edges(&self, mask: &ForegroundMask) -> Vec<(Coord, Coord)> {
+        self.edges(mask)
+            .filter(|&(_, _, samples)| samples == EDGE_SAMPLES.len())
+            .map(|(start, end, _)| (start, end))
+            .collect()
+    }
+}
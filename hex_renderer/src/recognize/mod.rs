@@ -0,0 +1,383 @@
+mod lattice;
+use lattice::{ForegroundMask, Lattice};
+
+mod region;
+use region::Region;
+
+mod trace;
+use trace::{split_patterns, trail_to_pattern, TrailGraph};
+
+use std::collections::HashMap;
+
+use tiny_skia::{Color, Pixmap};
+
+use crate::{
+    defaults,
+    options::{GridOptions, GridPatternOptions, Intersections, Lines},
+    pattern_utils::{Angle, Coord},
+    Pattern,
+};
+
+//how many partly drawn strokes are kept while looking for the one that matches
+const BEAM_WIDTH: usize = 64;
+//candidates that are this close to the best one can't be told apart
+const AMBIGUITY_THRESHOLD: f32 = 0.00001;
+//the most ways of drawing a pattern that are listed when they all look the same
+const MAX_ALTERNATIVES: usize = 4096;
+
+#[derive(Debug)]
+pub enum RecognizeError {
+    DecodeError,
+    NoGridFound,
+    UntraceablePattern(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct RecognizedPattern {
+    pub pattern: Pattern,
+    //other patterns that draw (almost) exactly the same with the given options
+    //eg. unbent monocolor lines don't show which way the pattern was drawn, so every way
+    //of drawing it is listed (up to MAX_ALTERNATIVES), otherwise only the ones that were
+    //still being considered at the end are
+    pub alternatives: Vec<Pattern>,
+    //average difference per channel (0 to 1) between the image and the pattern redrawn
+    pub difference: f32,
+}
+
+//recovers the patterns from an image made by HexGrid::draw_grid_png with the given options
+pub fn recognize_png(
+    png: &[u8],
+    options: &GridOptions,
+) -> Result<Vec<RecognizedPattern>, RecognizeError> {
+    let pixmap = Pixmap::decode_png(png).map_err(|_| RecognizeError::DecodeError)?;
+    recognize_pixmap(&pixmap, options)
+}
+
+//tries all of the default options and keeps the one that matches the image best
+pub fn recognize_png_with_defaults(png: &[u8]) -> Result<Vec<RecognizedPattern>, RecognizeError> {
+    let pixmap = Pixmap::decode_png(png).map_err(|_| RecognizeError::DecodeError)?;
+
+    let options: [&GridOptions; 10] = [
+        &defaults::UNIFORM_MONOCOLOR,
+        &defaults::UNIFORM_BENT_MONOCOLOR,
+        &defaults::MONOCOLOR,
+        &defaults::BENT_MONOCOLOR,
+        &defaults::UNIFORM_GRADIENT,
+        &defaults::UNIFORM_POINT_GRADIENT,
+        &defaults::GRADIENT,
+        &defaults::POINT_GRADIENT,
+        &defaults::UNIFORM_SEGMENT,
+        &defaults::SEGMENT,
+    ];
+
+    let mut best: Option<(f32, Vec<RecognizedPattern>)> = None;
+    let mut last_err = RecognizeError::NoGridFound;
+
+    for options in options {
+        match recognize_pixmap(&pixmap, options) {
+            Ok(patterns) => {
+                let difference = patterns
+                    .iter()
+                    .map(|pattern| pattern.difference)
+                    .sum::<f32>()
+                    / patterns.len() as f32;
+                if best.as_ref().is_none_or(|(best, _)| difference < *best) {
+                    best = Some((difference, patterns));
+                }
+            }
+            Err(err) => last_err = err,
+        }
+    }
+
+    best.map(|(_, patterns)| patterns).ok_or(last_err)
+}
+
+pub fn recognize_pixmap(
+    pixmap: &Pixmap,
+    options: &GridOptions,
+) -> Result<Vec<RecognizedPattern>, RecognizeError> {
+    let mask = ForegroundMask::new(pixmap);
+
+    let min_scale = (2.0 / options.line_thickness).max(4.0);
+    let lattice = Lattice::find(&mask, options.get_border_size(), min_scale)
+        .ok_or(RecognizeError::NoGridFound)?;
+
+    let edges = lattice.drawn_edges(&mask);
+    if edges.is_empty() {
+        return Err(RecognizeError::NoGridFound);
+    }
+
+    let background = mask.get_background().demultiply();
+    let background = Color::from_rgba8(
+        background.red(),
+        background.green(),
+        background.blue(),
+        background.alpha(),
+    );
+
+    let mut styles = Styles::new(options);
+    let mut recognized = Vec::new();
+
+    for (index, edges) in split_patterns(edges).into_iter().enumerate() {
+        let mut points: Vec<Coord> = edges
+            .iter()
+            .flat_map(|&(start, end)| [start, end])
+            .collect();
+        points.sort();
+        points.dedup();
+
+        let region = Region::new(pixmap, &lattice, &points, options, background);
+        let pattern = find_pattern(&edges, &points, &region, options, &styles)
+            .ok_or(RecognizeError::UntraceablePattern(index))?;
+
+        styles.advance(&pattern.pattern);
+        recognized.push(pattern);
+    }
+
+    Ok(recognized)
+}
+
+//part of a stroke along with which edges it's used up
+#[derive(Clone)]
+struct PartialTrail {
+    trail: Vec<Coord>,
+    edges: Vec<usize>,
+    used: Vec<bool>,
+}
+
+//tries the ways the edges could have been drawn one edge at a time
+//when there are too many to keep track of, only the ones where the edges drawn so far
+//look the most like the image are kept
+//(unless they'd all look the same, since then there's nothing to tell them apart by)
+fn find_pattern(
+    edges: &[(Coord, Coord)],
+    points: &[Coord],
+    region: &Region,
+    options: &GridOptions,
+    styles: &Styles,
+) -> Option<RecognizedPattern> {
+    let graph = TrailGraph::new(edges);
+
+    if styles.hide_stroke_order() {
+        //every trail redraws the same for each variation so only one of each is compared
+        let mut differences: HashMap<usize, f32> = HashMap::new();
+        let trails = graph.trails(MAX_ALTERNATIVES + 1);
+        return pick_pattern(trails, |pattern, start| {
+            *differences
+                .entry(styles.get_index(pattern))
+                .or_insert_with(|| region.difference(pattern, start, options, styles.get(pattern)))
+        });
+    }
+
+    let edge_pixels: Vec<Vec<usize>> = edges
+        .iter()
+        .map(|&(start, end)| region.edge_pixels(start, end))
+        .collect();
+    let point_pixels: HashMap<Coord, Vec<usize>> = points
+        .iter()
+        .map(|&point| (point, region.point_pixels(point)))
+        .collect();
+
+    let mut partials: Vec<PartialTrail> = graph
+        .starts()?
+        .into_iter()
+        .map(|start| PartialTrail {
+            trail: vec![start],
+            edges: Vec::new(),
+            used: vec![false; edges.len()],
+        })
+        .collect();
+
+    for _ in 0..graph.edge_count() {
+        let mut next_partials = Vec::new();
+        for partial in &partials {
+            for (point, edge) in graph.moves(*partial.trail.last().unwrap(), &partial.used) {
+                let mut next = partial.clone();
+                next.trail.push(point);
+                next.edges.push(edge);
+                next.used[edge] = true;
+                next_partials.push(next);
+            }
+        }
+
+        if next_partials.len() > BEAM_WIDTH {
+            let mut scored: Vec<(u64, PartialTrail)> = next_partials
+                .into_iter()
+                .map(|partial| {
+                    let mut trail = partial.trail.clone();
+                    graph.complete(&mut trail, &mut partial.used.clone());
+                    let pattern = trail_to_pattern(&trail);
+                    let differences =
+                        region.compare(&pattern, trail[0], options, styles.get(&pattern));
+
+                    //the last point is skipped since what's drawn there depends on what comes next
+                    let pixels = partial
+                        .edges
+                        .iter()
+                        .map(|&edge| &edge_pixels[edge])
+                        .chain(
+                            partial.trail[..partial.trail.len() - 1]
+                                .iter()
+                                .map(|point| &point_pixels[point]),
+                        )
+                        .flatten();
+                    let score = pixels.map(|&pixel| differences[pixel] as u64).sum();
+                    (score, partial)
+                })
+                .collect();
+
+            scored.sort_by_key(|(score, _)| *score);
+            next_partials = scored
+                .into_iter()
+                .take(BEAM_WIDTH)
+                .map(|(_, partial)| partial)
+                .collect();
+        }
+        partials = next_partials;
+    }
+
+    let trails = partials.into_iter().map(|partial| partial.trail).collect();
+    pick_pattern(trails, |pattern, start| {
+        region.difference(pattern, start, options, styles.get(pattern))
+    })
+}
+
+//the trail that redraws closest to the image, along with the ones that can't be told apart from it
+fn pick_pattern(
+    trails: Vec<Vec<Coord>>,
+    mut difference: impl FnMut(&Pattern, Coord) -> f32,
+) -> Option<RecognizedPattern> {
+    let mut candidates: Vec<(f32, Pattern)> = trails
+        .iter()
+        .map(|trail| {
+            let pattern = trail_to_pattern(trail);
+            (difference(&pattern, trail[0]), pattern)
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut candidates = candidates.into_iter();
+    let (difference, pattern) = candidates.next()?;
+    let alternatives = candidates
+        .take_while(|(other, _)| other - difference < AMBIGUITY_THRESHOLD)
+        .map(|(_, pattern)| pattern)
+        .collect();
+
+    Some(RecognizedPattern {
+        pattern,
+        alternatives,
+        difference,
+    })
+}
+
+//keeps track of which variation a pattern is drawn with the same way GridDraw does
+struct Styles<'a> {
+    variations: Vec<(&'a Intersections, &'a Lines)>,
+    intros: &'a [Vec<Angle>],
+    retros: &'a [Vec<Angle>],
+    index: usize,
+}
+
+impl<'a> Styles<'a> {
+    fn new(options: &'a GridOptions) -> Self {
+        match &options.pattern_options {
+            GridPatternOptions::Uniform(intersections, lines) => Self {
+                variations: vec![(intersections, lines)],
+                intros: &[],
+                retros: &[],
+                index: 0,
+            },
+            GridPatternOptions::Changing {
+                variations,
+                intros,
+                retros,
+            } => Self {
+                variations: variations
+                    .iter()
+                    .map(|(inter, lines)| (inter, lines))
+                    .collect(),
+                intros,
+                retros,
+                index: 0,
+            },
+        }
+    }
+
+    fn get_index(&self, pattern: &Pattern) -> usize {
+        if self.retros.contains(&pattern.angles) {
+            (self.index + self.variations.len() - 1) % self.variations.len()
+        } else {
+            self.index
+        }
+    }
+
+    fn get(&self, pattern: &Pattern) -> (&'a Intersections, &'a Lines) {
+        self.variations[self.get_index(pattern)]
+    }
+
+    //unbent monocolor lines with nothing marking where the pattern starts or ends
+    //look the same whichever way the edges were gone over
+    fn hide_stroke_order(&self) -> bool {
+        self.variations.iter().all(|(intersections, lines)| {
+            matches!(lines, Lines::Monocolor { bent: false, .. })
+                && !matches!(intersections, Intersections::EndsAndMiddle { .. })
+        })
+    }
+
+    fn advance(&mut self, pattern: &Pattern) {
+        self.index = self.get_index(pattern);
+        if self.intros.contains(&pattern.angles) {
+            self.index = (self.index + 1) % self.variations.len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grids::{GridDraw, HexGrid};
+
+    //includes ones that unbent monocolor lines used to lose track of
+    const PATTERNS: &str = "HexPattern(SOUTH_EAST awdwaaww), HexPattern(NORTH_EAST waawaqwawqq), \
+        HexPattern(WEST ddad), HexPattern(NORTH_EAST qaq), HexPattern(EAST aadaa), HexPattern(NORTH_WEST qwaeawq)";
+
+    //the pattern has to be found with the same start direction, or be one of the alternatives
+    #[test]
+    fn round_trips_every_default() {
+        let patterns: Vec<Pattern> = PATTERNS
+            .split(", ")
+            .map(|pattern| crate::parse::parse_pattern(pattern).unwrap())
+            .collect();
+        let grid = HexGrid::new_normal(patterns.clone(), 4).unwrap();
+
+        let defaults: [(&str, &GridOptions); 10] = [
+            ("UNIFORM_MONOCOLOR", &defaults::UNIFORM_MONOCOLOR),
+            ("UNIFORM_BENT_MONOCOLOR", &defaults::UNIFORM_BENT_MONOCOLOR),
+            ("MONOCOLOR", &defaults::MONOCOLOR),
+            ("BENT_MONOCOLOR", &defaults::BENT_MONOCOLOR),
+            ("UNIFORM_GRADIENT", &defaults::UNIFORM_GRADIENT),
+            ("UNIFORM_POINT_GRADIENT", &defaults::UNIFORM_POINT_GRADIENT),
+            ("GRADIENT", &defaults::GRADIENT),
+            ("POINT_GRADIENT", &defaults::POINT_GRADIENT),
+            ("UNIFORM_SEGMENT", &defaults::UNIFORM_SEGMENT),
+            ("SEGMENT", &defaults::SEGMENT),
+        ];
+        for (name, options) in defaults {
+            let png = grid.draw_grid_png(30.0, options).unwrap();
+            let recognized = recognize_png(&png, options).unwrap();
+            assert_eq!(recognized.len(), patterns.len(), "{name}");
+
+            let same = |a: &Pattern, b: &Pattern| {
+                a.get_start_direction() == b.get_start_direction() && a.canonical() == b.canonical()
+            };
+            for (pattern, found) in patterns.iter().zip(&recognized) {
+                assert!(
+                    same(pattern, &found.pattern)
+                        || found.alternatives.iter().any(|other| same(pattern, other)),
+                    "{name}: {pattern} was recognized as {}",
+                    found.pattern
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use tiny_skia::{Color, Pixmap};
+
+use crate::{
+    options::{GridOptions, Intersections, Lines},
+    pattern_utils::{Coord, HexCoord},
+    Pattern,
+};
+
+use super::lattice::Lattice;
+
+//how far from a point or edge (in line lengths) pixels still count as part of it
+const ELEMENT_RADIUS: f32 = 0.3;
+//the part of an edge that isn't covered by the points at either end
+const EDGE_RANGE: (f32, f32) = (0.2, 0.8);
+
+//the part of the image a single pattern (and its points) is drawn in
+pub struct Region<'a> {
+    pixmap: &'a Pixmap,
+    lattice: &'a Lattice,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    background: Color,
+}
+
+impl<'a> Region<'a> {
+    pub fn new(
+        pixmap: &'a Pixmap,
+        lattice: &'a Lattice,
+        points: &[Coord],
+        options: &GridOptions,
+        background: Color,
+    ) -> Self {
+        let border = options.get_border_size() * lattice.scale;
+
+        let mut top_left = HexCoord(f32::MAX, f32::MAX);
+        let mut bottom_right = HexCoord(f32::MIN, f32::MIN);
+        for &point in points {
+            let point = lattice.get_point(point);
+            top_left = top_left.min_components(point);
+            bottom_right = bottom_right.max_components(point);
+        }
+
+        let x = (top_left.0 - border).floor().max(0.0) as u32;
+        let y = (top_left.1 - border).floor().max(0.0) as u32;
+        let right = ((bottom_right.0 + border).ceil() as u32).min(pixmap.width());
+        let bottom = ((bottom_right.1 + border).ceil() as u32).min(pixmap.height());
+
+        Self {
+            pixmap,
+            lattice,
+            x,
+            y,
+            width: right.saturating_sub(x).max(1),
+            height: bottom.saturating_sub(y).max(1),
+            background,
+        }
+    }
+
+    //the pixels (in the region) close to the middle of an edge
+    pub fn edge_pixels(&self, start: Coord, end: Coord) -> Vec<usize> {
+        let start = self.lattice.get_point(start);
+        let end = self.lattice.get_point(end);
+        let length = (end - start).magnitude();
+        let unit = (end - start) / length;
+        let radius = ELEMENT_RADIUS * self.lattice.scale;
+
+        self.pixels_near(
+            start.min_components(end),
+            start.max_components(end),
+            |pixel| {
+                let offset = pixel - start;
+                let along = (offset.0 * unit.0 + offset.1 * unit.1) / length;
+                let across = (offset.0 * unit.1 - offset.1 * unit.0).abs();
+                along >= EDGE_RANGE.0 && along <= EDGE_RANGE.1 && across <= radius
+            },
+        )
+    }
+
+    //the pixels (in the region) close to a point
+    pub fn point_pixels(&self, point: Coord) -> Vec<usize> {
+        let point = self.lattice.get_point(point);
+        let radius = ELEMENT_RADIUS * self.lattice.scale;
+        self.pixels_near(point, point, |pixel| (pixel - point).magnitude() <= radius)
+    }
+
+    fn pixels_near(
+        &self,
+        top_left: HexCoord,
+        bottom_right: HexCoord,
+        filter: impl Fn(HexCoord) -> bool,
+    ) -> Vec<usize> {
+        let radius = ELEMENT_RADIUS * self.lattice.scale;
+        let min_x = (top_left.0 - radius - self.x as f32).floor().max(0.0) as u32;
+        let min_y = (top_left.1 - radius - self.y as f32).floor().max(0.0) as u32;
+        let max_x = ((bottom_right.0 + radius - self.x as f32).ceil() as u32).min(self.width);
+        let max_y = ((bottom_right.1 + radius - self.y as f32).ceil() as u32).min(self.height);
+
+        let mut pixels = Vec::new();
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let pixel = HexCoord((x + self.x) as f32 + 0.5, (y + self.y) as f32 + 0.5);
+                if filter(pixel) {
+                    pixels.push((y * self.width + x) as usize);
+                }
+            }
+        }
+        pixels
+    }
+
+    //redraws the pattern starting at the given point and returns how different each pixel is
+    pub fn compare(
+        &self,
+        pattern: &Pattern,
+        start: Coord,
+        options: &GridOptions,
+        (intersections, lines): (&Intersections, &Lines),
+    ) -> Vec<u32> {
+        let mut canvas = Pixmap::new(self.width, self.height).unwrap();
+        canvas.fill(self.background);
+
+        pattern.draw_pattern(
+            &mut canvas,
+            self.lattice.get_point(start) - HexCoord(self.x as f32, self.y as f32),
+            self.lattice.scale,
+            options.line_thickness,
+            lines,
+            intersections,
+            &options.center_dot,
+        );
+
+        let mut differences = Vec::with_capacity((self.width * self.height) as usize);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let drawn = canvas.pixel(x, y).unwrap();
+                let actual = self.pixmap.pixel(x + self.x, y + self.y).unwrap();
+                differences.push(
+                    drawn.red().abs_diff(actual.red()) as u32
+                        + drawn.green().abs_diff(actual.green()) as u32
+                        + drawn.blue().abs_diff(actual.blue()) as u32
+                        + drawn.alpha().abs_diff(actual.alpha()) as u32,
+                );
+            }
+        }
+        differences
+    }
+
+    //scales the total difference to the average difference per channel (0 to 1)
+    pub fn normalize(&self, total: u64) -> f32 {
+        total as f32 / (self.width * self.height * 4 * 255) as f32
+    }
+
+    //the average difference per channel between the image and the pattern redrawn
+    pub fn difference(
+        &self,
+        pattern: &Pattern,
+        start: Coord,
+        options: &GridOptions,
+        style: (&Intersections, &Lines),
+    ) -> f32 {
+        let differences = self.compare(pattern, start, options, style);
+        self.normalize(differences.iter().map(|&diff| diff as u64).sum())
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    pattern_utils::{Coord, Direction},
+    Pattern,
+};
+
+//splits the drawn edges into patterns in the order a HexGrid lays them out
+//(row by row, then left to right using the top row of each pattern)
+pub fn split_patterns(edges: Vec<(Coord, Coord)>) -> Vec<Vec<(Coord, Coord)>> {
+    let mut parents: HashMap<Coord, Coord> = HashMap::new();
+
+    fn find(parents: &mut HashMap<Coord, Coord>, point: Coord) -> Coord {
+        let parent = *parents.entry(point).or_insert(point);
+        if parent == point {
+            point
+        } else {
+            let root = find(parents, parent);
+            parents.insert(point, root);
+            root
+        }
+    }
+
+    for &(start, end) in &edges {
+        let start = find(&mut parents, start);
+        let end = find(&mut parents, end);
+        parents.insert(start, end);
+    }
+
+    let mut patterns: HashMap<Coord, Vec<(Coord, Coord)>> = HashMap::new();
+    for edge in edges {
+        let root = find(&mut parents, edge.0);
+        patterns.entry(root).or_default().push(edge);
+    }
+
+    let mut patterns: Vec<Vec<(Coord, Coord)>> = patterns.into_values().collect();
+    patterns.sort_by_key(|edges| {
+        edges
+            .iter()
+            .flat_map(|&(start, end)| [start, end])
+            .map(|point| (point.1, point.0))
+            .min()
+            .unwrap()
+    });
+    patterns
+}
+
+//the edges of a single pattern, for finding the ways it could have been drawn in one stroke
+pub struct TrailGraph {
+    neighbors: BTreeMap<Coord, Vec<(Coord, usize)>>,
+    edge_count: usize,
+}
+
+impl TrailGraph {
+    pub fn new(edges: &[(Coord, Coord)]) -> Self {
+        let mut neighbors: BTreeMap<Coord, Vec<(Coord, usize)>> = BTreeMap::new();
+        for (i, &(start, end)) in edges.iter().enumerate() {
+            neighbors.entry(start).or_default().push((end, i));
+            neighbors.entry(end).or_default().push((start, i));
+        }
+        Self {
+            neighbors,
+            edge_count: edges.len(),
+        }
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    //a stroke has to start on one of the two points with an odd number of edges
+    //(or anywhere if there aren't any), None if it can't be drawn in one stroke at all
+    pub fn starts(&self) -> Option<Vec<Coord>> {
+        let odd_points: Vec<Coord> = self
+            .neighbors
+            .iter()
+            .filter(|(_, edges)| edges.len() % 2 == 1)
+            .map(|(point, _)| *point)
+            .collect();
+
+        match odd_points.len() {
+            0 => Some(self.neighbors.keys().copied().collect()),
+            2 => Some(odd_points),
+            _ => None,
+        }
+    }
+
+    //the edges that can be taken from a point without leaving some edges unreachable
+    pub fn moves(&self, point: Coord, used: &[bool]) -> Vec<(Coord, usize)> {
+        let unused: Vec<(Coord, usize)> = self.neighbors[&point]
+            .iter()
+            .copied()
+            .filter(|&(_, edge)| !used[edge])
+            .collect();
+
+        if unused.len() <= 1 {
+            return unused;
+        }
+
+        let remaining = used.iter().filter(|used| !**used).count() - 1;
+        let mut used = used.to_vec();
+        unused
+            .into_iter()
+            .filter(|&(next, edge)| {
+                used[edge] = true;
+                let reachable = self.reachable_edges(next, &used);
+                used[edge] = false;
+                reachable == remaining
+            })
+            .collect()
+    }
+
+    fn reachable_edges(&self, start: Coord, used: &[bool]) -> usize {
+        let mut seen = vec![false; used.len()];
+        let mut stack = vec![start];
+        let mut count = 0;
+        while let Some(point) = stack.pop() {
+            for &(next, edge) in &self.neighbors[&point] {
+                if !used[edge] && !seen[edge] {
+                    seen[edge] = true;
+                    count += 1;
+                    stack.push(next);
+                }
+            }
+        }
+        count
+    }
+
+    //every way of drawing all of the edges in one stroke, stopping once there's limit of them
+    pub fn trails(&self, limit: usize) -> Vec<Vec<Coord>> {
+        let mut trails = Vec::new();
+        for start in self.starts().unwrap_or_default() {
+            let mut used = vec![false; self.edge_count];
+            self.extend_trails(&mut vec![start], &mut used, &mut trails, limit);
+        }
+        trails
+    }
+
+    fn extend_trails(
+        &self,
+        trail: &mut Vec<Coord>,
+        used: &mut [bool],
+        trails: &mut Vec<Vec<Coord>>,
+        limit: usize,
+    ) {
+        if trails.len() >= limit {
+            return;
+        }
+        let moves = self.moves(*trail.last().unwrap(), used);
+        if moves.is_empty() {
+            trails.push(trail.clone());
+            return;
+        }
+        for (next, edge) in moves {
+            used[edge] = true;
+            trail.push(next);
+            self.extend_trails(trail, used, trails, limit);
+            trail.pop();
+            used[edge] = false;
+        }
+    }
+
+    //finishes the stroke by always taking the first edge that's allowed
+    pub fn complete(&self, trail: &mut Vec<Coord>, used: &mut [bool]) {
+        while let Some(&(next, edge)) = self.moves(*trail.last().unwrap(), used).first() {
+            used[edge] = true;
+            trail.push(next);
+        }
+    }
+}
+
+pub fn trail_to_pattern(trail: &[Coord]) -> Pattern {
+    let directions: Vec<Direction> = trail
+        .windows(2)
+        .map(|points| {
+            Direction::try_from((points[1].0 - points[0].0, points[1].1 - points[0].1)).unwrap()
+        })
+        .collect();
+
+    let angles = directions
+        .windows(2)
+        .map(|directions| directions[1] - directions[0])
+        .collect();
+
+    Pattern::new(directions[0], angles)
+}
@@ -0,0 +1,275 @@
+use std::io::{self, IsTerminal, Write};
+
+use clap::{error::ErrorKind, Args, CommandFactory};
+use hex_renderer::{
+    defaults::{
+        components::{gradient::*, monocolor::*, segment::*, CENTER_DOT},
+        constants,
+    },
+    options::{
+        palettes, parse_hex_color, Background, CollisionOption, GridOptions, GridPatternOptions,
+        Intersections, Lines, OverloadOptions, Point,
+    },
+    Color,
+};
+
+use crate::{greater_than_zero, render::PatternOption, Cli};
+
+//flags that change the colors and sizes of the option (or theme) the grid is drawn with
+#[derive(Args)]
+pub struct ColorArgs {
+    /// A palette name (see --list-palettes) or #hex,#hex,...
+    /// Given more than once, patterns alternate between the palettes
+    #[arg(long, value_parser = palette_parser, conflicts_with = "theme")]
+    palette: Vec<Palette>,
+
+    #[arg(long, value_parser = greater_than_zero)]
+    line_thickness: Option<f32>,
+
+    #[arg(long, value_parser = color_parser)]
+    point_color: Option<Color>,
+
+    #[arg(long, value_parser = color_parser)]
+    collision_color: Option<Color>,
+
+    #[arg(long, value_parser = color_parser)]
+    label_color: Option<Color>,
+
+    /// A color or transparent
+    #[arg(long, value_parser = background_parser)]
+    background: Option<Background>,
+}
+
+#[derive(Clone)]
+struct Palette(Vec<Color>);
+
+impl ColorArgs {
+    //the option the same way defaults builds it, but with the palettes given instead of the built in ones
+    pub fn build(&self, option: PatternOption) -> GridOptions {
+        if self.palette.is_empty() {
+            return <&GridOptions>::from(option).clone();
+        }
+        let palettes: Vec<Vec<Color>> = self
+            .palette
+            .iter()
+            .map(|palette| palette.0.clone())
+            .collect();
+        let first = palettes[0].clone();
+
+        let gradient = |colors| Lines::Gradient {
+            colors,
+            segments_per_color: constants::SEGS_PER_COLOR,
+            bent: true,
+        };
+        let segment = |colors| Lines::SegmentColors {
+            colors,
+            triangles: *TRIANGLE,
+            collisions: *COLLISIONS,
+        };
+
+        let (pattern_options, center_dot) = match option {
+            PatternOption::UniformGradient => (
+                GridPatternOptions::Uniform(*GRADIENT_INTERSECTION, gradient(first)),
+                Point::None,
+            ),
+            PatternOption::Gradient => (
+                GridPatternOptions::gen_changing_gradient(*GRADIENT_INTERSECTION, palettes, true),
+                Point::None,
+            ),
+            PatternOption::UniformPointGradient => (
+                GridPatternOptions::Uniform(*GRADIENT_POINT_INTERSECTION, gradient(first)),
+                *GRADIENT_POINT,
+            ),
+            PatternOption::PointGradient => (
+                GridPatternOptions::gen_changing_gradient(
+                    *GRADIENT_POINT_INTERSECTION,
+                    palettes,
+                    true,
+                ),
+                *GRADIENT_POINT,
+            ),
+            PatternOption::UniformSegment => (
+                GridPatternOptions::Uniform(*SEGMENT_INTERSECTION, segment(first)),
+                *CENTER_DOT,
+            ),
+            PatternOption::Segment => (
+                GridPatternOptions::gen_changing_segment(
+                    *SEGMENT_INTERSECTION,
+                    palettes,
+                    *TRIANGLE,
+                    *COLLISIONS,
+                ),
+                *CENTER_DOT,
+            ),
+            PatternOption::UniformMonocolor => (
+                GridPatternOptions::Uniform(
+                    *MONOCOLOR_INTERSECTION,
+                    Lines::Monocolor {
+                        color: first[0],
+                        bent: false,
+                    },
+                ),
+                *CENTER_DOT,
+            ),
+            //every color of every palette gets a turn
+            PatternOption::Monocolor => (
+                GridPatternOptions::gen_changing_monocolor(
+                    *MONOCOLOR_INTERSECTION,
+                    palettes.concat(),
+                    false,
+                ),
+                *CENTER_DOT,
+            ),
+        };
+        GridOptions::generate(pattern_options, center_dot)
+    }
+
+    //the rest of the flags go on top of whatever options were picked
+    pub fn apply(&self, mut options: GridOptions) -> GridOptions {
+        if let Some(line_thickness) = self.line_thickness {
+            options.line_thickness = line_thickness;
+        }
+        if let Some(color) = self.point_color {
+            options.center_dot = options.center_dot.map_colors(&|_| color);
+        }
+        if let Some(label_color) = self.label_color {
+            options.label_color = label_color;
+        }
+        if let Some(background) = self.background {
+            options.background = background;
+        }
+
+        let point_color = self.point_color;
+        let collision_color = self.collision_color;
+        options.pattern_options =
+            map_variations(options.pattern_options, |intersections, lines| {
+                let intersections = match point_color {
+                    Some(color) => intersections.map_colors(&|_| color),
+                    None => intersections,
+                };
+                let lines = match (lines, collision_color) {
+                    (
+                        Lines::SegmentColors {
+                            colors,
+                            triangles,
+                            collisions,
+                        },
+                        Some(color),
+                    ) => Lines::SegmentColors {
+                        colors,
+                        triangles,
+                        collisions: with_collision_color(collisions, color),
+                    },
+                    (lines, _) => lines,
+                };
+                (intersections, lines)
+            });
+        options
+    }
+}
+
+fn map_variations(
+    pattern_options: GridPatternOptions,
+    f: impl Fn(Intersections, Lines) -> (Intersections, Lines),
+) -> GridPatternOptions {
+    match pattern_options {
+        GridPatternOptions::Uniform(intersections, lines) => {
+            let (intersections, lines) = f(intersections, lines);
+            GridPatternOptions::Uniform(intersections, lines)
+        }
+        GridPatternOptions::Changing {
+            variations,
+            intros,
+            retros,
+        } => GridPatternOptions::Changing {
+            variations: variations
+                .into_iter()
+                .map(|(intersections, lines)| f(intersections, lines))
+                .collect(),
+            intros,
+            retros,
+        },
+    }
+}
+
+//only the dashes change, the labels counting the overlapping lines keep their color
+fn with_collision_color(collisions: CollisionOption, color: Color) -> CollisionOption {
+    match collisions {
+        CollisionOption::Dashes(_) => CollisionOption::Dashes(color),
+        CollisionOption::OverloadedParallel { max_line, overload } => {
+            CollisionOption::OverloadedParallel {
+                max_line,
+                overload: match overload {
+                    OverloadOptions::Dashes(_) => OverloadOptions::Dashes(color),
+                    OverloadOptions::LabeledDashes { label, .. } => {
+                        OverloadOptions::LabeledDashes { color, label }
+                    }
+                    OverloadOptions::MatchedDashes => OverloadOptions::MatchedDashes,
+                },
+            }
+        }
+        collisions => collisions,
+    }
+}
+
+//each palette's name and colors, with a swatch of each color when printing to a terminal
+pub fn list_palettes() {
+    let stdout = io::stdout();
+    let swatches = stdout.is_terminal();
+    match write_palettes(&mut stdout.lock(), swatches) {
+        //the output was piped into something that stopped reading (like head)
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => (),
+        Err(err) => Cli::command()
+            .error(ErrorKind::Io, format!("Failed to list the palettes! {err}"))
+            .exit(),
+        Ok(()) => (),
+    }
+}
+
+fn write_palettes(out: &mut impl Write, swatches: bool) -> io::Result<()> {
+    for name in palettes::NAMES {
+        let colors = palettes::get_palette(name).unwrap_or_default();
+        let colors: Vec<String> = colors
+            .iter()
+            .map(|color| {
+                let color = color.to_color_u8();
+                let (r, g, b) = (color.red(), color.green(), color.blue());
+                if swatches {
+                    format!("\x1b[38;2;{r};{g};{b}m██\x1b[0m #{r:02x}{g:02x}{b:02x}")
+                } else {
+                    format!("#{r:02x}{g:02x}{b:02x}")
+                }
+            })
+            .collect();
+        writeln!(out, "{name:<8} {}", colors.join(" "))?;
+    }
+    out.flush()
+}
+
+fn color_parser(s: &str) -> Result<Color, String> {
+    parse_hex_color(s).map_err(|err| format!("{err:?}"))
+}
+
+fn palette_parser(s: &str) -> Result<Palette, String> {
+    if let Some(colors) = palettes::get_palette(s) {
+        return Ok(Palette(colors.to_vec()));
+    }
+    if !s.trim_start().starts_with('#') {
+        return Err(format!(
+            "`{s}` isn't a palette! Try one of {}",
+            palettes::NAMES.join(", ")
+        ));
+    }
+    s.split(',')
+        .map(color_parser)
+        .collect::<Result<_, _>>()
+        .map(Palette)
+}
+
+fn background_parser(s: &str) -> Result<Background, String> {
+    if s.eq_ignore_ascii_case("transparent") {
+        Ok(Background::Transparent)
+    } else {
+        color_parser(s).map(Background::Solid)
+    }
+}
@@ -0,0 +1,62 @@
+use clap::{Args, ValueEnum};
+use hex_renderer::{
+    parse::{Iota, ParsedIota},
+    Pattern,
+};
+
+use crate::{json, PatternArgs};
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    #[command(flatten)]
+    patterns: PatternArgs,
+
+    #[arg(long, value_enum, default_value = "hex")]
+    to: Notation,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum Notation {
+    //HexPattern(EAST qaq)
+    Hex,
+    //<EAST,qaq>
+    Angles,
+    //EAST qaq
+    Bare,
+    Json,
+}
+
+pub fn run(args: ConvertArgs) {
+    println!("{}", write_iotas(&args.patterns.get().iotas, args.to));
+}
+
+//iotas that aren't patterns are written the same way in every notation except json
+pub fn write_iotas(iotas: &[ParsedIota], notation: Notation) -> String {
+    match notation {
+        Notation::Json => json::to_json(iotas),
+        _ => iotas
+            .iter()
+            .map(|iota| write_iota(&iota.iota, notation))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+fn write_iota(iota: &Iota, notation: Notation) -> String {
+    match iota {
+        Iota::Pattern(pattern) => write_pattern(pattern, notation),
+        Iota::List(list) => format!("[{}]", write_iotas(list, notation)),
+        other => other.to_string(),
+    }
+}
+
+fn write_pattern(pattern: &Pattern, notation: Notation) -> String {
+    let direction = pattern.get_start_direction();
+    let angles = pattern.get_angle_sig();
+    match notation {
+        Notation::Angles => format!("<{direction},{angles}>"),
+        Notation::Bare if angles.is_empty() => direction.to_string(),
+        Notation::Bare => format!("{direction} {angles}"),
+        _ => pattern.to_string(),
+    }
+}
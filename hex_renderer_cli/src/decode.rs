@@ -0,0 +1,72 @@
+use clap::{error::ErrorKind, Args, CommandFactory};
+use hex_decoder::{assemble, disassemble};
+use hex_renderer::parse::{Iota, ParsedIota};
+
+use crate::{
+    convert::{self, Notation},
+    read_input, Cli, PatternArgs, RegistryArgs,
+};
+
+#[derive(Args)]
+pub struct DecodeArgs {
+    #[command(flatten)]
+    patterns: PatternArgs,
+
+    #[command(flatten)]
+    registry: RegistryArgs,
+}
+
+#[derive(Args)]
+pub struct EncodeArgs {
+    /// Lines in the format decode writes, or - to read them from stdin
+    #[arg(required_unless_present = "input", conflicts_with = "input")]
+    lines: Vec<String>,
+
+    /// A file to read the lines from, or - for stdin
+    #[arg(long)]
+    input: Option<String>,
+
+    #[arg(long, value_enum, default_value = "hex")]
+    to: Notation,
+
+    #[command(flatten)]
+    registry: RegistryArgs,
+}
+
+pub fn run_decode(args: DecodeArgs) {
+    let registry = args.registry.load();
+    let iotas = args.patterns.get().iotas;
+
+    //a single list is the usual way a spell gets copied out of the game
+    let iotas = match &iotas[..] {
+        [ParsedIota {
+            iota: Iota::List(list),
+            ..
+        }] => list,
+        _ => &iotas,
+    };
+    print!("{}", disassemble(iotas, &registry));
+}
+
+pub fn run_encode(args: EncodeArgs) {
+    let registry = args.registry.load();
+    let source = match (&args.input, &args.lines[..]) {
+        (Some(path), _) => read_input(path),
+        (None, [line]) if line == "-" => read_input(line),
+        (None, lines) => lines.join("\n"),
+    };
+    let iotas = assemble(&source, &registry).unwrap_or_else(|err| {
+        Cli::command()
+            .error(
+                ErrorKind::ValueValidation,
+                format!("Failed to encode! {err:?}"),
+            )
+            .exit()
+    });
+
+    let iotas: Vec<ParsedIota> = iotas
+        .into_iter()
+        .map(|iota| ParsedIota { iota, span: 0..0 })
+        .collect();
+    println!("{}", convert::write_iotas(&iotas, args.to));
+}
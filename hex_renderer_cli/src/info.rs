@@ -0,0 +1,48 @@
+use clap::Args;
+use hex_decoder::name_pattern;
+
+use crate::{PatternArgs, RegistryArgs};
+
+#[derive(Args)]
+pub struct InfoArgs {
+    #[command(flatten)]
+    patterns: PatternArgs,
+
+    #[command(flatten)]
+    registry: RegistryArgs,
+}
+
+//sizes are in line lengths, the same units the hex grid uses
+pub fn run(args: InfoArgs) {
+    let registry = args.registry.load();
+    let patterns = args.patterns.get().valid;
+
+    let mut total_segments = 0;
+    let mut colliding = 0;
+
+    for (i, pattern) in patterns.iter().enumerate() {
+        let (top_left, bottom_right) = pattern.get_bounds();
+        let size = bottom_right - top_left;
+        let segments = pattern.get_angles().len() + 1;
+        let collisions = pattern.get_collision_count();
+
+        total_segments += segments;
+        if collisions > 0 {
+            colliding += 1;
+        }
+
+        match name_pattern(pattern, &registry) {
+            Some(name) => println!("{i}: {pattern} ({name})"),
+            None => println!("{i}: {pattern}"),
+        }
+        println!(
+            "    segments: {segments}, size: {:.2} x {:.2}, collisions: {collisions}",
+            size.0, size.1
+        );
+    }
+
+    println!(
+        "{} patterns, {total_segments} segments, {colliding} self colliding",
+        patterns.len()
+    );
+}
@@ -0,0 +1,91 @@
+use std::ops::Range;
+
+use hex_renderer::parse::{self, Iota, ParsedIota};
+use serde_json::{json, Map, Value};
+
+//patterns are {"startDir": "EAST", "angles": "qaq"}, vectors are {"x": 1, "y": 2, "z": 3}
+//and lists are arrays, anything that can't be understood is {"unknown": "..."}
+//the top level array holds the iotas themselves rather than being a list
+
+pub fn to_json(iotas: &[ParsedIota]) -> String {
+    let values = iotas.iter().map(|iota| iota_to_json(&iota.iota)).collect();
+    serde_json::to_string_pretty(&Value::Array(values)).unwrap()
+}
+
+fn iota_to_json(iota: &Iota) -> Value {
+    match iota {
+        Iota::Pattern(pattern) => json!({
+            "startDir": pattern.get_start_direction().to_string(),
+            "angles": pattern.get_angle_sig(),
+        }),
+        Iota::Number(num) => json!(num),
+        Iota::Vector(x, y, z) => json!({"x": x, "y": y, "z": z}),
+        Iota::String(string) => json!(string),
+        Iota::List(list) => {
+            Value::Array(list.iter().map(|iota| iota_to_json(&iota.iota)).collect())
+        }
+        Iota::Unknown(text) => json!({ "unknown": text }),
+    }
+}
+
+//None if the input isn't json, so it can be read as any of the other notations instead
+pub fn from_json(input: &str) -> Option<Vec<ParsedIota>> {
+    let trimmed = input.trim_start();
+    if !trimmed.starts_with('[') && !trimmed.starts_with('{') {
+        return None;
+    }
+    let values = match serde_json::from_str(input).ok()? {
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+
+    //there's no way to know where each value came from, so they all cover the whole input
+    let span = 0..input.len();
+    Some(
+        values
+            .iter()
+            .map(|value| ParsedIota {
+                iota: json_to_iota(value, &span),
+                span: span.clone(),
+            })
+            .collect(),
+    )
+}
+
+fn json_to_iota(value: &Value, span: &Range<usize>) -> Iota {
+    match value {
+        Value::Number(num) => num
+            .as_f64()
+            .map_or(Iota::Unknown(num.to_string()), Iota::Number),
+        Value::String(string) => Iota::String(string.clone()),
+        Value::Array(values) => Iota::List(
+            values
+                .iter()
+                .map(|value| ParsedIota {
+                    iota: json_to_iota(value, span),
+                    span: span.clone(),
+                })
+                .collect(),
+        ),
+        Value::Object(object) => {
+            object_to_iota(object).unwrap_or_else(|| Iota::Unknown(value.to_string()))
+        }
+        Value::Bool(_) | Value::Null => Iota::Unknown(value.to_string()),
+    }
+}
+
+fn object_to_iota(object: &Map<String, Value>) -> Option<Iota> {
+    if let Some(direction) = object.get("startDir") {
+        let angles = object.get("angles").map_or(Some(""), Value::as_str)?;
+        let pattern = parse::parse_pattern(&format!("<{},{angles}>", direction.as_str()?)).ok()?;
+        Some(Iota::Pattern(pattern))
+    } else if let (Some(x), Some(y), Some(z)) = (object.get("x"), object.get("y"), object.get("z"))
+    {
+        Some(Iota::Vector(x.as_f64()?, y.as_f64()?, z.as_f64()?))
+    } else {
+        object
+            .get("unknown")?
+            .as_str()
+            .map(|text| Iota::Unknown(text.to_string()))
+    }
+}
@@ -1,144 +1,156 @@
-use clap::{error::ErrorKind, Args, CommandFactory, Parser, ValueEnum};
-use hex_renderer::{
-    defaults,
-    grids::{GridDraw, HexGrid, SquareGrid},
-    options::GridOptions,
-};
+use std::{fs, io};
+
+use clap::{error::ErrorKind, Args, CommandFactory, Parser, Subcommand};
+use hex_decoder::PatternRegistry;
 use parse_patterns::PatternParseResults;
 
+mod colors;
+mod convert;
+mod decode;
+mod info;
+mod json;
 mod parse_patterns;
+mod render;
+mod repl;
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
 struct Cli {
-    #[arg(value_parser = pattern_parser)]
-    patterns: PatternParseResults,
-
-    #[command(flatten)]
-    grid_type: GridArgs,
-
-    #[command(flatten)]
-    square_args: SquareArgs,
-
-    #[command(flatten)]
-    size: SizeArgs,
-
-    #[arg(long, value_parser = not_zero)]
-    max_width: Option<usize>,
-
-    file_name: Option<String>,
-
-    option: Option<PatternOption>,
+    #[command(subcommand)]
+    command: Option<Command>,
 
+    /// Shows the built in palettes (for render's --palette) and their colors
     #[arg(long)]
-    ignore_invalid: bool,
+    list_palettes: bool,
 }
 
-#[derive(Args)]
-#[group(required = true, multiple = false)]
-struct GridArgs {
-    #[arg(long)]
-    hex: bool,
-    #[arg(long, group = "square_group")]
-    square: bool,
-}
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
-enum PatternOption {
-    UniformGradient,
-    Gradient,
-    UniformPointGradient,
-    PointGradient,
-
-    UniformSegment,
-    Segment,
-
-    UniformMonocolor,
-    Monocolor,
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand)]
+enum Command {
+    /// Draws the patterns to an image
+    Render(render::RenderArgs),
+    /// Names the patterns, one per line
+    Decode(decode::DecodeArgs),
+    /// Turns pattern names and numbers (one per line) into patterns
+    Encode(decode::EncodeArgs),
+    /// Writes the patterns in another notation
+    Convert(convert::ConvertArgs),
+    /// Lists the size, segments and collisions of each pattern
+    Info(info::InfoArgs),
+    /// Keeps a list of patterns to edit, decode and render one command at a time
+    Repl(repl::ReplArgs),
 }
 
-#[derive(Args)]
-struct SizeArgs {
-    #[arg(long, group = "size", value_parser = not_zero)]
-    x_width: Option<usize>,
-    #[arg(long, group = "size", value_parser = not_zero)]
-    y_width: Option<usize>,
-    #[arg(long, conflicts_with = "size", value_parser = greater_than_zero)]
-    scale: Option<f32>,
+fn main() {
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Render(args)) => render::run(args),
+        Some(Command::Decode(args)) => decode::run_decode(args),
+        Some(Command::Encode(args)) => decode::run_encode(args),
+        Some(Command::Convert(args)) => convert::run(args),
+        Some(Command::Info(args)) => info::run(args),
+        Some(Command::Repl(args)) => repl::run(args),
+        None if cli.list_palettes => colors::list_palettes(),
+        None => Cli::command().print_help().unwrap(),
+    }
 }
 
+//the patterns every subcommand reads, in any notation parse_patterns understands
 #[derive(Args)]
-#[group(requires = "square_group")]
-struct SquareArgs {
-    #[arg(long, value_parser = positive_float)]
-    x_padding: Option<f32>,
-    #[arg(long, value_parser = positive_float)]
-    y_padding: Option<f32>,
-    #[arg(long, value_parser = positive_float)]
-    max_scale: Option<f32>,
-}
+struct PatternArgs {
+    /// The patterns, or - to read them from stdin
+    #[arg(required_unless_present = "input", conflicts_with = "input")]
+    patterns: Option<String>,
 
-fn main() {
-    let cli = Cli::parse();
+    /// A file to read the patterns from, or - for stdin
+    #[arg(long)]
+    input: Option<String>,
 
-    let patterns = cli.patterns.valid;
-    if !cli.patterns.invalid.is_empty() && !cli.ignore_invalid {
-        let mut cmd = Cli::command();
-        cmd.error(
-            ErrorKind::ValueValidation,
-            format!(
-                "Invalid patterns! {:?} add --ignore-invalid to ignore them.",
-                cli.patterns.invalid
-            ),
-        )
-        .exit();
+    #[arg(long)]
+    ignore_invalid: bool,
+}
+
+impl PatternArgs {
+    fn read(&self) -> String {
+        let path = match (&self.input, &self.patterns) {
+            (Some(path), _) => path,
+            (None, Some(patterns)) if patterns == "-" => patterns,
+            (None, Some(patterns)) => return patterns.clone(),
+            //clap makes sure one of them is there
+            (None, None) => unreachable!(),
+        };
+        read_input(path)
     }
 
-    let options = cli.option.unwrap_or(PatternOption::Segment).into();
+    //exits if any of the patterns couldn't be parsed (unless they're ignored)
+    fn get(&self) -> PatternParseResults {
+        let results = parse_patterns::parse_str(&self.read());
+        self.check(&results);
+        results
+    }
 
-    let file_name = cli.file_name.unwrap_or("HexPatterns.png".to_string());
+    //every section of the input on its own (see parse_patterns::split_sections)
+    fn get_sections(&self) -> Vec<(Option<String>, PatternParseResults)> {
+        let sections: Vec<_> = parse_patterns::split_sections(&self.read())
+            .into_iter()
+            .map(|section| (section.label, parse_patterns::parse_str(&section.text)))
+            .collect();
+        for (_, results) in &sections {
+            self.check(results);
+        }
+        sections
+    }
 
-    let grid: Box<dyn GridDraw> = if cli.grid_type.square {
-        let args = cli.square_args;
-        let square_grid = SquareGrid::new_normal(
-            patterns,
-            cli.max_width.unwrap_or(20),
-            args.max_scale.unwrap_or(0.4),
-            args.x_padding.unwrap_or(0.2),
-            args.y_padding.unwrap_or(0.1),
-        )
-        .unwrap();
+    fn check(&self, results: &PatternParseResults) {
+        if !results.invalid.is_empty() && !self.ignore_invalid {
+            Cli::command()
+                .error(
+                    ErrorKind::ValueValidation,
+                    format!(
+                        "Invalid patterns! {:?} add --ignore-invalid to ignore them.",
+                        results.invalid
+                    ),
+                )
+                .exit();
+        }
+    }
+}
 
-        Box::new(square_grid)
+//the whole file at path, or stdin when it's -
+fn read_input(path: &str) -> String {
+    let text = if path == "-" {
+        io::read_to_string(io::stdin())
     } else {
-        Box::new(HexGrid::new_normal(patterns, cli.max_width.unwrap_or(50)).unwrap())
+        fs::read_to_string(path)
     };
+    text.unwrap_or_else(|err| {
+        Cli::command()
+            .error(ErrorKind::Io, format!("Failed to read {path}! {err:?}"))
+            .exit()
+    })
+}
 
-    let scale;
-    if let Some(sc) = cli.size.scale {
-        scale = sc;
-    } else if let Some(x_width) = cli.size.x_width {
-        let y_width = cli.size.y_width.unwrap_or(usize::MAX);
-        scale = grid.get_bound_scale((x_width as f32, y_width as f32), options);
-    } else if let Some(y_width) = cli.size.y_width {
-        scale = grid.get_bound_scale((f32::MAX, y_width as f32), options);
-    } else {
-        scale = 100.0;
-    }
-
-    grid.draw_grid_to_file(&file_name, scale, options).unwrap();
+//pattern files on top of the built in ones, in the same format as hex_decoder's pattern_files
+#[derive(Args)]
+struct RegistryArgs {
+    #[arg(long)]
+    pattern_file: Vec<String>,
 }
 
-impl Into<&GridOptions> for PatternOption {
-    fn into(self) -> &'static GridOptions {
-        match self {
-            PatternOption::UniformGradient => &defaults::UNIFORM_GRADIENT,
-            PatternOption::Gradient => &defaults::GRADIENT,
-            PatternOption::UniformPointGradient => &defaults::UNIFORM_POINT_GRADIENT,
-            PatternOption::PointGradient => &defaults::POINT_GRADIENT,
-            PatternOption::UniformSegment => &defaults::UNIFORM_SEGMENT,
-            PatternOption::Segment => &defaults::SEGMENT,
-            PatternOption::UniformMonocolor => &defaults::UNIFORM_MONOCOLOR,
-            PatternOption::Monocolor => &defaults::MONOCOLOR,
+impl RegistryArgs {
+    fn load(&self) -> PatternRegistry {
+        let mut registry = PatternRegistry::with_builtins();
+        for path in &self.pattern_file {
+            registry.load_file(path).unwrap_or_else(|err| {
+                Cli::command()
+                    .error(
+                        ErrorKind::Io,
+                        format!("Failed to load pattern file {path}! {err:?}"),
+                    )
+                    .exit()
+            });
         }
+        registry
     }
 }
 
@@ -172,7 +184,3 @@ fn not_zero(s: &str) -> Result<usize, String> {
         Err("Value can't be zero!".to_string())
     }
 }
-
-fn pattern_parser(s: &str) -> Result<PatternParseResults, String> {
-    Ok(parse_patterns::parse_str(s))
-}
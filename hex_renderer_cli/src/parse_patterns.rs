@@ -1,108 +1,143 @@
 use hex_renderer::{
-    self,
-    pattern_utils::{Angle, Direction},
+    parse::{self, Iota, ParsedIota},
     Pattern,
 };
-use pest::Parser;
-use pest_derive::Parser;
 
-#[derive(Parser)]
-#[grammar_inline = r#"
+use crate::json;
 
-WHITESPACE = _{" "}
-
-hex = {SOI ~ iota ~ ("," ~ iota)* ~ EOI}
-iota = _{pattern | number | vector | unknown | empty}
-
-empty = _{&"," | EOI}
-
-unknown = {(!"," ~ ANY)+}
+#[derive(Clone)]
+pub struct PatternParseResults {
+    pub valid: Vec<Pattern>,
+    pub invalid: Vec<String>,
+    pub iotas: Vec<ParsedIota>,
+}
 
-vector = {"(" ~ number ~ ("," ~ number){2} ~ ")"}
+//a part of the input that's handled on its own
+pub struct Section {
+    pub label: Option<String>,
+    pub text: String,
+}
 
-number = @{"-"? ~ digit+ ~ (("." ~ digit+) | !".")}
-digit = _{'0'..'9'}
+//reads json (see json::from_json) or anything hex_renderer's parser understands
+//  // starts a comment that goes to the end of the line
+//  iotas can be split over multiple lines instead of being separated by commas
+pub fn parse_str(patterns_str: &str) -> PatternParseResults {
+    //comments and line breaks are replaced by something the same length
+    //so the spans of errors still point at the right part of the input
+    let patterns_str = strip_comments(patterns_str);
 
-pattern = ${pattern1 | pattern2 | (direction ~ ((WHITESPACE+ ~ angles) | (WHITESPACE* ~ (&"," | EOI))))}
-pattern1 = _{"<" ~ WHITESPACE* ~ pattern12_inner ~ WHITESPACE* ~ ">"}
-pattern2 = _{^"HexPattern(" ~ WHITESPACE* ~ pattern12_inner ~ WHITESPACE* ~ ")"}
-pattern12_inner = _{direction ~ WHITESPACE* ~ angles? ~ (("," ~ WHITESPACE* ~ angles) | !",")}
+    //json is tried first since [...] is also how lists are written
+    let parsed = json::from_json(&patterns_str).map_or_else(
+        || parse::parse_iotas(&patterns_str.replace(['\n', '\r'], ",")),
+        Ok,
+    );
 
-direction = _{north_east | east | south_east | south_west | west | north_west}
-north_east = {^"north_east" | ^"northeast" | ^"ne"}
-east = {^"east" | ^"e"}
-south_east = {^"south_east" | ^"southeast" | ^"se"}
-south_west = {^"south_west" | ^"soutwest" | ^"sw"}
-west = {^"west" | ^"w"}
-north_west = {^"north_west" | ^"northwest" | ^"nw"}
+    match parsed {
+        Ok(iotas) => from_iotas(iotas),
+        Err(err) => PatternParseResults {
+            valid: vec![],
+            invalid: vec![format!(
+                "{} (at {}..{})",
+                err.message, err.span.start, err.span.end
+            )],
+            iotas: vec![],
+        },
+    }
+}
 
-angles = ${angle+}
-angle = _{w | e | d | s | a | q}
-w = {^"w"}
-e = {^"e"}
-d = {^"d"}
-s = {^"s"}
-a = {^"a"}
-q = {^"q"}
+//the results for iotas that have already been parsed
+pub fn from_iotas(iotas: Vec<ParsedIota>) -> PatternParseResults {
+    let mut results = PatternParseResults {
+        valid: vec![],
+        invalid: vec![],
+        iotas: vec![],
+    };
+    for iota in iotas.iter().cloned() {
+        add_iota(&mut results, iota);
+    }
+    results.iotas = iotas;
+    results
+}
 
-"#]
-struct PatternParser;
+//iotas that aren't patterns are drawn as chips, so only patterns are collected here
+fn add_iota(results: &mut PatternParseResults, iota: ParsedIota) {
+    match iota.iota {
+        Iota::Pattern(pattern) => results.valid.push(pattern),
+        Iota::List(list) => {
+            for iota in list {
+                add_iota(results, iota);
+            }
+        }
+        _ => (),
+    }
+}
 
-#[derive(Clone)]
-pub struct PatternParseResults {
-    pub valid: Vec<Pattern>,
-    pub invalid: Vec<String>,
+fn strip_comments(text: &str) -> String {
+    text.split_inclusive('\n')
+        .map(|line| {
+            //\" and \\ don't end a string
+            let mut in_string = false;
+            let mut escaped = false;
+            let comment = line.char_indices().find(|&(i, char)| {
+                if escaped {
+                    escaped = false;
+                } else if in_string && char == '\\' {
+                    escaped = true;
+                } else if char == '"' {
+                    in_string = !in_string;
+                }
+                !in_string && line[i..].starts_with("//")
+            });
+            match comment {
+                Some((start, _)) => {
+                    let end = line.trim_end_matches(['\n', '\r']).len();
+                    format!(
+                        "{}{}{}",
+                        &line[..start],
+                        " ".repeat(end - start),
+                        &line[end..]
+                    )
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect()
 }
 
-pub fn parse_str(patterns_str: &str) -> PatternParseResults {
-    let parsed_patterns = PatternParser::parse(Rule::hex, patterns_str)
-        .unwrap()
-        .next()
-        .unwrap();
+//splits the input up for batch mode
+//  a line starting with # starts a new section, with the rest of the line as its label
+//  without any of those, every line is a section of its own
+//empty sections (like ones that are only comments) are left out
+pub fn split_sections(text: &str) -> Vec<Section> {
+    let text = strip_comments(text);
+    let mut sections = Vec::new();
 
-    let mut errors = vec![];
-    let patterns = parsed_patterns
-        .into_inner()
-        .filter_map(|rule| {
-            if matches!(rule.as_rule(), Rule::pattern) {
-                let mut innards = rule.into_inner();
-                let direction = match innards.next().unwrap().as_rule() {
-                    Rule::north_east => Direction::NorthEast,
-                    Rule::east => Direction::East,
-                    Rule::south_east => Direction::SouthEast,
-                    Rule::south_west => Direction::SouthWest,
-                    Rule::west => Direction::West,
-                    Rule::north_west => Direction::NorthWest,
-                    _ => unreachable!(),
+    if text.lines().any(|line| line.trim_start().starts_with('#')) {
+        let mut current = Section {
+            label: None,
+            text: String::new(),
+        };
+        for line in text.lines() {
+            if let Some(label) = line.trim_start().strip_prefix('#') {
+                let label = label.trim();
+                let next = Section {
+                    label: (!label.is_empty()).then(|| label.to_string()),
+                    text: String::new(),
                 };
-                let angles: Vec<Angle> = if let Some(angles) = innards.next() {
-                    angles
-                        .into_inner()
-                        .map(|a| match a.as_rule() {
-                            Rule::w => Angle::Forward,
-                            Rule::e => Angle::Right,
-                            Rule::d => Angle::BackRight,
-                            Rule::s => Angle::Back,
-                            Rule::a => Angle::BackLeft,
-                            Rule::q => Angle::Left,
-                            _ => unreachable!(),
-                        })
-                        .collect()
-                } else {
-                    vec![]
-                };
-                Some(Pattern::new(direction, angles))
-            } else if !matches!(rule.as_rule(), Rule::EOI) && !matches!(rule.as_rule(), Rule::empty)
-            {
-                errors.push(rule.as_str().to_string());
-                None
+                sections.push(std::mem::replace(&mut current, next));
             } else {
-                None
+                current.text.push_str(line);
+                current.text.push('\n');
             }
-        })
-        .collect();
-    PatternParseResults {
-        valid: patterns,
-        invalid: errors,
+        }
+        sections.push(current);
+    } else {
+        sections.extend(text.lines().map(|line| Section {
+            label: None,
+            text: line.to_string(),
+        }));
     }
+
+    sections.retain(|section| !section.text.trim().is_empty());
+    sections
 }
@@ -0,0 +1,425 @@
+use std::{collections::HashSet, fs, io, path::Path};
+
+use clap::{error::ErrorKind, Args, CommandFactory, ValueEnum};
+use hex_decoder::PatternRegistry;
+use hex_renderer::{
+    defaults,
+    grids::{
+        BlockStyle, GridCreationError, GridDraw, GridFileError, GridItem, GridLabels, GridNode,
+        HexGrid, ImageFormat, NestedGrid, SquareGrid,
+    },
+    options::GridOptions,
+    Pattern,
+};
+
+use crate::{
+    colors::ColorArgs, greater_than_zero, not_zero, parse_patterns::PatternParseResults,
+    positive_float, Cli, PatternArgs, RegistryArgs,
+};
+
+//the file name that writes to stdout instead
+const STDOUT: &str = "-";
+
+#[derive(Args)]
+pub struct RenderArgs {
+    #[command(flatten)]
+    patterns: PatternArgs,
+
+    #[command(flatten)]
+    grid_type: GridArgs,
+
+    #[command(flatten)]
+    square_args: SquareArgs,
+
+    #[command(flatten)]
+    size: SizeArgs,
+
+    #[arg(long, value_parser = not_zero)]
+    max_width: Option<usize>,
+
+    /// The file to write to, or - for stdout
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Overrides the format the output's extension would pick (png if it doesn't have one)
+    #[arg(long, value_enum)]
+    format: Option<FormatOption>,
+
+    /// Draws every line (or # section) of the input to its own image, named after its index (or label)
+    #[arg(long)]
+    batch: bool,
+
+    #[arg(long, value_enum)]
+    option: Option<PatternOption>,
+
+    #[arg(long, conflicts_with = "option")]
+    theme: Option<String>,
+
+    #[command(flatten)]
+    colors: ColorArgs,
+
+    #[arg(long)]
+    save_theme: Option<String>,
+
+    /// Nested grids don't have room for labels
+    #[arg(long, value_enum, conflicts_with = "nested")]
+    labels: Option<LabelOption>,
+
+    #[command(flatten)]
+    registry: RegistryArgs,
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct GridArgs {
+    #[arg(long)]
+    hex: bool,
+    #[arg(long, group = "square_group")]
+    square: bool,
+    #[arg(long, group = "square_group")]
+    nested: bool,
+}
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum GridKind {
+    Hex,
+    Square,
+    Nested,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum PatternOption {
+    UniformGradient,
+    Gradient,
+    UniformPointGradient,
+    PointGradient,
+
+    UniformSegment,
+    Segment,
+
+    UniformMonocolor,
+    Monocolor,
+}
+
+#[derive(Args)]
+struct SizeArgs {
+    #[arg(long, group = "size", value_parser = not_zero)]
+    x_width: Option<usize>,
+    #[arg(long, group = "size", value_parser = not_zero)]
+    y_width: Option<usize>,
+    #[arg(long, conflicts_with = "size", value_parser = greater_than_zero)]
+    scale: Option<f32>,
+}
+
+#[derive(Args, Default)]
+#[group(requires = "square_group")]
+pub struct SquareArgs {
+    #[arg(long, value_parser = positive_float)]
+    x_padding: Option<f32>,
+    #[arg(long, value_parser = positive_float)]
+    y_padding: Option<f32>,
+    #[arg(long, value_parser = positive_float)]
+    max_scale: Option<f32>,
+    #[arg(long, value_enum, requires = "nested")]
+    block_style: Option<BlockStyleOption>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum LabelOption {
+    Names,
+    Indices,
+    None,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum FormatOption {
+    Png,
+    Svg,
+    Webp,
+    Jpeg,
+    Ppm,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum BlockStyleOption {
+    Boxes,
+    Brackets,
+}
+
+pub fn run(cli: RenderArgs) {
+    let options = if let Some(path) = &cli.theme {
+        GridOptions::load_theme(path).unwrap_or_else(|err| {
+            Cli::command()
+                .error(
+                    ErrorKind::ValueValidation,
+                    format!("Failed to load theme {path}! {err:?}"),
+                )
+                .exit()
+        })
+    } else {
+        cli.colors
+            .build(cli.option.unwrap_or(PatternOption::Segment))
+    };
+    let options = &cli.colors.apply(options);
+    let registry = &cli.registry.load();
+
+    if let Some(path) = &cli.save_theme {
+        options.save_theme(path).unwrap_or_else(|err| {
+            Cli::command()
+                .error(
+                    ErrorKind::Io,
+                    format!("Failed to save theme {path}! {err:?}"),
+                )
+                .exit()
+        });
+    }
+
+    let format = cli.format.map(ImageFormat::from);
+    let file_name = cli.output.clone().unwrap_or(format!(
+        "HexPatterns.{}",
+        format.unwrap_or_default().extension()
+    ));
+
+    //an output with an unknown extension is caught before anything is drawn
+    if format.is_none() {
+        if let Err(err) = ImageFormat::from_path(&file_name) {
+            Cli::command()
+                .error(
+                    ErrorKind::ValueValidation,
+                    format!("Can't write {file_name}! {err:?}"),
+                )
+                .exit();
+        }
+    }
+
+    if cli.batch && file_name == STDOUT {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "Batch mode can't write to stdout!",
+            )
+            .exit();
+    }
+
+    if cli.batch {
+        let path = Path::new(&file_name);
+        let stem = path.with_extension("");
+        let extension = path.extension().map_or(String::new(), |extension| {
+            format!(".{}", extension.to_string_lossy())
+        });
+        let mut used = HashSet::new();
+        for (i, (label, parsed)) in cli.patterns.get_sections().into_iter().enumerate() {
+            let mut name = label.map_or(i.to_string(), |label| to_file_name(&label));
+            //repeated labels (or ones that only differ in the characters to_file_name replaces)
+            //would otherwise overwrite each other's files
+            while !used.insert(name.clone()) {
+                name = format!("{name}_{i}");
+            }
+            draw(
+                &cli,
+                parsed,
+                options,
+                registry,
+                &format!("{}_{name}{extension}", stem.display()),
+                format,
+            );
+        }
+    } else {
+        let parsed = cli.patterns.get();
+        draw(&cli, parsed, options, registry, &file_name, format);
+    }
+}
+
+//keeps labels from turning into paths or names the file system won't accept
+fn to_file_name(label: &str) -> String {
+    label
+        .chars()
+        .map(|char| {
+            if char.is_alphanumeric() || char == '-' {
+                char
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn draw(
+    cli: &RenderArgs,
+    parsed: PatternParseResults,
+    options: &GridOptions,
+    registry: &PatternRegistry,
+    file_name: &str,
+    format: Option<ImageFormat>,
+) {
+    let grid = build_grid(
+        (&cli.grid_type).into(),
+        &parsed,
+        cli.max_width,
+        &cli.square_args,
+        cli.labels.unwrap_or(LabelOption::None),
+        registry,
+    )
+    .unwrap_or_else(|err| {
+        Cli::command()
+            .error(
+                ErrorKind::ValueValidation,
+                format!("Failed to create the grid! {err:?}"),
+            )
+            .exit()
+    });
+
+    let scale;
+    if let Some(sc) = cli.size.scale {
+        scale = sc;
+    } else if let Some(x_width) = cli.size.x_width {
+        let y_width = cli.size.y_width.unwrap_or(usize::MAX);
+        scale = grid.get_bound_scale((x_width as f32, y_width as f32), options);
+    } else if let Some(y_width) = cli.size.y_width {
+        scale = grid.get_bound_scale((f32::MAX, y_width as f32), options);
+    } else {
+        scale = 100.0;
+    }
+
+    match write_grid(grid.as_ref(), file_name, format, scale, options) {
+        //stdout was piped into something that stopped reading (like head)
+        Err(GridFileError::SaveError(err)) if err.kind() == io::ErrorKind::BrokenPipe => (),
+        Err(err) => Cli::command()
+            .error(
+                ErrorKind::Io,
+                format!("Failed to write {file_name}! {err:?}"),
+            )
+            .exit(),
+        Ok(()) => (),
+    }
+}
+
+//nested grids can't have labels, so callers have to turn those down before getting here
+//(registry is what names are looked up in for LabelOption::Names)
+pub fn build_grid(
+    kind: GridKind,
+    parsed: &PatternParseResults,
+    max_width: Option<usize>,
+    square_args: &SquareArgs,
+    labels: LabelOption,
+    registry: &PatternRegistry,
+) -> Result<Box<dyn GridDraw>, GridCreationError> {
+    debug_assert!(kind != GridKind::Nested || labels == LabelOption::None);
+    let labels = get_labels(labels, &parsed.valid, registry);
+    let items = GridItem::from_iotas(&parsed.iotas);
+    let args = square_args;
+
+    Ok(match kind {
+        GridKind::Square => Box::new(SquareGrid::new_items(
+            items,
+            max_width.unwrap_or(20),
+            args.max_scale.unwrap_or(0.4),
+            args.x_padding.unwrap_or(0.2),
+            args.y_padding.unwrap_or(0.1),
+            labels,
+        )?),
+        GridKind::Nested => {
+            let nodes = GridNode::from_iotas(
+                &parsed.iotas,
+                &defaults::INTRO_ANGLES,
+                &defaults::RETRO_ANGLES,
+            );
+            Box::new(NestedGrid::new(
+                nodes,
+                max_width.unwrap_or(20),
+                args.max_scale.unwrap_or(0.4),
+                args.x_padding.unwrap_or(0.2),
+                args.y_padding.unwrap_or(0.3),
+                args.block_style.unwrap_or(BlockStyleOption::Boxes).into(),
+            )?)
+        }
+        GridKind::Hex => Box::new(HexGrid::new_items(items, max_width.unwrap_or(50), labels)?),
+    })
+}
+
+//the format is picked from the file name when it isn't given (see ImageFormat::from_path)
+pub fn write_grid(
+    grid: &dyn GridDraw,
+    file_name: &str,
+    format: Option<ImageFormat>,
+    scale: f32,
+    options: &GridOptions,
+) -> Result<(), GridFileError> {
+    let format = match format {
+        Some(format) => format,
+        None => ImageFormat::from_path(file_name)?,
+    };
+    if file_name == STDOUT {
+        grid.draw_grid_to_writer(&mut io::stdout().lock(), format, scale, options)
+    } else {
+        grid.draw_grid_as(format, scale, options)
+            .map_err(GridFileError::DrawError)
+            .and_then(|data| fs::write(file_name, data).map_err(GridFileError::SaveError))
+    }
+}
+
+impl From<&GridArgs> for GridKind {
+    fn from(value: &GridArgs) -> Self {
+        if value.square {
+            GridKind::Square
+        } else if value.nested {
+            GridKind::Nested
+        } else {
+            GridKind::Hex
+        }
+    }
+}
+
+impl From<PatternOption> for &GridOptions {
+    fn from(value: PatternOption) -> Self {
+        match value {
+            PatternOption::UniformGradient => &defaults::UNIFORM_GRADIENT,
+            PatternOption::Gradient => &defaults::GRADIENT,
+            PatternOption::UniformPointGradient => &defaults::UNIFORM_POINT_GRADIENT,
+            PatternOption::PointGradient => &defaults::POINT_GRADIENT,
+            PatternOption::UniformSegment => &defaults::UNIFORM_SEGMENT,
+            PatternOption::Segment => &defaults::SEGMENT,
+            PatternOption::UniformMonocolor => &defaults::UNIFORM_MONOCOLOR,
+            PatternOption::Monocolor => &defaults::MONOCOLOR,
+        }
+    }
+}
+
+//unknown patterns are labeled with their angles
+fn get_labels(option: LabelOption, patterns: &[Pattern], registry: &PatternRegistry) -> GridLabels {
+    match option {
+        LabelOption::Names => GridLabels::new(
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Some(
+                        hex_decoder::name_pattern(pattern, registry)
+                            .unwrap_or_else(|| pattern.get_angle_sig()),
+                    )
+                })
+                .collect(),
+        ),
+        LabelOption::Indices => GridLabels::indices(patterns.len()),
+        LabelOption::None => GridLabels::default(),
+    }
+}
+
+impl From<FormatOption> for ImageFormat {
+    fn from(value: FormatOption) -> Self {
+        match value {
+            FormatOption::Png => ImageFormat::Png,
+            FormatOption::Svg => ImageFormat::Svg,
+            FormatOption::Webp => ImageFormat::Webp,
+            FormatOption::Jpeg => ImageFormat::Jpeg,
+            FormatOption::Ppm => ImageFormat::Ppm,
+        }
+    }
+}
+
+impl From<BlockStyleOption> for BlockStyle {
+    fn from(value: BlockStyleOption) -> Self {
+        match value {
+            BlockStyleOption::Boxes => BlockStyle::Boxes,
+            BlockStyleOption::Brackets => BlockStyle::Brackets,
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use std::{
+    fs,
+    io::{self, IsTerminal, Write},
+};
+
+use clap::{error::ErrorKind, Args, CommandFactory, ValueEnum};
+use hex_decoder::{assemble, disassemble, name_pattern, PatternRegistry};
+use hex_renderer::{
+    options::GridOptions,
+    parse::{Iota, ParsedIota},
+};
+
+use crate::{
+    convert::{self, Notation},
+    parse_patterns::{self, PatternParseResults},
+    render::{self, GridKind, LabelOption, PatternOption, SquareArgs},
+    Cli, RegistryArgs,
+};
+
+const HELP: &str = "\
+add <patterns>             adds patterns (in any notation the other commands read) to the end
+insert <index> <patterns>  adds patterns before the one at index
+set <index> <patterns>     replaces the pattern at index
+del <index>                removes the pattern at index
+encode <name>              adds a pattern by its name, like a line of decode's output
+clear                      removes every pattern
+load <file>                replaces the patterns with the ones in the file
+list                       shows each pattern's index and name
+decode                     names the patterns the same way the decode command does
+export [notation]          writes the patterns as hex (the default), angles, bare or json
+render <file> [scale]      draws the patterns, in the format the file's extension picks
+theme <option | file>      draws with one of render's --option values or a theme file
+grid <hex | square | nested>
+labels <names | indices | none>
+help
+quit
+
+Anything else is added as patterns, so a spell can be pasted in as is.
+Pasting a single list while there aren't any patterns adds what's in the list instead.";
+
+#[derive(Args)]
+pub struct ReplArgs {
+    /// A file with the patterns to start with
+    #[arg(long)]
+    input: Option<String>,
+
+    #[command(flatten)]
+    registry: RegistryArgs,
+}
+
+//the working list of patterns and how they're drawn
+struct Repl {
+    iotas: Vec<ParsedIota>,
+    registry: PatternRegistry,
+    options: GridOptions,
+    grid: GridKind,
+    labels: LabelOption,
+}
+
+enum Outcome {
+    Edited,
+    Unchanged,
+    Quit,
+}
+
+pub fn run(args: ReplArgs) {
+    let mut repl = Repl {
+        iotas: Vec::new(),
+        registry: args.registry.load(),
+        options: <&GridOptions>::from(PatternOption::Segment).clone(),
+        grid: GridKind::Hex,
+        labels: LabelOption::None,
+    };
+    if let Some(path) = &args.input {
+        repl.load(path)
+            .unwrap_or_else(|err| Cli::command().error(ErrorKind::Io, err).exit());
+        repl.print_list();
+    }
+
+    //the prompt is left out when commands are piped in
+    let prompt = io::stdin().is_terminal();
+    if prompt {
+        println!("Type help for a list of commands");
+    }
+    let mut lines = io::stdin().lines();
+    loop {
+        if prompt {
+            print!("> ");
+            io::stdout().flush().unwrap();
+        }
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line.unwrap_or_else(|err| {
+            Cli::command()
+                .error(ErrorKind::Io, format!("Failed to read stdin! {err:?}"))
+                .exit()
+        });
+
+        match repl.run_command(line.trim()) {
+            Ok(Outcome::Edited) => repl.print_list(),
+            Ok(Outcome::Unchanged) => (),
+            Ok(Outcome::Quit) => break,
+            Err(err) => eprintln!("{err}"),
+        }
+    }
+}
+
+impl Repl {
+    fn run_command(&mut self, line: &str) -> Result<Outcome, String> {
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        match command {
+            "" => return Ok(Outcome::Unchanged),
+            "add" => {
+                let iotas = self.parse(rest)?;
+                self.iotas.extend(iotas);
+            }
+            "insert" => {
+                let (index, rest) = split_index(rest)?;
+                let index = self.index(index, true)?;
+                let iotas = self.parse(rest)?;
+                self.iotas.splice(index..index, iotas);
+            }
+            "set" => {
+                let (index, rest) = split_index(rest)?;
+                let index = self.index(index, false)?;
+                let iotas = parse(rest)?;
+                self.iotas.splice(index..=index, iotas);
+            }
+            "del" => {
+                let index = self.index(rest, false)?;
+                self.iotas.remove(index);
+            }
+            "encode" => {
+                let iotas = assemble(rest, &self.registry)
+                    .map_err(|err| format!("Failed to encode! {err:?}"))?;
+                self.iotas.extend(
+                    iotas
+                        .into_iter()
+                        .map(|iota| ParsedIota { iota, span: 0..0 }),
+                );
+            }
+            "clear" => self.iotas.clear(),
+            "load" => self.load(rest)?,
+            "list" => {
+                self.print_list();
+                return Ok(Outcome::Unchanged);
+            }
+            "decode" => print!("{}", disassemble(&self.iotas, &self.registry)),
+            "export" => {
+                let notation = if rest.is_empty() {
+                    Notation::Hex
+                } else {
+                    Notation::from_str(rest, true)?
+                };
+                println!("{}", convert::write_iotas(&self.iotas, notation));
+            }
+            "render" => self.render(rest)?,
+            "theme" => {
+                self.options = match PatternOption::from_str(rest, true) {
+                    Ok(option) => <&GridOptions>::from(option).clone(),
+                    Err(_) => GridOptions::load_theme(rest)
+                        .map_err(|err| format!("Failed to load theme {rest}! {err:?}"))?,
+                };
+            }
+            "grid" => self.grid = GridKind::from_str(rest, true)?,
+            "labels" => self.labels = LabelOption::from_str(rest, true)?,
+            "help" => println!("{HELP}"),
+            "quit" | "exit" => return Ok(Outcome::Quit),
+            //a mistyped command would otherwise be added as an unknown iota
+            _ => {
+                let iotas = self.parse(line)?;
+                if iotas
+                    .iter()
+                    .all(|iota| matches!(iota.iota, Iota::Unknown(_)))
+                {
+                    return Err(format!(
+                        "`{command}` isn't a command! Type help for a list of commands"
+                    ));
+                }
+                self.iotas.extend(iotas);
+            }
+        }
+
+        Ok(match command {
+            "decode" | "export" | "render" | "theme" | "grid" | "labels" | "help" => {
+                Outcome::Unchanged
+            }
+            _ => Outcome::Edited,
+        })
+    }
+
+    //a spell copied out of the game is a single list, so that's unwrapped when starting over
+    fn parse(&self, text: &str) -> Result<Vec<ParsedIota>, String> {
+        let iotas = parse(text)?;
+        if self.iotas.is_empty() {
+            Ok(unwrap_spell(iotas))
+        } else {
+            Ok(iotas)
+        }
+    }
+
+    fn load(&mut self, path: &str) -> Result<(), String> {
+        let text =
+            fs::read_to_string(path).map_err(|err| format!("Failed to read {path}! {err:?}"))?;
+        self.iotas = unwrap_spell(parse(&text)?);
+        Ok(())
+    }
+
+    //the index has to point at a pattern, unless it's for adding after the last one
+    fn index(&self, text: &str, allow_end: bool) -> Result<usize, String> {
+        let len = self.iotas.len() + usize::from(allow_end);
+        match text.parse::<usize>() {
+            Ok(index) if index < len => Ok(index),
+            Ok(index) => Err(format!(
+                "{index} is out of range! There are {} patterns",
+                self.iotas.len()
+            )),
+            Err(_) => Err(format!("`{text}` isn't an index!")),
+        }
+    }
+
+    fn render(&self, args: &str) -> Result<(), String> {
+        let (file_name, scale) = match args.split_once(char::is_whitespace) {
+            Some((file_name, scale)) => (file_name, crate::greater_than_zero(scale.trim())?),
+            None if args.is_empty() => return Err("render needs a file to write to!".to_string()),
+            None => (args, 100.0),
+        };
+
+        if self.grid == GridKind::Nested && self.labels != LabelOption::None {
+            return Err(
+                "Nested grids can't have labels! Switch to labels none or another grid".to_string(),
+            );
+        }
+
+        let parsed = parse_patterns::from_iotas(self.iotas.clone());
+        let grid = render::build_grid(
+            self.grid,
+            &parsed,
+            None,
+            &SquareArgs::default(),
+            self.labels,
+            &self.registry,
+        )
+        .map_err(|err| format!("Failed to create the grid! {err:?}"))?;
+        render::write_grid(grid.as_ref(), file_name, None, scale, &self.options)
+            .map_err(|err| format!("Failed to write {file_name}! {err:?}"))?;
+        println!("Wrote {file_name}");
+        Ok(())
+    }
+
+    //each pattern's index next to its name (or the pattern itself when it isn't known)
+    fn print_list(&self) {
+        if self.iotas.is_empty() {
+            println!("(no patterns)");
+        }
+        for (i, iota) in self.iotas.iter().enumerate() {
+            println!("{i:>3}  {}", self.name(&iota.iota));
+        }
+    }
+
+    fn name(&self, iota: &Iota) -> String {
+        match iota {
+            Iota::Pattern(pattern) => {
+                name_pattern(pattern, &self.registry).unwrap_or_else(|| pattern.to_string())
+            }
+            Iota::List(list) => {
+                let names: Vec<String> = list.iter().map(|iota| self.name(&iota.iota)).collect();
+                format!("[{}]", names.join(", "))
+            }
+            other => other.to_string(),
+        }
+    }
+}
+
+fn parse(text: &str) -> Result<Vec<ParsedIota>, String> {
+    let PatternParseResults { invalid, iotas, .. } = parse_patterns::parse_str(text);
+    if !invalid.is_empty() {
+        return Err(format!("Invalid patterns! {invalid:?}"));
+    }
+    if iotas.is_empty() {
+        return Err("There aren't any patterns to add!".to_string());
+    }
+    Ok(iotas)
+}
+
+fn unwrap_spell(iotas: Vec<ParsedIota>) -> Vec<ParsedIota> {
+    match &iotas[..] {
+        [ParsedIota {
+            iota: Iota::List(list),
+            ..
+        }] => list.clone(),
+        _ => iotas,
+    }
+}
+
+fn split_index(text: &str) -> Result<(&str, &str), String> {
+    text.split_once(char::is_whitespace)
+        .map(|(index, rest)| (index, rest.trim()))
+        .ok_or_else(|| "Expected an index followed by patterns!".to_string())
+}